@@ -5,135 +5,72 @@
 // Usage:
 //     cargo run --example plot_iv_smile -- <csv_path> <EXP_STRING>
 //
-// The CSV must contain the same columns expected by test_utils::load_test_data.
+// The CSV is read with surface_lib::io::SnapshotReader (default snapshot columns).
 // The output image will be written to iv_smile.png in the working directory.
 
 use std::env;
 use std::error::Error;
 
-use csv::ReaderBuilder;
 use plotters::prelude::*;
+use surface_lib::io::{ExpiryDate, OptionQuote, SnapshotReader};
 use surface_lib::models::svi::svi_model::SVISlice;
 use surface_lib::{
     calibrate_svi, default_configs, price_with_svi, CalibrationParams, FixedParameters,
     MarketDataRow, SVIParams, SviModelParams,
 };
 
-// ---------------------------------------------------------------------------
-// CSV deserialization helpers
-// ---------------------------------------------------------------------------
-
-#[derive(serde::Deserialize, Clone)]
-struct CsvRow {
-    #[serde(rename = "symbol")]
-    symbol: String,
-    #[serde(rename = "option_type")]
-    option_type: String,
-    #[serde(rename = "strike_price")]
-    strike_price: f64,
-    #[serde(rename = "underlying_price")]
-    underlying_price: f64,
-    #[serde(rename = "years_to_exp")]
-    years_to_exp: f64,
-    #[serde(rename = "mark_iv")]
-    market_iv: f64,
-    #[serde(rename = "bid_iv")]
-    bid_iv: Option<f64>,
-    #[serde(rename = "ask_iv")]
-    ask_iv: Option<f64>,
-    vega: f64,
-    #[serde(rename = "expiration_ts")]
-    expiration: i64,
-}
-
-impl From<CsvRow> for MarketDataRow {
-    fn from(r: CsvRow) -> Self {
-        MarketDataRow {
-            option_type: r.option_type,
-            strike_price: r.strike_price,
-            underlying_price: r.underlying_price,
-            years_to_exp: r.years_to_exp,
-            market_iv: if r.market_iv > 1.0 {
-                r.market_iv / 100.0
-            } else {
-                r.market_iv
-            },
-            vega: if r.vega > 0.0 { r.vega } else { 1.0 },
-            expiration: r.expiration,
-        }
-    }
-}
-
-fn load_csv(path: &str) -> Result<Vec<CsvRow>, Box<dyn Error>> {
-    let mut rdr = ReaderBuilder::new().has_headers(true).from_path(path)?;
-    let mut rows = Vec::new();
-    for result in rdr.deserialize() {
-        let row: CsvRow = result?;
-        rows.push(row);
-    }
-    Ok(rows)
-}
-
-fn filter_by_expiration_string(data: Vec<CsvRow>, target_exp: &str) -> Vec<CsvRow> {
-    // Try instrument name filtering first
-    if let Some(first_row) = data.first() {
-        let inst_name = &first_row.symbol;
-        println!("Sample symbol: {}", inst_name);
+fn filter_by_expiration_string(
+    data: Vec<OptionQuote>,
+    target_exp: &str,
+) -> Result<Vec<OptionQuote>, Box<dyn Error>> {
+    let target: ExpiryDate = target_exp.parse()?;
+    let (filtered, others): (Vec<_>, Vec<_>) = data
+        .into_iter()
+        .partition(|q| q.parsed_symbol().map(|p| p.expiry) == Some(target));
 
-        let token = format!("-{}-", target_exp.to_uppercase());
-        let filtered: Vec<_> = data
-            .iter()
-            .filter(|row| row.symbol.to_uppercase().contains(&token))
-            .cloned()
-            .collect();
-
-        if !filtered.is_empty() {
-            println!(
-                "Filtered {} options by symbol containing '{}'",
-                filtered.len(),
-                target_exp
-            );
-            return filtered;
-        }
+    if !filtered.is_empty() {
+        println!(
+            "Filtered {} options by symbol expiry '{}'",
+            filtered.len(),
+            target_exp
+        );
+        return Ok(filtered);
     }
 
-    // Fallback: if no instrument name or no matches, show available expirations
+    // No match: show available expirations
     println!(
         "No instrument name matches found for '{}'. Available options:",
         target_exp
     );
 
-    use std::collections::HashMap;
-    let mut exp_data: HashMap<i64, (usize, f64)> = HashMap::new(); // timestamp -> (count, avg_days)
-
-    for row in &data {
-        let entry = exp_data.entry(row.expiration).or_insert((0, 0.0));
-        entry.0 += 1; // count
-        entry.1 += row.years_to_exp * 365.0; // sum of days
+    use std::collections::BTreeMap;
+    let mut exp_data: BTreeMap<i64, (usize, String)> = BTreeMap::new();
+    for q in &others {
+        let entry = exp_data
+            .entry(q.row.expiration)
+            .or_insert((0, String::new()));
+        entry.0 += 1;
+        if let Some(parsed) = q.parsed_symbol() {
+            entry.1 = parsed.expiry.code();
+        }
     }
-
-    for (&timestamp, &(count, days_sum)) in &exp_data {
-        let avg_days = days_sum / count as f64;
-        println!(
-            "  {}: {} options (~{:.1} days to expiration)",
-            timestamp, count, avg_days
-        );
+    for (timestamp, (count, code)) in exp_data {
+        println!("  {} ({}): {} options", code, timestamp, count);
     }
 
-    // Return empty if no match found
-    Vec::new()
+    Ok(Vec::new())
 }
 
 fn filter_otm_and_moneyness(
-    csv_rows: Vec<CsvRow>,
+    quotes: Vec<OptionQuote>,
     moneyness_min: f64,
     moneyness_max: f64,
-) -> Vec<CsvRow> {
-    csv_rows
+) -> Vec<OptionQuote> {
+    quotes
         .into_iter()
-        .filter(|row| {
-            let underlying = row.underlying_price;
-            let strike = row.strike_price;
+        .filter(|q| {
+            let underlying = q.row.underlying_price;
+            let strike = q.row.strike_price;
             let moneyness = strike / underlying;
 
             // Check moneyness range
@@ -142,7 +79,7 @@ fn filter_otm_and_moneyness(
             }
 
             // Check OTM condition
-            match row.option_type.as_str() {
+            match q.row.option_type.as_str() {
                 "call" => strike > underlying, // OTM calls: strike > spot
                 "put" => strike < underlying,  // OTM puts: strike < spot
                 _ => false,
@@ -163,8 +100,8 @@ fn main() -> Result<(), Box<dyn Error>> {
     let csv_path = &args[1];
     let expiration_str = &args[2];
 
-    let mut csv_rows = load_csv(csv_path)?;
-    csv_rows = filter_by_expiration_string(csv_rows, expiration_str);
+    let mut csv_rows = SnapshotReader::new().read_path(csv_path)?;
+    csv_rows = filter_by_expiration_string(csv_rows, expiration_str)?;
 
     println!("Loaded {} options after expiry filtering", csv_rows.len());
 
@@ -183,7 +120,7 @@ fn main() -> Result<(), Box<dyn Error>> {
     if csv_rows.is_empty() {
         return Err("No data after OTM and moneyness filtering".into());
     }
-    let data: Vec<MarketDataRow> = csv_rows.iter().cloned().map(|r| r.clone().into()).collect();
+    let data: Vec<MarketDataRow> = csv_rows.iter().map(|q| q.row.clone()).collect();
 
     // Calibrate SVI
     let mut config = default_configs::fast();
@@ -225,7 +162,7 @@ fn main() -> Result<(), Box<dyn Error>> {
     )?;
 
     // Price with calibrated parameters
-    let fixed = FixedParameters { r: 0.0, q: 0.0 };
+    let fixed = FixedParameters::flat(0.0, 0.0);
     let priced = price_with_svi(svi_params.clone(), data.clone(), fixed);
 
     // Print debug table
@@ -252,8 +189,8 @@ fn main() -> Result<(), Box<dyn Error>> {
         let _model_iv_pct = pr.model_iv * 100.0;
 
         if let (Some(bid), Some(ask)) = (csv.bid_iv, csv.ask_iv) {
-            if bid > 0.0 && ask > bid {
-                error_bars.push((row.strike_price, bid, ask));
+            if ask > bid {
+                error_bars.push((row.strike_price, bid * 100.0, ask * 100.0));
             }
         }
 
@@ -61,10 +61,8 @@ fn main() -> Result<()> {
     };
 
     // Define fixed parameters
-    let fixed_params = FixedParameters {
-        r: 0.02, // 2% risk-free rate
-        q: 0.0,  // No dividend yield
-    };
+    // 2% risk-free rate, no dividend yield
+    let fixed_params = FixedParameters::flat(0.02, 0.0);
 
     // Price all options
     let pricing_results = price_with_svi(svi_params, market_data, fixed_params);
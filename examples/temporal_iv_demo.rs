@@ -170,6 +170,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         allow_short_extrapolate: true,                 // Enable 1d and 3d extrapolation
         allow_long_extrapolate: true,                  // Enable 45d and 60d extrapolation
         min_maturities: 2,
+        ..Default::default()
     };
 
     println!("Temporal Configuration:");
@@ -232,6 +233,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             allow_short_extrapolate: true,
             allow_long_extrapolate: true,
             min_maturities: 2,
+            ..Default::default()
         };
 
         let comparison_metrics =
@@ -0,0 +1,753 @@
+//! `surface` command-line tool
+//!
+//! Runs the library's calibration, pricing and metrics pipelines on option chain
+//! snapshots without writing Rust:
+//!
+//! ```text
+//! surface --preset fast calibrate --data chain.csv --output surface.json
+//! surface price --surface surface.json --contracts contracts.csv
+//! surface metrics --data chain.csv --days 7,30,90
+//! surface check --surface surface.json
+//! surface --config profile.toml plot --data chain.csv --expiry 10JAN25 --output smile.svg
+//! ```
+//!
+//! The optimizer comes from `--config` (a TOML profile), `--preset`, or the
+//! `[optimizer]` section of a `--job` spec, in that order of precedence. A job spec also
+//! supplies the data path, expiries, bounds, model parameters and fixed tenors; flags
+//! given on the command line override it.
+
+use anyhow::{anyhow, Context, Result};
+use clap::{Args, Parser, Subcommand};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use surface_lib::io::{ExpiryDate, OptionQuote, SnapshotReader};
+use surface_lib::models::traits::SurfaceModel;
+use surface_lib::models::utils::price_option;
+use surface_lib::plot::{quote_smile_svg, PlotOptions};
+use surface_lib::snapshot::SurfaceRecord;
+use surface_lib::{
+    build_fixed_time_metrics_from_market_data, build_linear_iv, calibrate_svi_surface,
+    implied_event_moves, load_profile, DeltaConvention, EventMove, FixedParameters,
+    FixedTimeMetrics, ForwardCurve, JobSpec, LinearIvConfig, LinearIvOutput, MarketDataRow,
+    OptimizationConfig, Preset, PricingResult, RateCurve, SurfaceCalibrationOptions,
+    TemporalConfig, Tenor, VarianceClock,
+};
+
+#[derive(Parser)]
+#[command(
+    name = "surface",
+    version,
+    about = "Volatility surface calibration, pricing and metrics"
+)]
+struct Cli {
+    #[command(flatten)]
+    settings: SettingsArgs,
+    #[command(subcommand)]
+    command: Command,
+}
+
+/// Options shared by every subcommand
+#[derive(Args)]
+struct SettingsArgs {
+    /// Calibration job spec (TOML)
+    #[arg(long, global = true, value_name = "FILE")]
+    job: Option<PathBuf>,
+    /// Optimizer profile (TOML); overrides the job's [optimizer]
+    #[arg(long, global = true, value_name = "FILE", conflicts_with = "preset")]
+    config: Option<PathBuf>,
+    /// Optimizer preset: production, fast, research or minimal
+    #[arg(long, global = true)]
+    preset: Option<Preset>,
+    /// CMA-ES seed, for reproducible calibrations
+    #[arg(long, global = true)]
+    seed: Option<u64>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Calibrate an SVI surface to an option chain and write it as JSON
+    Calibrate(CalibrateArgs),
+    /// Price contracts with a calibrated surface
+    Price(PriceArgs),
+    /// Linear IV ATM/RR/BF per expiry and fixed-tenor metrics
+    Metrics(MetricsArgs),
+    /// Check a calibrated surface for butterfly and calendar arbitrage
+    Check(CheckArgs),
+    /// Plot market against model implied volatility for one expiry as SVG
+    Plot(PlotArgs),
+}
+
+/// Option chain selection
+#[derive(Args)]
+struct DataArgs {
+    /// Option chain snapshot CSV (defaults to the job's [data] path)
+    #[arg(long, value_name = "CSV")]
+    data: Option<PathBuf>,
+    /// Expiries to use, as Unix timestamps or symbol expiry dates such as 10JAN25,
+    /// 250110 or 2025-01-10 (defaults to the job's expirations, or all)
+    #[arg(long = "expiry", value_name = "EXPIRY", value_delimiter = ',')]
+    expiries: Vec<String>,
+}
+
+#[derive(Args)]
+struct CalibrateArgs {
+    #[command(flatten)]
+    data: DataArgs,
+    /// Expiries with fewer quotes are reported as failures
+    #[arg(long)]
+    min_points: Option<usize>,
+    /// Maximum number of slices calibrated concurrently
+    #[arg(long)]
+    threads: Option<usize>,
+    /// Calibrate every slice from scratch instead of warm-starting from its neighbour
+    #[arg(long)]
+    no_warm_start: bool,
+    /// Output file (default: stdout)
+    #[arg(long, short)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct PriceArgs {
+    /// Surface JSON written by `surface calibrate`
+    #[arg(long, value_name = "JSON")]
+    surface: PathBuf,
+    /// Contracts CSV with option_type, strike_price, underlying_price and years_to_exp columns
+    #[arg(long, value_name = "CSV")]
+    contracts: PathBuf,
+    /// Risk-free rate (default: the optimizer's fixed_params.r)
+    #[arg(long, allow_negative_numbers = true)]
+    rate: Option<f64>,
+    /// Dividend yield (default: the optimizer's fixed_params.q)
+    #[arg(long, allow_negative_numbers = true)]
+    div: Option<f64>,
+    /// Output CSV (default: stdout)
+    #[arg(long, short)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct MetricsArgs {
+    #[command(flatten)]
+    data: DataArgs,
+    /// Deltas to interpolate (default: -0.25,-0.1,0.1,0.25)
+    #[arg(
+        long,
+        value_delimiter = ',',
+        allow_hyphen_values = true,
+        value_name = "DELTAS"
+    )]
+    deltas: Vec<f64>,
+    /// Fixed tenors in days (default: the job's [temporal], or 1,7,14,30,60,90,180,365)
+    #[arg(long, value_delimiter = ',', value_name = "DAYS")]
+    days: Vec<i32>,
+    /// Extra tenors such as 30m, 4h or 2025-03-28T08:00:00Z (default: the job's [temporal])
+    #[arg(long, value_delimiter = ',', value_name = "TENORS")]
+    tenors: Vec<Tenor>,
+    /// Log-moneyness points ln(K/F) of the fixed-tenor smiles (default: the job's [temporal])
+    #[arg(
+        long,
+        value_delimiter = ',',
+        allow_hyphen_values = true,
+        value_name = "X"
+    )]
+    moneyness_grid: Vec<f64>,
+    /// Interpolate in business time, weighting Saturday and Sunday by this amount
+    #[arg(long, value_name = "WEIGHT")]
+    weekend_weight: Option<f64>,
+    /// Risk-free rate used for deltas
+    #[arg(long, allow_negative_numbers = true)]
+    rate: Option<f64>,
+    /// Dividend yield used for deltas
+    #[arg(long, allow_negative_numbers = true)]
+    div: Option<f64>,
+    /// Delta convention: spot, forward, premium_adjusted_spot or premium_adjusted_forward
+    #[arg(long, default_value_t = DeltaConvention::Spot)]
+    delta_convention: DeltaConvention,
+    /// Output file (default: stdout)
+    #[arg(long, short)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct CheckArgs {
+    /// Surface JSON written by `surface calibrate`
+    #[arg(long, value_name = "JSON")]
+    surface: PathBuf,
+    /// Lower end of the log-moneyness grid
+    #[arg(long, default_value_t = -1.0, allow_negative_numbers = true)]
+    k_min: f64,
+    /// Upper end of the log-moneyness grid
+    #[arg(long, default_value_t = 1.0, allow_negative_numbers = true)]
+    k_max: f64,
+    /// Number of grid points
+    #[arg(long, default_value_t = 81)]
+    points: usize,
+    /// Output file (default: stdout)
+    #[arg(long, short)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct PlotArgs {
+    /// Option chain snapshot CSV (defaults to the job's [data] path)
+    #[arg(long, value_name = "CSV")]
+    data: Option<PathBuf>,
+    /// Expiry to plot, as a Unix timestamp or a symbol expiry date such as 10JAN25
+    #[arg(long)]
+    expiry: String,
+    /// Surface JSON to plot (default: calibrate the expiry)
+    #[arg(long, value_name = "JSON")]
+    surface: Option<PathBuf>,
+    /// Output SVG
+    #[arg(long, short, default_value = "smile.svg")]
+    output: PathBuf,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = Settings::resolve(&cli.settings).and_then(|settings| match cli.command {
+        Command::Calibrate(args) => calibrate(&settings, args),
+        Command::Price(args) => price(&settings, args),
+        Command::Metrics(args) => metrics(&settings, args),
+        Command::Check(args) => check(args),
+        Command::Plot(args) => plot(&settings, args),
+    });
+    match result {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("error: {e:#}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Settings
+// ---------------------------------------------------------------------------
+
+/// Optimizer and job settings after applying the command-line overrides
+struct Settings {
+    config: OptimizationConfig,
+    job: Option<JobSpec>,
+}
+
+impl Settings {
+    fn resolve(args: &SettingsArgs) -> Result<Self> {
+        let job = args.job.as_ref().map(JobSpec::from_file).transpose()?;
+        let mut config = if let Some(path) = &args.config {
+            load_profile(path)?
+        } else if let Some(preset) = args.preset {
+            preset.config()
+        } else if let Some(job) = &job {
+            job.optimizer.clone()
+        } else {
+            OptimizationConfig::default()
+        };
+        if args.seed.is_some() {
+            config.cmaes.seed = args.seed;
+        }
+        Ok(Self { config, job })
+    }
+
+    /// Load the quotes selected by `args`, falling back to the job's `[data]` section
+    fn load_chain(&self, args: &DataArgs) -> Result<Vec<OptionQuote>> {
+        let path = match (&args.data, &self.job) {
+            (Some(path), _) => path.clone(),
+            (None, Some(job)) => job.data.path.clone(),
+            (None, None) => {
+                return Err(anyhow!(
+                    "no option chain given: pass --data or a --job with a [data] section"
+                ))
+            }
+        };
+        let quotes = load_chain(&path)?;
+
+        let expiries: Vec<String> = if !args.expiries.is_empty() {
+            args.expiries.clone()
+        } else if let Some(job) = &self.job {
+            job.data.expirations.iter().map(i64::to_string).collect()
+        } else {
+            Vec::new()
+        };
+        select_expiries(quotes, &expiries)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Option chain loading
+// ---------------------------------------------------------------------------
+
+/// Load a snapshot CSV with the default [`SnapshotReader`] columns.
+///
+/// Quotes of one expiry are given their mean `years_to_exp`, so that the expiry forms a
+/// single maturity for the term-structure metrics even when the file has no snapshot time.
+fn load_chain(path: &Path) -> Result<Vec<OptionQuote>> {
+    let mut quotes = SnapshotReader::new().read_path(path)?;
+    if quotes.is_empty() {
+        return Err(anyhow!("no quotes in {}", path.display()));
+    }
+
+    let mut maturities: BTreeMap<i64, (f64, usize)> = BTreeMap::new();
+    for quote in &quotes {
+        let entry = maturities.entry(quote.row.expiration).or_default();
+        entry.0 += quote.row.years_to_exp;
+        entry.1 += 1;
+    }
+    for quote in &mut quotes {
+        let (sum, n) = maturities[&quote.row.expiration];
+        quote.row.years_to_exp = sum / n as f64;
+    }
+    Ok(quotes)
+}
+
+/// Keep the quotes of the requested expiries (all when `expiries` is empty)
+fn select_expiries(quotes: Vec<OptionQuote>, expiries: &[String]) -> Result<Vec<OptionQuote>> {
+    if expiries.is_empty() {
+        return Ok(quotes);
+    }
+    let mut selected = Vec::new();
+    for expiry in expiries {
+        let wanted = resolve_expiry(&quotes, expiry)?;
+        if !selected.contains(&wanted) {
+            selected.push(wanted);
+        }
+    }
+    Ok(quotes
+        .into_iter()
+        .filter(|q| selected.contains(&q.row.expiration))
+        .collect())
+}
+
+/// Expiration timestamp named by a timestamp or an expiry date such as `10JAN25`
+fn resolve_expiry(quotes: &[OptionQuote], expiry: &str) -> Result<i64> {
+    let found = match expiry.parse::<ExpiryDate>() {
+        Ok(date) => quotes
+            .iter()
+            .find(|q| q.parsed_symbol().is_some_and(|p| p.expiry == date)),
+        Err(_) => {
+            let ts = expiry.trim().parse::<i64>().map_err(|_| {
+                anyhow!(
+                    "invalid expiry `{}` (expected a Unix timestamp or a date such as 10JAN25, 250110 or 2025-01-10)",
+                    expiry
+                )
+            })?;
+            quotes.iter().find(|q| q.row.expiration == ts)
+        }
+    };
+    found.map(|q| q.row.expiration).ok_or_else(|| {
+        let mut available: BTreeMap<i64, Option<String>> = BTreeMap::new();
+        for q in quotes {
+            available
+                .entry(q.row.expiration)
+                .or_insert_with(|| expiry_code(q));
+        }
+        let available: Vec<String> = available
+            .into_iter()
+            .map(|(ts, code)| match code {
+                Some(code) => format!("{code} ({ts})"),
+                None => ts.to_string(),
+            })
+            .collect();
+        anyhow!(
+            "no quotes for expiry `{}` (available: {})",
+            expiry,
+            available.join(", ")
+        )
+    })
+}
+
+/// Expiry of the quote's symbol, e.g. `10JAN25`
+fn expiry_code(quote: &OptionQuote) -> Option<String> {
+    quote.parsed_symbol().map(|p| p.expiry.code())
+}
+
+/// Quotes grouped by expiration, sorted by maturity
+fn group_by_expiry(quotes: &[OptionQuote]) -> BTreeMap<i64, Vec<&OptionQuote>> {
+    let mut groups: BTreeMap<i64, Vec<&OptionQuote>> = BTreeMap::new();
+    for quote in quotes {
+        groups.entry(quote.row.expiration).or_default().push(quote);
+    }
+    groups
+}
+
+fn rows(quotes: &[OptionQuote]) -> Vec<MarketDataRow> {
+    quotes.iter().map(|q| q.row.clone()).collect()
+}
+
+fn mean_underlying<'a>(rows: impl IntoIterator<Item = &'a MarketDataRow>) -> f64 {
+    let (sum, n) = rows
+        .into_iter()
+        .fold((0.0, 0usize), |(s, n), r| (s + r.underlying_price, n + 1));
+    sum / n.max(1) as f64
+}
+
+/// Write to `path`, or to stdout without one
+fn write_output(path: Option<&Path>, contents: &str) -> Result<()> {
+    match path {
+        Some(path) => std::fs::write(path, contents)
+            .with_context(|| format!("failed to write {}", path.display())),
+        None => match writeln!(std::io::stdout().lock(), "{}", contents.trim_end()) {
+            // A closed pipe (e.g. `| head`) is not an error
+            Err(e) if e.kind() != std::io::ErrorKind::BrokenPipe => Err(e.into()),
+            _ => Ok(()),
+        },
+    }
+}
+
+fn load_surface(path: &Path) -> Result<SurfaceRecord> {
+    let json = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read surface {}", path.display()))?;
+    SurfaceRecord::from_json(&json).with_context(|| format!("in surface {}", path.display()))
+}
+
+// ---------------------------------------------------------------------------
+// Subcommands
+// ---------------------------------------------------------------------------
+
+fn calibrate(settings: &Settings, args: CalibrateArgs) -> Result<ExitCode> {
+    let data = rows(&settings.load_chain(&args.data)?);
+    let record = calibrate_record(settings, data, &args)?;
+    for failure in &record.failures {
+        eprintln!(
+            "warning: expiry {} ({} quotes) failed: {}",
+            failure.expiration, failure.points, failure.error
+        );
+    }
+    write_output(args.output.as_deref(), &record.to_json_pretty()?)?;
+    Ok(ExitCode::SUCCESS)
+}
+
+fn calibrate_record(
+    settings: &Settings,
+    data: Vec<MarketDataRow>,
+    args: &CalibrateArgs,
+) -> Result<SurfaceRecord> {
+    let calib_params = settings
+        .job
+        .as_ref()
+        .map(JobSpec::calibration_params)
+        .unwrap_or_default();
+    let defaults = SurfaceCalibrationOptions::default();
+    let options = SurfaceCalibrationOptions {
+        warm_start: !args.no_warm_start,
+        max_threads: args.threads,
+        min_points: args.min_points.unwrap_or(defaults.min_points),
+        ..defaults
+    };
+    let record_params = settings
+        .job
+        .as_ref()
+        .map(JobSpec::calibration_params)
+        .unwrap_or_default();
+
+    let surface =
+        calibrate_svi_surface(data.clone(), settings.config.clone(), calib_params, options)?;
+    Ok(SurfaceRecord::from_calibration(
+        &surface,
+        &data,
+        &settings.config,
+        &record_params,
+    ))
+}
+
+/// A contract to price
+#[derive(Deserialize)]
+struct Contract {
+    option_type: String,
+    strike_price: f64,
+    underlying_price: f64,
+    years_to_exp: f64,
+}
+
+/// `base` with flat `--rate`/`--div` overrides
+fn override_rates(base: &FixedParameters, rate: Option<f64>, div: Option<f64>) -> FixedParameters {
+    FixedParameters {
+        r: rate.map_or_else(|| base.r.clone(), RateCurve::flat),
+        q: div.map_or_else(|| base.q.clone(), RateCurve::flat),
+        dividends: base.dividends.clone(),
+    }
+}
+
+fn price(settings: &Settings, args: PriceArgs) -> Result<ExitCode> {
+    let model = load_surface(&args.surface)?.model()?;
+    let fixed_params = override_rates(&settings.config.fixed_params, args.rate, args.div);
+
+    let mut reader = csv::Reader::from_path(&args.contracts)
+        .with_context(|| format!("failed to open contracts {}", args.contracts.display()))?;
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for (line, result) in reader.deserialize().enumerate() {
+        let context = || format!("{}: row {}", args.contracts.display(), line + 2);
+        let contract: Contract = result.with_context(context)?;
+        let option_type = contract.option_type.to_lowercase();
+        let priced = price_option(
+            &option_type,
+            contract.strike_price,
+            contract.underlying_price,
+            contract.years_to_exp,
+            &fixed_params,
+            &model,
+        )
+        .with_context(context)?;
+        writer.serialize(PricingResult {
+            option_type,
+            strike_price: contract.strike_price,
+            underlying_price: contract.underlying_price,
+            years_to_exp: contract.years_to_exp,
+            model_price: priced.price,
+            model_iv: priced.model_iv,
+        })?;
+    }
+    let csv = String::from_utf8(writer.into_inner()?)?;
+    write_output(args.output.as_deref(), &csv)?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Linear IV metrics of one expiry
+#[derive(Serialize)]
+struct ExpiryMetrics {
+    expiration: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    code: Option<String>,
+    forward: f64,
+    #[serde(flatten)]
+    output: LinearIvOutput,
+}
+
+/// An expiry whose metrics could not be computed
+#[derive(Serialize)]
+struct MetricsFailure {
+    expiration: i64,
+    error: String,
+}
+
+#[derive(Serialize)]
+struct MetricsReport {
+    expiries: Vec<ExpiryMetrics>,
+    failures: Vec<MetricsFailure>,
+    fixed_tenors: Vec<FixedTimeMetrics>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    events: Vec<EventMove>,
+}
+
+fn metrics(settings: &Settings, args: MetricsArgs) -> Result<ExitCode> {
+    let quotes = settings.load_chain(&args.data)?;
+    let defaults = LinearIvConfig::default();
+    let strike_config = LinearIvConfig {
+        deltas: if args.deltas.is_empty() {
+            defaults.deltas.clone()
+        } else {
+            args.deltas.clone()
+        },
+        fixed_params: override_rates(&defaults.fixed_params, args.rate, args.div),
+        delta_convention: args.delta_convention,
+        ..defaults
+    };
+    let mut temporal = if !args.days.is_empty() {
+        TemporalConfig::from_days(args.days.clone())
+    } else {
+        settings
+            .job
+            .as_ref()
+            .and_then(|job| job.temporal.clone())
+            .unwrap_or_default()
+    };
+    if !args.tenors.is_empty() {
+        temporal.tenors = args.tenors.clone();
+    }
+    if !args.moneyness_grid.is_empty() {
+        temporal.moneyness_grid = args.moneyness_grid.clone();
+    }
+    if let Some(weight) = args.weekend_weight {
+        temporal.clock = VarianceClock::weekends(weight);
+    }
+    temporal
+        .validate()
+        .context("invalid --days, --tenors, --moneyness-grid or --weekend-weight")?;
+
+    let mut report = MetricsReport {
+        expiries: Vec::new(),
+        failures: Vec::new(),
+        fixed_tenors: Vec::new(),
+        events: Vec::new(),
+    };
+    let mut usable = Vec::new();
+    for (expiration, group) in group_by_expiry(&quotes) {
+        let data: Vec<MarketDataRow> = group.iter().map(|q| q.row.clone()).collect();
+        let forward = mean_underlying(&data);
+        match build_linear_iv(&data, forward, data[0].years_to_exp, &strike_config) {
+            Ok(output) => {
+                report.expiries.push(ExpiryMetrics {
+                    expiration,
+                    code: expiry_code(group[0]),
+                    forward,
+                    output,
+                });
+                usable.extend(data);
+            }
+            Err(e) => {
+                eprintln!("warning: expiry {expiration} skipped: {e:#}");
+                report.failures.push(MetricsFailure {
+                    expiration,
+                    error: format!("{e:#}"),
+                });
+            }
+        }
+    }
+
+    if report.expiries.len() >= temporal.min_maturities {
+        report.fixed_tenors =
+            build_fixed_time_metrics_from_market_data(&usable, &temporal, &strike_config)?;
+        if !temporal.events.is_empty() {
+            let forwards = ForwardCurve::from_market_data(&usable)?;
+            report.events = implied_event_moves(&usable, &forwards, &temporal, &strike_config)?;
+        }
+    } else {
+        eprintln!(
+            "warning: fixed-tenor metrics need {} maturities, got {}",
+            temporal.min_maturities,
+            report.expiries.len()
+        );
+    }
+
+    write_output(
+        args.output.as_deref(),
+        &serde_json::to_string_pretty(&report)?,
+    )?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Negative butterfly density (g(k) < 0) within one slice
+#[derive(Serialize)]
+struct ButterflyViolation {
+    expiration: Option<i64>,
+    t: f64,
+    k: f64,
+    message: String,
+}
+
+/// Total variance decreasing between two adjacent slices
+#[derive(Serialize)]
+struct CalendarViolation {
+    t1: f64,
+    t2: f64,
+    k: f64,
+    message: String,
+}
+
+#[derive(Serialize)]
+struct ArbitrageReport {
+    arbitrage_free: bool,
+    slices: usize,
+    k_grid: (f64, f64, usize),
+    butterfly: Vec<ButterflyViolation>,
+    calendar: Vec<CalendarViolation>,
+}
+
+/// Writes the report and exits with status 1 when any violation is found.
+fn check(args: CheckArgs) -> Result<ExitCode> {
+    if !(args.k_min < args.k_max && args.points >= 2) {
+        return Err(anyhow!(
+            "log-moneyness grid needs --k-min < --k-max and at least 2 points"
+        ));
+    }
+    let record = load_surface(&args.surface)?;
+    let model = record.model()?;
+    let grid: Vec<f64> = (0..args.points)
+        .map(|i| args.k_min + (args.k_max - args.k_min) * i as f64 / (args.points - 1) as f64)
+        .collect();
+
+    let mut butterfly = Vec::new();
+    for slice in &record.slices {
+        for &k in &grid {
+            if let Err(e) = model.check_butterfly_arbitrage_at_k(k, slice.t) {
+                butterfly.push(ButterflyViolation {
+                    expiration: slice.expiration,
+                    t: slice.t,
+                    k,
+                    message: e.to_string(),
+                });
+            }
+        }
+    }
+    let mut calendar = Vec::new();
+    for pair in record.slices.windows(2) {
+        for &k in &grid {
+            if let Err(e) = model.check_calendar_arbitrage(k, pair[0].t, pair[1].t) {
+                calendar.push(CalendarViolation {
+                    t1: pair[0].t,
+                    t2: pair[1].t,
+                    k,
+                    message: e.to_string(),
+                });
+            }
+        }
+    }
+
+    let report = ArbitrageReport {
+        arbitrage_free: butterfly.is_empty() && calendar.is_empty(),
+        slices: record.slices.len(),
+        k_grid: (args.k_min, args.k_max, args.points),
+        butterfly,
+        calendar,
+    };
+    write_output(
+        args.output.as_deref(),
+        &serde_json::to_string_pretty(&report)?,
+    )?;
+    Ok(if report.arbitrage_free {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+fn plot(settings: &Settings, args: PlotArgs) -> Result<ExitCode> {
+    let data_args = DataArgs {
+        data: args.data.clone(),
+        expiries: vec![args.expiry.clone()],
+    };
+    let quotes = settings.load_chain(&data_args)?;
+    let expiration = quotes[0].row.expiration;
+    let t = quotes[0].row.years_to_exp;
+
+    let record = match &args.surface {
+        Some(path) => load_surface(path)?,
+        None => {
+            let calibrate_args = CalibrateArgs {
+                data: data_args,
+                min_points: None,
+                threads: None,
+                no_warm_start: false,
+                output: None,
+            };
+            calibrate_record(settings, rows(&quotes), &calibrate_args)?
+        }
+    };
+    let slice = record
+        .slices
+        .iter()
+        .find(|s| s.expiration == Some(expiration))
+        .or_else(|| {
+            record
+                .slices
+                .iter()
+                .min_by(|a, b| (a.t - t).abs().total_cmp(&(b.t - t).abs()))
+        })
+        .ok_or_else(|| anyhow!("surface has no slices"))?;
+    let label = expiry_code(&quotes[0]).unwrap_or_else(|| expiration.to_string());
+    let options = PlotOptions::default().with_title(format!(
+        "SVI Model vs Market IV Smile | Exp: {} (t={:.4}y, {:.1}d)",
+        label,
+        t,
+        t * 365.0
+    ));
+    let svg = quote_smile_svg(&quotes, &slice.params, &options)?;
+    std::fs::write(&args.output, svg)
+        .with_context(|| format!("failed to write {}", args.output.display()))?;
+    eprintln!("smile written to {}", args.output.display());
+    Ok(ExitCode::SUCCESS)
+}
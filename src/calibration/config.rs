@@ -1,22 +1,28 @@
 use crate::calibration::types::FixedParameters;
-use serde::Deserialize;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
 
 /// CMA-ES specific configuration parameters
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct CmaEsConfig {
     /// Random seed for reproducibility
     pub seed: Option<u64>,
     /// Whether to evaluate the population in parallel
     pub parallel_eval: bool,
-    /// Verbosity level (0=silent, 1=minimal, 2=normal)
+    /// Verbosity level (0=silent, >0 logs calibration progress through the `log` facade
+    /// when no observer is attached)
     pub verbosity: u8,
     /// Number of IPOP restarts (0 = no IPOP)
     pub ipop_restarts: usize,
     /// Factor to increase population size in IPOP restarts
     pub ipop_increase_factor: f64,
-    /// Max function evaluations per run (0=unlimited)
+    /// Max function evaluations per CMA-ES run (0=unlimited); caps the generations of each
+    /// run at `max_evaluations / population size`
     pub max_evaluations: usize,
-    /// Initial coordinate-wise standard deviation
+    /// Initial coordinate-wise standard deviation, as a fraction of each parameter's bound
+    /// width. The solver never starts narrower than a quarter of the width, so values
+    /// below 0.25 are rejected.
     pub sigma0: f64,
     /// Number of BIPOP restarts (0 = no BIPOP)
     pub bipop_restarts: usize,
@@ -30,6 +36,39 @@ pub struct CmaEsConfig {
     pub use_subrun_budgeting: bool,
     /// Use mini CMA-ES on refinement
     pub mini_cmaes_on_refinement: bool,
+
+    // Tuning of the canonical CMA-ES (global and mini stage). Learning rates set to 0 are
+    // derived from the problem dimension and population size.
+    /// Rank-one covariance learning rate (0 = automatic)
+    pub c1: f64,
+    /// Rank-μ covariance learning rate (0 = automatic)
+    pub c_mu: f64,
+    /// Cumulation learning rate of the step-size path (0 = automatic)
+    pub c_sigma: f64,
+    /// Step-size damping (0 = automatic)
+    pub d_sigma: f64,
+    /// Weight of the rank-μ update in the automatic `c_mu`
+    pub alpha_mu: f64,
+    /// Threshold factor of the evolution-path stall test (h_sigma)
+    pub hsig_threshold_factor: f64,
+    /// Size of small BIPOP populations relative to the base population
+    pub bipop_small_population_factor: f64,
+    /// Share of the evaluation budget given to small BIPOP runs
+    pub bipop_small_budget_factor: f64,
+    /// Share of the evaluation budget given to large BIPOP runs
+    pub bipop_large_budget_factor: f64,
+    /// Population growth factor of large BIPOP runs
+    pub bipop_large_pop_increase_factor: f64,
+    /// Mirroring passes before an out-of-bounds candidate is clamped
+    pub max_bound_iterations: usize,
+    /// Convergence threshold of the covariance eigendecomposition
+    pub eig_precision_threshold: f64,
+    /// Floor on covariance eigenvalues
+    pub min_eig_value: f64,
+    /// Threshold below which matrix entries are treated as zero
+    pub matrix_op_threshold: f64,
+    /// Generations without improvement before a run stops
+    pub stagnation_limit: usize,
 }
 
 impl Default for CmaEsConfig {
@@ -48,11 +87,42 @@ impl Default for CmaEsConfig {
             total_evals_budget: 200000,
             use_subrun_budgeting: false,
             mini_cmaes_on_refinement: true,
+            c1: 0.0,
+            c_mu: 0.0,
+            c_sigma: 0.0,
+            d_sigma: 0.0,
+            alpha_mu: 2.0,
+            hsig_threshold_factor: 1.4,
+            bipop_small_population_factor: 0.5,
+            bipop_small_budget_factor: 1.0,
+            bipop_large_budget_factor: 3.0,
+            bipop_large_pop_increase_factor: 2.0,
+            max_bound_iterations: 8,
+            eig_precision_threshold: 1e-15,
+            min_eig_value: 1e-15,
+            matrix_op_threshold: 1e-20,
+            stagnation_limit: 200,
         }
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+/// Optimizer backend used by the calibration pipeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OptimizerKind {
+    /// CMA-ES global search followed by L-BFGS-B refinement
+    #[default]
+    CmaesLbfgsb,
+    /// Bounded Nelder–Mead simplex search
+    NelderMead,
+    /// Differential evolution (DE/rand/1/bin)
+    DifferentialEvolution,
+    /// Levenberg–Marquardt on the model residuals
+    LevenbergMarquardt,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct AdaptiveBoundsConfig {
     pub enabled: bool,
     pub max_iterations: usize,
@@ -72,8 +142,11 @@ impl Default for AdaptiveBoundsConfig {
 }
 
 /// Main configuration struct for optimization
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
 pub struct OptimizationConfig {
+    /// Iteration cap of every backend: CMA-ES generations per run and L-BFGS-B,
+    /// Nelder–Mead and Levenberg–Marquardt iterations
     #[serde(default = "default_max_iterations")]
     pub max_iterations: usize,
 
@@ -91,19 +164,26 @@ pub struct OptimizationConfig {
     #[serde(default = "default_max_gen")]
     pub max_gen: usize,
 
-    /// Objective tolerance
+    /// Objective tolerance: L-BFGS-B stops once an iteration improves the objective by at
+    /// most `obj_tol` relative to `|f|` (to `max(|f|, 1)` with finite-difference gradients)
     #[serde(default = "default_obj_tol")]
     pub obj_tol: f64,
 
     /// Covariance matrix adaptation parameter
+    #[deprecated(note = "no backend reads it; use `cmaes.c1` and `cmaes.c_mu`")]
     #[serde(default = "default_alpha_cov")]
     pub alpha_cov: f64,
 
     /// Step size adaptation parameter
+    #[deprecated(note = "no backend reads it; use `cmaes.c_sigma` and `cmaes.d_sigma`")]
     #[serde(default = "default_alpha_sigma")]
     pub alpha_sigma: f64,
 
     /// Target success rate
+    #[deprecated(
+        note = "no backend reads it; the canonical CMA-ES adapts its step size \
+                         from the evolution path"
+    )]
     #[serde(default = "default_target_sr")]
     pub target_sr: f64,
 
@@ -114,8 +194,13 @@ pub struct OptimizationConfig {
     /// Adaptive bounds configuration
     #[serde(default)]
     pub adaptive_bounds: AdaptiveBoundsConfig,
+
+    /// Optimizer backend used to minimise the calibration objective
+    #[serde(default)]
+    pub optimizer: OptimizerKind,
 }
 
+#[allow(deprecated)]
 impl Default for OptimizationConfig {
     fn default() -> Self {
         Self {
@@ -130,6 +215,7 @@ impl Default for OptimizationConfig {
             target_sr: default_target_sr(),
             cmaes: CmaEsConfig::default(),
             adaptive_bounds: AdaptiveBoundsConfig::default(),
+            optimizer: OptimizerKind::default(),
         }
     }
 }
@@ -144,9 +230,6 @@ impl OptimizationConfig {
             pop_size: 25,
             max_gen: 50,
             obj_tol: 1e-8,
-            alpha_cov: 0.2,
-            alpha_sigma: 0.5,
-            target_sr: 0.2,
             cmaes: CmaEsConfig {
                 verbosity: 0, // Slightly more verbose for production monitoring
                 max_evaluations: 100000,
@@ -154,6 +237,8 @@ impl OptimizationConfig {
                 ..CmaEsConfig::default()
             },
             adaptive_bounds: AdaptiveBoundsConfig::default(),
+            optimizer: OptimizerKind::default(),
+            ..Self::default()
         }
     }
 
@@ -166,9 +251,6 @@ impl OptimizationConfig {
             pop_size: 30,
             max_gen: 50,
             obj_tol: 1e-6,
-            alpha_cov: 0.2,
-            alpha_sigma: 0.5,
-            target_sr: 0.2,
             cmaes: CmaEsConfig {
                 verbosity: 2,
                 max_evaluations: 10000,
@@ -177,6 +259,8 @@ impl OptimizationConfig {
                 ..CmaEsConfig::default()
             },
             adaptive_bounds: AdaptiveBoundsConfig::default(),
+            optimizer: OptimizerKind::default(),
+            ..Self::default()
         }
     }
 
@@ -189,9 +273,6 @@ impl OptimizationConfig {
             pop_size: 100,
             max_gen: 200,
             obj_tol: 1e-9,
-            alpha_cov: 0.15,
-            alpha_sigma: 0.3,
-            target_sr: 0.15,
             cmaes: CmaEsConfig {
                 verbosity: 1,
                 max_evaluations: 500000,
@@ -201,6 +282,8 @@ impl OptimizationConfig {
                 ..CmaEsConfig::default()
             },
             adaptive_bounds: AdaptiveBoundsConfig::default(),
+            optimizer: OptimizerKind::default(),
+            ..Self::default()
         }
     }
 
@@ -213,9 +296,6 @@ impl OptimizationConfig {
             pop_size: 10,
             max_gen: 20,
             obj_tol: 1e-4,
-            alpha_cov: 0.3,
-            alpha_sigma: 0.7,
-            target_sr: 0.3,
             cmaes: CmaEsConfig {
                 verbosity: 0,
                 max_evaluations: 1000,
@@ -224,10 +304,227 @@ impl OptimizationConfig {
                 ..CmaEsConfig::default()
             },
             adaptive_bounds: AdaptiveBoundsConfig::default(),
+            optimizer: OptimizerKind::default(),
+            ..Self::default()
+        }
+    }
+
+    /// Check that every setting is within its admissible range and that no two settings
+    /// contradict each other.
+    ///
+    /// Errors name the offending field by its TOML path (e.g. `cmaes.sigma0`).
+    pub fn validate(&self) -> Result<()> {
+        if self.max_iterations == 0 {
+            return Err(invalid(
+                "max_iterations",
+                "must be > 0",
+                self.max_iterations,
+            ));
+        }
+        if !(self.tolerance > 0.0 && self.tolerance.is_finite()) {
+            return Err(invalid(
+                "tolerance",
+                "must be > 0 and finite",
+                self.tolerance,
+            ));
+        }
+        if self.pop_size == 1 {
+            return Err(invalid(
+                "pop_size",
+                "must be 0 (automatic) or at least 2",
+                self.pop_size,
+            ));
+        }
+        if self.max_gen == 0 {
+            return Err(invalid("max_gen", "must be > 0", self.max_gen));
+        }
+        if !(self.obj_tol >= 0.0 && self.obj_tol.is_finite()) {
+            return Err(invalid("obj_tol", "must be >= 0 and finite", self.obj_tol));
+        }
+        #[allow(deprecated)]
+        let deprecated_fields = [
+            ("alpha_cov", self.alpha_cov, default_alpha_cov()),
+            ("alpha_sigma", self.alpha_sigma, default_alpha_sigma()),
+            ("target_sr", self.target_sr, default_target_sr()),
+        ];
+        for (field, value, default) in deprecated_fields {
+            if value != default {
+                let requirement = format!("is deprecated and unused; leave it at {default}");
+                return Err(invalid(field, &requirement, value));
+            }
+        }
+        if !self.fixed_params.r.is_finite() {
+            return Err(invalid(
+                "fixed_params.r",
+                "must be finite",
+                &self.fixed_params.r,
+            ));
+        }
+        if !self.fixed_params.q.is_finite() {
+            return Err(invalid(
+                "fixed_params.q",
+                "must be finite",
+                &self.fixed_params.q,
+            ));
+        }
+
+        let cmaes = &self.cmaes;
+        if !(cmaes.sigma0 >= 0.25 && cmaes.sigma0.is_finite()) {
+            return Err(invalid(
+                "cmaes.sigma0",
+                "must be >= 0.25 and finite",
+                cmaes.sigma0,
+            ));
+        }
+        if !(cmaes.ipop_increase_factor >= 1.0 && cmaes.ipop_increase_factor.is_finite()) {
+            return Err(invalid(
+                "cmaes.ipop_increase_factor",
+                "must be >= 1 and finite",
+                cmaes.ipop_increase_factor,
+            ));
+        }
+        if cmaes.lbfgsb_enabled && cmaes.lbfgsb_max_iterations == 0 {
+            return Err(invalid(
+                "cmaes.lbfgsb_max_iterations",
+                "must be > 0 when L-BFGS-B is enabled",
+                cmaes.lbfgsb_max_iterations,
+            ));
+        }
+
+        // Tuning of the canonical CMA-ES; learning rates of 0 are derived automatically
+        let learning_rates = [
+            ("cmaes.c1", cmaes.c1),
+            ("cmaes.c_mu", cmaes.c_mu),
+            ("cmaes.c_sigma", cmaes.c_sigma),
+        ];
+        for (field, value) in learning_rates {
+            if !(value == 0.0 || (value > 0.0 && value < 1.0)) {
+                return Err(invalid(field, "must be 0 (automatic) or in (0, 1)", value));
+            }
+        }
+        if !(cmaes.d_sigma >= 0.0 && cmaes.d_sigma.is_finite()) {
+            return Err(invalid(
+                "cmaes.d_sigma",
+                "must be 0 (automatic) or > 0",
+                cmaes.d_sigma,
+            ));
+        }
+        let positive_fields = [
+            ("cmaes.alpha_mu", cmaes.alpha_mu),
+            ("cmaes.hsig_threshold_factor", cmaes.hsig_threshold_factor),
+            (
+                "cmaes.bipop_small_budget_factor",
+                cmaes.bipop_small_budget_factor,
+            ),
+            (
+                "cmaes.bipop_large_budget_factor",
+                cmaes.bipop_large_budget_factor,
+            ),
+            (
+                "cmaes.eig_precision_threshold",
+                cmaes.eig_precision_threshold,
+            ),
+            ("cmaes.min_eig_value", cmaes.min_eig_value),
+            ("cmaes.matrix_op_threshold", cmaes.matrix_op_threshold),
+        ];
+        for (field, value) in positive_fields {
+            if !(value > 0.0 && value.is_finite()) {
+                return Err(invalid(field, "must be > 0 and finite", value));
+            }
+        }
+        if !(cmaes.bipop_small_population_factor > 0.0
+            && cmaes.bipop_small_population_factor <= 1.0)
+        {
+            return Err(invalid(
+                "cmaes.bipop_small_population_factor",
+                "must be in (0, 1]",
+                cmaes.bipop_small_population_factor,
+            ));
         }
+        if !(cmaes.bipop_large_pop_increase_factor >= 1.0
+            && cmaes.bipop_large_pop_increase_factor.is_finite())
+        {
+            return Err(invalid(
+                "cmaes.bipop_large_pop_increase_factor",
+                "must be >= 1 and finite",
+                cmaes.bipop_large_pop_increase_factor,
+            ));
+        }
+        if cmaes.max_bound_iterations == 0 {
+            return Err(invalid(
+                "cmaes.max_bound_iterations",
+                "must be > 0",
+                cmaes.max_bound_iterations,
+            ));
+        }
+        if cmaes.stagnation_limit == 0 {
+            return Err(invalid(
+                "cmaes.stagnation_limit",
+                "must be > 0",
+                cmaes.stagnation_limit,
+            ));
+        }
+
+        // Settings that contradict each other
+        if cmaes.c1 > 0.0 && cmaes.c_mu > 0.0 && cmaes.c1 + cmaes.c_mu > 1.0 {
+            return Err(conflict(&format!(
+                "`cmaes.c1` + `cmaes.c_mu` = {} exceeds 1, \
+                 which makes the covariance update indefinite",
+                cmaes.c1 + cmaes.c_mu
+            )));
+        }
+        if cmaes.use_subrun_budgeting && cmaes.total_evals_budget == 0 {
+            return Err(conflict(
+                "`cmaes.use_subrun_budgeting` requires a `cmaes.total_evals_budget` > 0",
+            ));
+        }
+        if cmaes.max_evaluations > 0
+            && cmaes.total_evals_budget > 0
+            && cmaes.max_evaluations > cmaes.total_evals_budget
+        {
+            return Err(conflict(&format!(
+                "`cmaes.max_evaluations` ({}) exceeds `cmaes.total_evals_budget` ({})",
+                cmaes.max_evaluations, cmaes.total_evals_budget
+            )));
+        }
+
+        let adaptive = &self.adaptive_bounds;
+        if adaptive.enabled && adaptive.max_iterations == 0 {
+            return Err(invalid(
+                "adaptive_bounds.max_iterations",
+                "must be > 0 when adaptive bounds are enabled",
+                adaptive.max_iterations,
+            ));
+        }
+        if !(adaptive.proximity_threshold > 0.0 && adaptive.proximity_threshold < 1.0) {
+            return Err(invalid(
+                "adaptive_bounds.proximity_threshold",
+                "must be in (0, 1)",
+                adaptive.proximity_threshold,
+            ));
+        }
+        if !(adaptive.expansion_factor > 0.0 && adaptive.expansion_factor.is_finite()) {
+            return Err(invalid(
+                "adaptive_bounds.expansion_factor",
+                "must be > 0 and finite",
+                adaptive.expansion_factor,
+            ));
+        }
+
+        Ok(())
     }
 }
 
+/// Error for a configuration field outside its admissible range
+fn invalid(field: &str, requirement: &str, value: impl std::fmt::Display) -> anyhow::Error {
+    anyhow!("invalid optimization config: `{field}` {requirement} (got {value})")
+}
+
+/// Error for settings that are individually valid but cannot be combined
+fn conflict(message: &str) -> anyhow::Error {
+    anyhow!("contradictory optimization config: {message}")
+}
+
 fn default_max_iterations() -> usize {
     5000
 }
@@ -259,3 +556,48 @@ fn default_alpha_sigma() -> f64 {
 fn default_target_sr() -> f64 {
     0.2
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_are_valid() {
+        for config in [
+            OptimizationConfig::default(),
+            OptimizationConfig::production(),
+            OptimizationConfig::fast(),
+            OptimizationConfig::research(),
+            OptimizationConfig::minimal(),
+        ] {
+            config.validate().unwrap();
+        }
+    }
+
+    #[test]
+    fn rejects_contradictory_settings() {
+        let error = |edit: fn(&mut OptimizationConfig)| {
+            let mut config = OptimizationConfig::default();
+            edit(&mut config);
+            config.validate().unwrap_err().to_string()
+        };
+
+        assert!(error(|c| {
+            c.cmaes.c1 = 0.6;
+            c.cmaes.c_mu = 0.5;
+        })
+        .contains("exceeds 1"));
+        assert!(error(|c| {
+            c.cmaes.use_subrun_budgeting = true;
+            c.cmaes.total_evals_budget = 0;
+        })
+        .contains("requires a `cmaes.total_evals_budget`"));
+        assert!(error(|c| c.cmaes.max_evaluations = 300_000)
+            .contains("exceeds `cmaes.total_evals_budget`"));
+        assert!(error(|c| c.cmaes.sigma0 = 0.1).contains("`cmaes.sigma0` must be >= 0.25"));
+        #[allow(deprecated)]
+        let deprecated = error(|c| c.target_sr = 0.3);
+        assert!(deprecated.contains("`target_sr` is deprecated and unused; leave it at 0.2"));
+        assert!(error(|c| c.cmaes.c_mu = 1.5).contains("`cmaes.c_mu` must be 0 (automatic)"));
+    }
+}
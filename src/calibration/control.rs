@@ -0,0 +1,112 @@
+//! Wall-clock deadlines, cooperative cancellation and progress observers for calibration runs
+//!
+//! A [`CalibrationControl`] is checked before every objective evaluation. Once its deadline
+//! has passed or its [`CancellationToken`] has been cancelled, the running optimizer stops
+//! receiving useful evaluations and the pipeline returns the best solution found so far,
+//! marked as truncated in the [`CalibrationOutcome`](crate::calibration::types::CalibrationOutcome).
+
+use crate::calibration::observer::{CalibrationEvent, CalibrationObserver};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Cooperative cancellation flag shared between a caller and running calibrations.
+///
+/// Cloning the token shares the underlying flag, so a clone can be handed to another
+/// thread and cancelled from there.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request cancellation of every calibration holding this token
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    /// Whether cancellation has been requested
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Run-time controls for a calibration: stop conditions besides the evaluation budgets in
+/// [`OptimizationConfig`](crate::calibration::config::OptimizationConfig), and an optional
+/// progress observer.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+/// use surface_lib::calibration::control::{CalibrationControl, CancellationToken};
+///
+/// let token = CancellationToken::new();
+/// let control = CalibrationControl::new()
+///     .with_timeout(Duration::from_millis(250))
+///     .with_cancellation(token.clone());
+/// assert!(!control.should_stop());
+/// token.cancel();
+/// assert!(control.should_stop());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CalibrationControl {
+    /// Wall-clock instant after which the calibration stops
+    pub deadline: Option<Instant>,
+    /// Token that stops the calibration when cancelled
+    pub cancellation: Option<CancellationToken>,
+    /// Receiver of progress events
+    pub observer: Option<Arc<dyn CalibrationObserver>>,
+}
+
+impl CalibrationControl {
+    /// Control without deadline or cancellation
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop at the given instant
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Stop once `timeout` has elapsed from now
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        self.with_deadline(Instant::now() + timeout)
+    }
+
+    /// Stop when `token` is cancelled
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Report progress events to `observer`
+    pub fn with_observer(mut self, observer: Arc<dyn CalibrationObserver>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    /// Forward `event` to the observer, if any
+    pub fn emit(&self, event: CalibrationEvent) {
+        if let Some(observer) = &self.observer {
+            observer.on_event(&event);
+        }
+    }
+
+    /// Whether an observer is attached
+    pub fn is_observed(&self) -> bool {
+        self.observer.is_some()
+    }
+
+    /// Whether the deadline has passed or cancellation has been requested
+    pub fn should_stop(&self) -> bool {
+        self.cancellation
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+            || self.deadline.is_some_and(|d| Instant::now() >= d)
+    }
+}
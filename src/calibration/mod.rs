@@ -1,5 +1,9 @@
 pub mod config;
+pub mod control;
+pub mod observer;
+pub mod optimizer;
 pub mod pipeline;
+pub mod profile;
 pub mod types;
 
 // Re-export optimization algorithms for easy access inside the library
@@ -0,0 +1,173 @@
+//! Structured progress reporting for calibration runs
+//!
+//! A [`CalibrationObserver`] attached to a
+//! [`CalibrationControl`](crate::calibration::control::CalibrationControl) receives a
+//! [`CalibrationEvent`] for every stage boundary, objective improvement, bound expansion and
+//! for the final result, so progress can be forwarded to metrics, logs or UIs.
+//!
+//! When no observer is attached and `CmaEsConfig::verbosity > 0`, the pipeline falls back to
+//! [`LogObserver`], which routes the events through the `log` facade. The library never
+//! prints to stdout or stderr itself.
+
+use std::fmt;
+use std::time::Duration;
+
+/// Phase of a calibration run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalibrationStage {
+    /// Full CMA-ES global search (no initial guess)
+    Cmaes,
+    /// CMA-ES restricted to the neighbourhood of an initial guess
+    MiniCmaes,
+    /// L-BFGS-B local refinement
+    Lbfgsb,
+    /// One pass of the adaptive-bounds loop (1-based)
+    AdaptiveIteration { iteration: usize },
+}
+
+impl fmt::Display for CalibrationStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CalibrationStage::Cmaes => f.write_str("CMA-ES"),
+            CalibrationStage::MiniCmaes => f.write_str("mini CMA-ES"),
+            CalibrationStage::Lbfgsb => f.write_str("L-BFGS-B"),
+            CalibrationStage::AdaptiveIteration { iteration } => {
+                write!(f, "adaptive iteration {}", iteration)
+            }
+        }
+    }
+}
+
+/// Progress event emitted during a calibration run
+#[derive(Debug, Clone, PartialEq)]
+pub enum CalibrationEvent {
+    /// A stage is about to run
+    StageStarted { stage: CalibrationStage },
+    /// A stage completed with the given best objective
+    StageFinished {
+        stage: CalibrationStage,
+        objective: f64,
+        /// Objective evaluations used by the run so far
+        evaluations: usize,
+    },
+    /// A new best objective was evaluated by the current optimizer pass
+    /// (each adaptive-bounds iteration starts a new pass)
+    ObjectiveImproved {
+        objective: f64,
+        params: Vec<f64>,
+        /// Objective evaluations used by the run so far
+        evaluations: usize,
+    },
+    /// The adaptive-bounds loop widened the parameter box after an iteration
+    BoundsExpanded {
+        iteration: usize,
+        bounds: Vec<(f64, f64)>,
+    },
+    /// Final summary of the run
+    Finished {
+        objective: f64,
+        params: Vec<f64>,
+        evaluations: usize,
+        /// True if a deadline or cancellation stopped the run early
+        truncated: bool,
+        elapsed: Duration,
+    },
+}
+
+/// Receiver of [`CalibrationEvent`]s.
+///
+/// Events may be delivered from optimizer worker threads, hence the `Send + Sync` bound.
+/// Any `Fn(&CalibrationEvent)` closure is an observer.
+///
+/// # Example
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use surface_lib::calibration::control::CalibrationControl;
+/// use surface_lib::calibration::observer::CalibrationEvent;
+///
+/// let control = CalibrationControl::new().with_observer(Arc::new(|event: &CalibrationEvent| {
+///     if let CalibrationEvent::Finished { objective, elapsed, .. } = event {
+///         println!("calibrated to {:.3e} in {:?}", objective, elapsed);
+///     }
+/// }));
+/// ```
+pub trait CalibrationObserver: Send + Sync {
+    fn on_event(&self, event: &CalibrationEvent);
+}
+
+impl<F> CalibrationObserver for F
+where
+    F: Fn(&CalibrationEvent) + Send + Sync,
+{
+    fn on_event(&self, event: &CalibrationEvent) {
+        self(event)
+    }
+}
+
+impl fmt::Debug for dyn CalibrationObserver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CalibrationObserver")
+    }
+}
+
+/// Observer forwarding progress to the [`log`] facade under the `surface_lib::calibration`
+/// target.
+///
+/// Stage boundaries, bound expansions and the summary are logged at `info`; objective
+/// improvements at `debug`. Event data is attached as structured key-values.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LogObserver;
+
+impl LogObserver {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+const LOG_TARGET: &str = "surface_lib::calibration";
+
+impl CalibrationObserver for LogObserver {
+    fn on_event(&self, event: &CalibrationEvent) {
+        match event {
+            CalibrationEvent::StageStarted { stage } => {
+                log::info!(target: LOG_TARGET, stage:% = stage; "calibration stage started")
+            }
+            CalibrationEvent::StageFinished {
+                stage,
+                objective,
+                evaluations,
+            } => log::info!(
+                target: LOG_TARGET,
+                stage:% = stage, objective = *objective, evaluations = *evaluations;
+                "calibration stage finished"
+            ),
+            CalibrationEvent::ObjectiveImproved {
+                objective,
+                params,
+                evaluations,
+            } => log::debug!(
+                target: LOG_TARGET,
+                objective = *objective, params:? = params, evaluations = *evaluations;
+                "calibration objective improved"
+            ),
+            CalibrationEvent::BoundsExpanded { iteration, bounds } => log::info!(
+                target: LOG_TARGET,
+                iteration = *iteration, bounds:? = bounds;
+                "adaptive bounds expanded"
+            ),
+            CalibrationEvent::Finished {
+                objective,
+                params,
+                evaluations,
+                truncated,
+                elapsed,
+            } => log::info!(
+                target: LOG_TARGET,
+                objective = *objective, params:? = params, evaluations = *evaluations,
+                truncated = *truncated, elapsed_ms = elapsed.as_secs_f64() * 1e3;
+                "calibration finished"
+            ),
+        }
+    }
+}
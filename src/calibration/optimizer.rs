@@ -0,0 +1,1311 @@
+//! Optimizer backends for the calibration pipeline
+//!
+//! The pipeline minimises [`ModelCalibrator::evaluate_objective`] inside the box
+//! constraints returned by [`ModelCalibrator::param_bounds`]. Every backend implements the
+//! [`Optimizer`] trait, so any calibrator can be solved by any backend and the resulting
+//! fits compared on the same market snapshot.
+//!
+//! Available backends (selected through [`OptimizationConfig::optimizer`]):
+//! - [`CmaesLbfgsbOptimizer`]: CMA-ES global search followed by L-BFGS-B refinement (default)
+//! - [`NelderMeadOptimizer`]: bounded Nelder–Mead simplex search
+//! - [`DifferentialEvolutionOptimizer`]: DE/rand/1/bin population search
+//! - [`LevenbergMarquardtOptimizer`]: damped Gauss–Newton on the model residuals
+
+use crate::calibration::config::{OptimizationConfig, OptimizerKind};
+use crate::calibration::control::CalibrationControl;
+use crate::calibration::observer::{CalibrationEvent, CalibrationStage};
+use crate::calibration::types::{MarketDataRow, ModelCalibrator};
+use cmaes_lbfgsb::cmaes::{canonical_cmaes_optimize, CmaesCanonicalConfig};
+use cmaes_lbfgsb::lbfgsb_optimize::{lbfgsb_optimize, LbfgsbConfig};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// A model calibrator bound to the market data it is being fitted to.
+///
+/// Besides forwarding evaluations to the calibrator, the problem enforces the
+/// [`CalibrationControl`] stop conditions and tracks the best point evaluated so far, so a
+/// truncated run can still return its best solution.
+pub struct CalibrationProblem<'a> {
+    model: &'a dyn ModelCalibrator,
+    market_data: &'a [MarketDataRow],
+    control: CalibrationControl,
+    evaluations: AtomicUsize,
+    truncated: AtomicBool,
+    best: Mutex<Option<(f64, Vec<f64>)>>,
+}
+
+impl<'a> CalibrationProblem<'a> {
+    pub fn new(model: &'a dyn ModelCalibrator, market_data: &'a [MarketDataRow]) -> Self {
+        Self {
+            model,
+            market_data,
+            control: CalibrationControl::default(),
+            evaluations: AtomicUsize::new(0),
+            truncated: AtomicBool::new(false),
+            best: Mutex::new(None),
+        }
+    }
+
+    /// Attach a deadline, cancellation token and/or observer
+    pub fn with_control(mut self, control: CalibrationControl) -> Self {
+        self.control = control;
+        self
+    }
+
+    /// The calibrator being solved
+    pub fn model(&self) -> &dyn ModelCalibrator {
+        self.model
+    }
+
+    /// Box constraints for each parameter
+    pub fn bounds(&self) -> &[(f64, f64)] {
+        self.model.param_bounds()
+    }
+
+    /// Objective value at `x`.
+    ///
+    /// Once the control asks to stop, returns `f64::INFINITY` without evaluating the model
+    /// and marks the problem as truncated. The first evaluation always goes through so that
+    /// a solution exists even if the deadline passed before the run started.
+    pub fn objective(&self, x: &[f64]) -> f64 {
+        if self.check_stop() {
+            return f64::INFINITY;
+        }
+        let value = self.model.evaluate_objective(x, self.market_data);
+        let evaluations = self.evaluations.fetch_add(1, Ordering::Relaxed) + 1;
+
+        // Improvements are reported while holding the lock so observers see them in order
+        let mut best = self.best.lock().unwrap_or_else(|e| e.into_inner());
+        if best.as_ref().is_none_or(|(f, _)| value < *f) {
+            *best = Some((value, x.to_vec()));
+            if self.control.is_observed() {
+                self.control.emit(CalibrationEvent::ObjectiveImproved {
+                    objective: value,
+                    params: x.to_vec(),
+                    evaluations,
+                });
+            }
+        }
+        value
+    }
+
+    /// Analytic gradient at `x`, if the calibrator provides one and the run may continue
+    pub fn gradient(&self, x: &[f64]) -> Option<Vec<f64>> {
+        if self.check_stop() {
+            return None;
+        }
+        self.model.evaluate_gradient(x, self.market_data)
+    }
+
+    /// Residual vector at `x`, if the calibrator provides one and the run may continue
+    pub fn residuals(&self, x: &[f64]) -> Option<Vec<f64>> {
+        if self.check_stop() {
+            return None;
+        }
+        self.model.evaluate_residuals(x, self.market_data)
+    }
+
+    /// Report `event` to the attached observer, if any
+    pub fn emit(&self, event: CalibrationEvent) {
+        self.control.emit(event);
+    }
+
+    /// Report the start of `stage`
+    pub fn stage_started(&self, stage: CalibrationStage) {
+        self.emit(CalibrationEvent::StageStarted { stage });
+    }
+
+    /// Report the end of `stage` with its resulting objective
+    pub fn stage_finished(&self, stage: CalibrationStage, objective: f64) {
+        self.emit(CalibrationEvent::StageFinished {
+            stage,
+            objective,
+            evaluations: self.evaluations(),
+        });
+    }
+
+    /// Number of objective evaluations performed so far
+    pub fn evaluations(&self) -> usize {
+        self.evaluations.load(Ordering::Relaxed)
+    }
+
+    /// Whether the run was cut short by the deadline or cancellation
+    pub fn is_truncated(&self) -> bool {
+        self.truncated.load(Ordering::Relaxed)
+    }
+
+    /// Best `(objective, parameters)` evaluated so far
+    pub fn best(&self) -> Option<(f64, Vec<f64>)> {
+        self.best.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    fn check_stop(&self) -> bool {
+        if self.is_truncated() {
+            return true;
+        }
+        if self.evaluations() > 0 && self.control.should_stop() {
+            self.truncated.store(true, Ordering::Relaxed);
+            return true;
+        }
+        false
+    }
+
+    /// Centre of the parameter box, used as the starting point when no guess is given
+    fn midpoint(&self) -> Vec<f64> {
+        self.bounds()
+            .iter()
+            .map(|(lo, hi)| 0.5 * (lo + hi))
+            .collect()
+    }
+}
+
+/// Optimizer backend minimising a [`CalibrationProblem`].
+pub trait Optimizer: Send + Sync {
+    /// Short identifier of the backend (e.g. "nelder_mead")
+    fn name(&self) -> &str;
+
+    /// Minimise the problem objective and return `(objective, parameters)`.
+    /// `initial_guess`, when given, is used as the starting point or search centre.
+    fn minimize(
+        &self,
+        problem: &CalibrationProblem<'_>,
+        config: &OptimizationConfig,
+        initial_guess: Option<&[f64]>,
+    ) -> (f64, Vec<f64>);
+}
+
+impl OptimizerKind {
+    /// Instantiate the backend for this kind
+    pub fn build(self) -> Box<dyn Optimizer> {
+        match self {
+            OptimizerKind::CmaesLbfgsb => Box::new(CmaesLbfgsbOptimizer),
+            OptimizerKind::NelderMead => Box::new(NelderMeadOptimizer),
+            OptimizerKind::DifferentialEvolution => Box::new(DifferentialEvolutionOptimizer),
+            OptimizerKind::LevenbergMarquardt => Box::new(LevenbergMarquardtOptimizer),
+        }
+    }
+}
+
+// ================================================================================================
+// CMA-ES + L-BFGS-B
+// ================================================================================================
+
+/// CMA-ES for global search (or a mini CMA-ES around the initial guess) followed by
+/// L-BFGS-B local refinement.
+///
+/// Both CMA-ES stages search the unit box spanned by the parameter bounds, starting with
+/// step `cmaes.sigma0`, and take `pop_size`, `max_gen` and the `cmaes` tuning fields;
+/// `cmaes.max_evaluations` and `max_iterations` cap every run. L-BFGS-B stops on
+/// `tolerance` or `obj_tol`, after at most `cmaes.lbfgsb_max_iterations` and
+/// `max_iterations` iterations.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CmaesLbfgsbOptimizer;
+
+impl Optimizer for CmaesLbfgsbOptimizer {
+    fn name(&self) -> &str {
+        "cmaes_lbfgsb"
+    }
+
+    fn minimize(
+        &self,
+        problem: &CalibrationProblem<'_>,
+        config: &OptimizationConfig,
+        initial_guess: Option<&[f64]>,
+    ) -> (f64, Vec<f64>) {
+        let bounds = problem.bounds();
+        let obj_fn = |x: &[f64]| problem.objective(x);
+
+        // 1) CMA-ES approach, either a "mini CMA-ES" around the initial guess or full CMA-ES if none provided.
+        let (best_obj, best_sol) = {
+            // If we have an initial guess, check if we should run mini CMA-ES or go straight to L-BFGS-B
+            if let Some(guess) = initial_guess {
+                let guess_obj = obj_fn(guess);
+                if config.cmaes.mini_cmaes_on_refinement {
+                    // Run mini-CMA-ES with the guess as the initial distribution center
+                    problem.stage_started(CalibrationStage::MiniCmaes);
+                    let (obj, params) = match run_cmaes(problem, config, Some(guess.to_vec())) {
+                        Some(params) => (obj_fn(&params), params),
+                        None => problem
+                            .best()
+                            .unwrap_or_else(|| (guess_obj, guess.to_vec())),
+                    };
+                    problem.stage_finished(CalibrationStage::MiniCmaes, obj);
+                    (obj, params)
+                } else {
+                    // Skip mini CMA-ES and use the initial guess directly for L-BFGS-B
+                    (guess_obj, guess.to_vec())
+                }
+            } else {
+                problem.stage_started(CalibrationStage::Cmaes);
+                let (obj, params) = match run_cmaes(problem, config, None) {
+                    Some(params) => (obj_fn(&params), params),
+                    None => problem
+                        .best()
+                        .unwrap_or_else(|| (f64::INFINITY, problem.midpoint())),
+                };
+                problem.stage_finished(CalibrationStage::Cmaes, obj);
+                (obj, params)
+            }
+        };
+
+        // 2) Local refinement of the best solution with L-BFGS-B (if enabled and time remains)
+        if problem.is_truncated() || !config.cmaes.lbfgsb_enabled {
+            return (best_obj, best_sol);
+        }
+
+        problem.stage_started(CalibrationStage::Lbfgsb);
+        let max_iterations = config
+            .cmaes
+            .lbfgsb_max_iterations
+            .min(config.max_iterations);
+
+        // Prefer exact gradients when the calibrator provides them; otherwise fall back to
+        // the finite-difference L-BFGS-B from `cmaes_lbfgsb`.
+        let refine_res = if problem.gradient(&best_sol).is_some() {
+            Ok(lbfgsb_with_gradient(
+                |x| problem.objective(x),
+                |x| problem.gradient(x),
+                bounds,
+                &best_sol,
+                max_iterations,
+                config.tolerance,
+                config.obj_tol,
+                None::<fn(&[f64], f64)>,
+                || problem.is_truncated(),
+            ))
+        } else {
+            let mut refined_solution = best_sol.clone();
+            lbfgsb_optimize(
+                &mut refined_solution,
+                bounds,
+                &obj_fn,
+                max_iterations,
+                config.tolerance,
+                None::<fn(&[f64], f64)>,
+                Some(LbfgsbConfig {
+                    obj_tol: config.obj_tol,
+                    ..LbfgsbConfig::default()
+                }),
+            )
+        };
+
+        // Keep the CMA-ES solution unless the refinement improved on it
+        let (obj, params) = match refine_res {
+            Ok((loc_obj, loc_sol)) if loc_obj < best_obj => (loc_obj, loc_sol),
+            _ => (best_obj, best_sol),
+        };
+        problem.stage_finished(CalibrationStage::Lbfgsb, obj);
+        (obj, params)
+    }
+}
+
+/// Settings of the canonical CMA-ES runs
+fn canonical_cmaes_config(config: &OptimizationConfig, dim: usize) -> CmaesCanonicalConfig {
+    let cmaes = &config.cmaes;
+    let automatic = |v: f64| (v > 0.0).then_some(v);
+
+    // The solver has no evaluation limit per run, so `max_evaluations` caps the generations
+    let population = if config.pop_size == 0 {
+        4 + (3.0 * (dim as f64).ln()) as usize
+    } else {
+        config.pop_size
+    };
+    let max_generations = match cmaes.max_evaluations {
+        0 => config.max_gen,
+        limit => config.max_gen.min(limit.div_ceil(population)),
+    }
+    .min(config.max_iterations);
+
+    CmaesCanonicalConfig {
+        population_size: config.pop_size,
+        max_generations,
+        seed: cmaes.seed.unwrap_or(123456),
+        c1: automatic(cmaes.c1),
+        c_mu: automatic(cmaes.c_mu),
+        c_sigma: automatic(cmaes.c_sigma),
+        d_sigma: automatic(cmaes.d_sigma),
+        parallel_eval: cmaes.parallel_eval,
+        // Progress is reported through the observer; keep the solver itself silent
+        verbosity: 0,
+        ipop_restarts: cmaes.ipop_restarts,
+        ipop_increase_factor: cmaes.ipop_increase_factor,
+        bipop_restarts: cmaes.bipop_restarts,
+        total_evals_budget: cmaes.total_evals_budget,
+        use_subrun_budgeting: cmaes.use_subrun_budgeting,
+        alpha_mu: Some(cmaes.alpha_mu),
+        hsig_threshold_factor: Some(cmaes.hsig_threshold_factor),
+        bipop_small_population_factor: Some(cmaes.bipop_small_population_factor),
+        bipop_small_budget_factor: Some(cmaes.bipop_small_budget_factor),
+        bipop_large_budget_factor: Some(cmaes.bipop_large_budget_factor),
+        bipop_large_pop_increase_factor: Some(cmaes.bipop_large_pop_increase_factor),
+        max_bound_iterations: Some(cmaes.max_bound_iterations),
+        eig_precision_threshold: Some(cmaes.eig_precision_threshold),
+        min_eig_value: Some(cmaes.min_eig_value),
+        matrix_op_threshold: Some(cmaes.matrix_op_threshold),
+        stagnation_limit: Some(cmaes.stagnation_limit),
+        // The solver starts at a quarter of the mean bound width, raised to `min_sigma`;
+        // on the unit box that makes the initial step `sigma0` of every parameter's width
+        min_sigma: Some(4.0 * cmaes.sigma0),
+    }
+}
+
+/// Run the canonical CMA-ES from `initial_mean` and return its best parameters, or `None`
+/// if the run was stopped.
+///
+/// The solver searches the unit box, each coordinate a fraction of its parameter's bound
+/// width, so `cmaes.sigma0` scales with the widths. `cmaes_lbfgsb` has no stop hook: once
+/// the run is stopped every evaluation returns `f64::INFINITY` without touching the model,
+/// so the remaining generations stagnate cheaply, and the caller falls back to
+/// [`CalibrationProblem::best`].
+fn run_cmaes(
+    problem: &CalibrationProblem<'_>,
+    config: &OptimizationConfig,
+    initial_mean: Option<Vec<f64>>,
+) -> Option<Vec<f64>> {
+    let bounds = problem.bounds();
+    let unit_box = vec![(0.0, 1.0); bounds.len()];
+    let to_params = |u: &[f64]| -> Vec<f64> {
+        u.iter()
+            .zip(bounds)
+            .map(|(u, (lo, hi))| lo + u * (hi - lo))
+            .collect()
+    };
+    let to_unit = |x: Vec<f64>| -> Vec<f64> {
+        x.iter()
+            .zip(bounds)
+            .map(|(x, (lo, hi))| if hi > lo { (x - lo) / (hi - lo) } else { 0.0 })
+            .collect()
+    };
+
+    let result = canonical_cmaes_optimize(
+        |u: &[f64]| problem.objective(&to_params(u)),
+        &unit_box,
+        canonical_cmaes_config(config, bounds.len()),
+        initial_mean.map(to_unit),
+    );
+    if problem.is_truncated() {
+        return None;
+    }
+    Some(to_params(&result.best_solution.1))
+}
+
+/// Projected L-BFGS minimisation of `f` over `bounds` using the exact gradient `grad`.
+///
+/// Search directions come from the usual two-loop recursion on the free variables; steps
+/// are projected back onto the box and accepted under an Armijo condition. Stops when the
+/// projected gradient's largest component falls below `tol`, when an iteration improves
+/// the objective by at most `obj_tol` relative to `|f|`, when `grad` returns `None` or
+/// once `stop` returns true. Returns the best `(f, x)` visited.
+#[allow(clippy::too_many_arguments)]
+pub fn lbfgsb_with_gradient<F, G, C, S>(
+    f: F,
+    grad: G,
+    bounds: &[(f64, f64)],
+    x0: &[f64],
+    max_iterations: usize,
+    tol: f64,
+    obj_tol: f64,
+    callback: Option<C>,
+    stop: S,
+) -> (f64, Vec<f64>)
+where
+    F: Fn(&[f64]) -> f64,
+    G: Fn(&[f64]) -> Option<Vec<f64>>,
+    C: Fn(&[f64], f64),
+    S: Fn() -> bool,
+{
+    const MEMORY: usize = 7;
+    const ARMIJO_C1: f64 = 1e-4;
+    const MAX_BACKTRACKS: usize = 40;
+
+    let n = x0.len();
+    let mut x = clamp_to_bounds(x0, bounds);
+    let mut fx = f(&x);
+    let mut g = match grad(&x) {
+        Some(g) if g.len() == n => g,
+        _ => return (fx, x),
+    };
+
+    // A variable is held at a bound while the gradient pushes it further outside the box
+    let is_active = |x: &[f64], g: &[f64], i: usize| {
+        let (lo, hi) = bounds[i];
+        (x[i] <= lo && g[i] > 0.0) || (x[i] >= hi && g[i] < 0.0)
+    };
+
+    let mut history: std::collections::VecDeque<(Vec<f64>, Vec<f64>, f64)> =
+        std::collections::VecDeque::with_capacity(MEMORY);
+
+    for _iter in 0..max_iterations {
+        if stop() {
+            break;
+        }
+        let projected: Vec<f64> = (0..n)
+            .map(|i| if is_active(&x, &g, i) { 0.0 } else { g[i] })
+            .collect();
+        if projected.iter().fold(0.0_f64, |m, v| m.max(v.abs())) < tol {
+            break;
+        }
+
+        // Two-loop recursion: d = -H * projected gradient
+        let mut q = projected.clone();
+        let mut alphas = Vec::with_capacity(history.len());
+        for (s, y, rho) in history.iter().rev() {
+            let alpha = rho * dot(s, &q);
+            for (qi, yi) in q.iter_mut().zip(y) {
+                *qi -= alpha * yi;
+            }
+            alphas.push(alpha);
+        }
+        if let Some((s, y, _)) = history.back() {
+            let gamma = dot(s, y) / dot(y, y).max(1e-300);
+            q.iter_mut().for_each(|qi| *qi *= gamma);
+        }
+        for ((s, y, rho), alpha) in history.iter().zip(alphas.iter().rev()) {
+            let beta = rho * dot(y, &q);
+            for (qi, si) in q.iter_mut().zip(s) {
+                *qi += (alpha - beta) * si;
+            }
+        }
+        let mut d: Vec<f64> = (0..n)
+            .map(|i| if is_active(&x, &g, i) { 0.0 } else { -q[i] })
+            .collect();
+
+        // Fall back to steepest descent if the quasi-Newton direction is not a descent direction
+        if dot(&d, &projected) >= 0.0 {
+            history.clear();
+            d = projected.iter().map(|v| -v).collect();
+        }
+
+        // Backtracking line search along the projected path
+        let mut step = 1.0;
+        let mut accepted = None;
+        for _ in 0..MAX_BACKTRACKS {
+            if stop() {
+                break;
+            }
+            let trial: Vec<f64> = x.iter().zip(&d).map(|(xi, di)| xi + step * di).collect();
+            let trial = clamp_to_bounds(&trial, bounds);
+            let decrease: f64 = g
+                .iter()
+                .zip(trial.iter().zip(&x))
+                .map(|(gi, (t, xi))| gi * (t - xi))
+                .sum();
+            let f_trial = f(&trial);
+            if f_trial.is_finite() && f_trial <= fx + ARMIJO_C1 * decrease {
+                accepted = Some((trial, f_trial));
+                break;
+            }
+            step *= 0.5;
+        }
+
+        let (x_new, f_new) = match accepted {
+            Some(v) => v,
+            None => break,
+        };
+        let g_new = match grad(&x_new) {
+            Some(g) if g.len() == n => g,
+            _ => break,
+        };
+
+        let s: Vec<f64> = x_new.iter().zip(&x).map(|(a, b)| a - b).collect();
+        let y: Vec<f64> = g_new.iter().zip(&g).map(|(a, b)| a - b).collect();
+        let sy = dot(&s, &y);
+        if sy > 1e-12 * dot(&y, &y).sqrt() * dot(&s, &s).sqrt() {
+            if history.len() == MEMORY {
+                history.pop_front();
+            }
+            history.push_back((s, y, 1.0 / sy));
+        } else {
+            // Curvature information is unreliable (e.g. a bound was hit): restart the memory
+            history.clear();
+        }
+
+        let f_change = fx - f_new;
+        x = x_new;
+        fx = f_new;
+        g = g_new;
+
+        if let Some(ref cb) = callback {
+            cb(&x, fx);
+        }
+
+        if f_change.abs() <= obj_tol * (fx + f_change).abs().max(fx.abs()) {
+            break;
+        }
+    }
+
+    (fx, x)
+}
+
+// ================================================================================================
+// NELDER–MEAD
+// ================================================================================================
+
+/// Bounded Nelder–Mead simplex search.
+///
+/// Uses `max_iterations` and `tolerance` from the configuration. Starts from the initial
+/// guess, or the centre of the parameter box when none is given.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NelderMeadOptimizer;
+
+impl Optimizer for NelderMeadOptimizer {
+    fn name(&self) -> &str {
+        "nelder_mead"
+    }
+
+    fn minimize(
+        &self,
+        problem: &CalibrationProblem<'_>,
+        config: &OptimizationConfig,
+        initial_guess: Option<&[f64]>,
+    ) -> (f64, Vec<f64>) {
+        let x0 = initial_guess
+            .map(|g| g.to_vec())
+            .unwrap_or_else(|| problem.midpoint());
+        nelder_mead(
+            |x| problem.objective(x),
+            problem.bounds(),
+            &x0,
+            config.max_iterations,
+            config.tolerance,
+            || problem.is_truncated(),
+        )
+    }
+}
+
+/// Minimise `f` with a Nelder–Mead simplex projected onto `bounds`.
+///
+/// The simplex is rebuilt around the best vertex once it collapses, which guards against
+/// the classic premature-convergence failure of the method. Stops early once `stop` returns
+/// true. Returns `(f_min, x_min)`.
+pub fn nelder_mead<F, S>(
+    f: F,
+    bounds: &[(f64, f64)],
+    x0: &[f64],
+    max_iterations: usize,
+    tol: f64,
+    stop: S,
+) -> (f64, Vec<f64>)
+where
+    F: Fn(&[f64]) -> f64,
+    S: Fn() -> bool,
+{
+    const ALPHA: f64 = 1.0; // reflection
+    const GAMMA: f64 = 2.0; // expansion
+    const RHO: f64 = 0.5; // contraction
+    const SHRINK: f64 = 0.5;
+    const MAX_RESTARTS: usize = 2;
+
+    let n = x0.len();
+    let mut best_x = clamp_to_bounds(x0, bounds);
+    let mut best_f = f(&best_x);
+    let mut iterations = 0usize;
+
+    for _restart in 0..=MAX_RESTARTS {
+        // Initial simplex: best point plus a 5% step along each axis, pointing inwards at bounds
+        let mut simplex: Vec<(f64, Vec<f64>)> = Vec::with_capacity(n + 1);
+        simplex.push((best_f, best_x.clone()));
+        for i in 0..n {
+            let mut x = best_x.clone();
+            let (lo, hi) = bounds[i];
+            let step = 0.05 * (hi - lo).max(1e-8);
+            x[i] = if x[i] + step <= hi {
+                x[i] + step
+            } else {
+                x[i] - step
+            };
+            simplex.push((f(&x), x));
+        }
+
+        let start_f = best_f;
+        while iterations < max_iterations && !stop() {
+            iterations += 1;
+            simplex.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+            let f_best = simplex[0].0;
+            let f_worst = simplex[n].0;
+            if (f_worst - f_best).abs() <= tol {
+                break;
+            }
+
+            // Centroid of all but the worst vertex
+            let mut centroid = vec![0.0; n];
+            for (_, x) in &simplex[..n] {
+                for (c, xi) in centroid.iter_mut().zip(x) {
+                    *c += xi / n as f64;
+                }
+            }
+
+            let worst = simplex[n].1.clone();
+            let along = |coef: f64| -> Vec<f64> {
+                let p: Vec<f64> = centroid
+                    .iter()
+                    .zip(&worst)
+                    .map(|(c, w)| c + coef * (c - w))
+                    .collect();
+                clamp_to_bounds(&p, bounds)
+            };
+
+            let xr = along(ALPHA);
+            let fr = f(&xr);
+
+            if fr < simplex[0].0 {
+                let xe = along(GAMMA);
+                let fe = f(&xe);
+                simplex[n] = if fe < fr { (fe, xe) } else { (fr, xr) };
+            } else if fr < simplex[n - 1].0 {
+                simplex[n] = (fr, xr);
+            } else {
+                let (xc, fc) = if fr < simplex[n].0 {
+                    let xc = along(RHO);
+                    let fc = f(&xc);
+                    (xc, fc)
+                } else {
+                    let xc = along(-RHO);
+                    let fc = f(&xc);
+                    (xc, fc)
+                };
+
+                if fc < simplex[n].0.min(fr) {
+                    simplex[n] = (fc, xc);
+                } else {
+                    // Shrink towards the best vertex
+                    let x_best = simplex[0].1.clone();
+                    for (fv, xv) in simplex.iter_mut().skip(1) {
+                        for (xi, bi) in xv.iter_mut().zip(&x_best) {
+                            *xi = bi + SHRINK * (*xi - bi);
+                        }
+                        *fv = f(xv);
+                    }
+                }
+            }
+        }
+
+        simplex.sort_by(|a, b| a.0.total_cmp(&b.0));
+        if simplex[0].0 < best_f {
+            best_f = simplex[0].0;
+            best_x = simplex[0].1.clone();
+        }
+
+        // Stop restarting once a restart no longer improves the objective
+        if iterations >= max_iterations || start_f - best_f <= tol || stop() {
+            break;
+        }
+    }
+
+    (best_f, best_x)
+}
+
+// ================================================================================================
+// DIFFERENTIAL EVOLUTION
+// ================================================================================================
+
+/// Differential evolution (DE/rand/1/bin) population search.
+///
+/// Uses `pop_size`, `max_gen`, `tolerance` and `cmaes.seed` from the configuration. When an
+/// initial guess is given it replaces one member of the initial population.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DifferentialEvolutionOptimizer;
+
+impl Optimizer for DifferentialEvolutionOptimizer {
+    fn name(&self) -> &str {
+        "differential_evolution"
+    }
+
+    fn minimize(
+        &self,
+        problem: &CalibrationProblem<'_>,
+        config: &OptimizationConfig,
+        initial_guess: Option<&[f64]>,
+    ) -> (f64, Vec<f64>) {
+        differential_evolution(
+            |x| problem.objective(x),
+            problem.bounds(),
+            initial_guess,
+            config.pop_size,
+            config.max_gen,
+            config.tolerance,
+            config.cmaes.seed.unwrap_or(123456),
+            || problem.is_truncated(),
+        )
+    }
+}
+
+/// Minimise `f` over `bounds` with DE/rand/1/bin. Returns `(f_min, x_min)`.
+///
+/// Stops after `max_gen` generations, once the spread of population objectives falls
+/// below `tol` or once `stop` returns true. Populations smaller than the four members
+/// DE/rand/1 needs are raised to four; with no parameters `f` is evaluated once at the
+/// empty point.
+#[allow(clippy::too_many_arguments)]
+pub fn differential_evolution<F, S>(
+    f: F,
+    bounds: &[(f64, f64)],
+    x0: Option<&[f64]>,
+    pop_size: usize,
+    max_gen: usize,
+    tol: f64,
+    seed: u64,
+    stop: S,
+) -> (f64, Vec<f64>)
+where
+    F: Fn(&[f64]) -> f64,
+    S: Fn() -> bool,
+{
+    const DIFFERENTIAL_WEIGHT: f64 = 0.8;
+    const CROSSOVER_RATE: f64 = 0.9;
+
+    let n = bounds.len();
+    if n == 0 {
+        return (f(&[]), Vec::new());
+    }
+    // A target plus three distinct donors
+    let np = pop_size.max(4);
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let mut population: Vec<Vec<f64>> = (0..np)
+        .map(|_| {
+            bounds
+                .iter()
+                .map(|&(lo, hi)| if hi > lo { rng.gen_range(lo..hi) } else { lo })
+                .collect()
+        })
+        .collect();
+    if let Some(guess) = x0 {
+        population[0] = clamp_to_bounds(guess, bounds);
+    }
+    let mut fitness: Vec<f64> = population.iter().map(|x| f(x)).collect();
+
+    'generations: for _gen in 0..max_gen {
+        for i in 0..np {
+            if stop() {
+                break 'generations;
+            }
+            // Three distinct members, all different from the target
+            let mut pick = || loop {
+                let j = rng.gen_range(0..np);
+                if j != i {
+                    break j;
+                }
+            };
+            let r1 = pick();
+            let mut r2 = pick();
+            while r2 == r1 {
+                r2 = pick();
+            }
+            let mut r3 = pick();
+            while r3 == r1 || r3 == r2 {
+                r3 = pick();
+            }
+
+            let j_rand = rng.gen_range(0..n);
+            let trial: Vec<f64> = (0..n)
+                .map(|j| {
+                    if j == j_rand || rng.gen::<f64>() < CROSSOVER_RATE {
+                        let v = population[r1][j]
+                            + DIFFERENTIAL_WEIGHT * (population[r2][j] - population[r3][j]);
+                        // Bounce back between the base vector and the violated bound
+                        let (lo, hi) = bounds[j];
+                        if v < lo {
+                            lo + rng.gen::<f64>() * (population[r1][j] - lo)
+                        } else if v > hi {
+                            hi - rng.gen::<f64>() * (hi - population[r1][j])
+                        } else {
+                            v
+                        }
+                    } else {
+                        population[i][j]
+                    }
+                })
+                .collect();
+
+            let f_trial = f(&trial);
+            if f_trial <= fitness[i] {
+                population[i] = trial;
+                fitness[i] = f_trial;
+            }
+        }
+
+        let f_min = fitness.iter().copied().fold(f64::INFINITY, f64::min);
+        let f_max = fitness.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        if f_max - f_min <= tol {
+            break;
+        }
+    }
+
+    let best = (0..np)
+        .min_by(|&a, &b| fitness[a].total_cmp(&fitness[b]))
+        .unwrap_or(0);
+    (fitness[best], population[best].clone())
+}
+
+// ================================================================================================
+// LEVENBERG–MARQUARDT
+// ================================================================================================
+
+/// Levenberg–Marquardt on the calibrator's residual vector.
+///
+/// Requires [`ModelCalibrator::evaluate_residuals`]; calibrators without residuals are solved
+/// with the single residual `sqrt(objective)`. The Jacobian is approximated by forward
+/// differences. Uses `max_iterations` and `tolerance` from the configuration. The returned
+/// objective is re-evaluated with [`ModelCalibrator::evaluate_objective`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LevenbergMarquardtOptimizer;
+
+impl Optimizer for LevenbergMarquardtOptimizer {
+    fn name(&self) -> &str {
+        "levenberg_marquardt"
+    }
+
+    fn minimize(
+        &self,
+        problem: &CalibrationProblem<'_>,
+        config: &OptimizationConfig,
+        initial_guess: Option<&[f64]>,
+    ) -> (f64, Vec<f64>) {
+        let x0 = initial_guess
+            .map(|g| g.to_vec())
+            .unwrap_or_else(|| problem.midpoint());
+
+        let residual_fn = |x: &[f64]| -> Option<Vec<f64>> {
+            problem.residuals(x).or_else(|| {
+                let obj = problem.objective(x);
+                (obj.is_finite() && obj < 1.0e12).then(|| vec![obj.max(0.0).sqrt()])
+            })
+        };
+
+        let x = levenberg_marquardt(
+            residual_fn,
+            problem.bounds(),
+            &x0,
+            config.max_iterations,
+            config.tolerance,
+            || problem.is_truncated(),
+        )
+        .map(|(_, x)| x)
+        .unwrap_or_else(|| clamp_to_bounds(&x0, problem.bounds()));
+
+        (problem.objective(&x), x)
+    }
+}
+
+/// Minimise `0.5 * ||r(x)||²` over `bounds` with projected Levenberg–Marquardt steps.
+///
+/// `residuals` returns `None` for parameter sets the model rejects; such trial steps are
+/// treated as failed. Iterations end early once `stop` returns true. Returns `None` if the
+/// starting point itself is rejected, otherwise `(cost, x_min)`.
+pub fn levenberg_marquardt<R, S>(
+    residuals: R,
+    bounds: &[(f64, f64)],
+    x0: &[f64],
+    max_iterations: usize,
+    tol: f64,
+    stop: S,
+) -> Option<(f64, Vec<f64>)>
+where
+    R: Fn(&[f64]) -> Option<Vec<f64>>,
+    S: Fn() -> bool,
+{
+    let n = x0.len();
+    let cost_of = |r: &[f64]| 0.5 * r.iter().map(|v| v * v).sum::<f64>();
+
+    let mut x = clamp_to_bounds(x0, bounds);
+    let mut r = residuals(&x)?;
+    let mut cost = cost_of(&r);
+    let mut mu: Option<f64> = None;
+
+    for _iter in 0..max_iterations {
+        if stop() {
+            break;
+        }
+        // Forward-difference Jacobian, stepping inwards at an upper bound
+        let mut jac = vec![vec![0.0; n]; r.len()];
+        for j in 0..n {
+            let h = 1e-7 * x[j].abs().max(1.0);
+            let mut xp = x.clone();
+            let step = if xp[j] + h <= bounds[j].1 { h } else { -h };
+            xp[j] += step;
+            let rp = match residuals(&xp) {
+                Some(rp) if rp.len() == r.len() => rp,
+                _ => continue,
+            };
+            for (row, (rpi, ri)) in jac.iter_mut().zip(rp.iter().zip(&r)) {
+                row[j] = (rpi - ri) / step;
+            }
+        }
+
+        // Normal equations J^T J and gradient J^T r
+        let mut jtj = vec![vec![0.0; n]; n];
+        let mut grad = vec![0.0; n];
+        for (row, ri) in jac.iter().zip(&r) {
+            for a in 0..n {
+                grad[a] += row[a] * ri;
+                for b in 0..n {
+                    jtj[a][b] += row[a] * row[b];
+                }
+            }
+        }
+
+        let damping = *mu
+            .get_or_insert_with(|| 1e-3 * (0..n).map(|i| jtj[i][i]).fold(0.0, f64::max).max(1e-12));
+
+        let mut lhs = jtj.clone();
+        for (i, row) in lhs.iter_mut().enumerate() {
+            row[i] += damping * jtj[i][i].max(1e-12);
+        }
+        let neg_grad: Vec<f64> = grad.iter().map(|g| -g).collect();
+
+        let accepted = solve_linear_system(lhs, neg_grad).and_then(|delta| {
+            let trial: Vec<f64> = x.iter().zip(&delta).map(|(xi, d)| xi + d).collect();
+            let trial = clamp_to_bounds(&trial, bounds);
+            let r_trial = residuals(&trial)?;
+            let cost_trial = cost_of(&r_trial);
+            (cost_trial < cost).then_some((trial, r_trial, cost_trial))
+        });
+
+        match accepted {
+            Some((trial, r_trial, cost_trial)) => {
+                let step_norm = trial
+                    .iter()
+                    .zip(&x)
+                    .map(|(a, b)| (a - b).powi(2))
+                    .sum::<f64>()
+                    .sqrt();
+                let improvement = cost - cost_trial;
+                x = trial;
+                r = r_trial;
+                cost = cost_trial;
+                mu = Some(damping * 0.3);
+                if improvement <= tol * (cost + tol) || step_norm <= tol * 1e-3 {
+                    break;
+                }
+            }
+            None => {
+                mu = Some(damping * 10.0);
+                if damping > 1e16 {
+                    break;
+                }
+            }
+        }
+    }
+
+    Some((cost, x))
+}
+
+// ================================================================================================
+// HELPERS
+// ================================================================================================
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn clamp_to_bounds(x: &[f64], bounds: &[(f64, f64)]) -> Vec<f64> {
+    x.iter()
+        .zip(bounds)
+        .map(|(v, &(lo, hi))| v.clamp(lo, hi))
+        .collect()
+}
+
+/// Solve `a * x = b` by Gaussian elimination with partial pivoting
+fn solve_linear_system(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> Option<Vec<f64>> {
+    let n = b.len();
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[pivot][col].abs() < 1e-300 {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        for row in (col + 1)..n {
+            let factor = a[row][col] / a[col][col];
+            let (upper, lower) = a.split_at_mut(row);
+            for (target, source) in lower[0][col..].iter_mut().zip(&upper[col][col..]) {
+                *target -= factor * source;
+            }
+            b[row] -= factor * b[col];
+        }
+    }
+
+    let mut x = vec![0.0; n];
+    for row in (0..n).rev() {
+        let tail: f64 = ((row + 1)..n).map(|k| a[row][k] * x[k]).sum();
+        x[row] = (b[row] - tail) / a[row][row];
+    }
+    x.iter().all(|v| v.is_finite()).then_some(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calibration::config::CmaEsConfig;
+
+    fn rosenbrock(x: &[f64]) -> f64 {
+        (1.0 - x[0]).powi(2) + 100.0 * (x[1] - x[0] * x[0]).powi(2)
+    }
+
+    #[test]
+    fn test_nelder_mead_rosenbrock() {
+        let bounds = [(-2.0, 2.0), (-1.0, 3.0)];
+        let (f, x) = nelder_mead(rosenbrock, &bounds, &[-1.2, 1.0], 5000, 1e-14, || false);
+        assert!(f < 1e-6, "f = {}", f);
+        assert!((x[0] - 1.0).abs() < 1e-2 && (x[1] - 1.0).abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_nelder_mead_respects_bounds() {
+        // Unconstrained minimum at (3, 3) lies outside the box
+        let bounds = [(0.0, 1.0), (0.0, 1.0)];
+        let f = |x: &[f64]| (x[0] - 3.0).powi(2) + (x[1] - 3.0).powi(2);
+        let (_, x) = nelder_mead(f, &bounds, &[0.5, 0.5], 1000, 1e-12, || false);
+        assert!((x[0] - 1.0).abs() < 1e-6 && (x[1] - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_differential_evolution_rosenbrock() {
+        let bounds = [(-2.0, 2.0), (-1.0, 3.0)];
+        let (f, x) = differential_evolution(rosenbrock, &bounds, None, 40, 400, 1e-12, 7, || false);
+        assert!(f < 1e-4, "f = {}", f);
+        assert!(x.iter().zip(&bounds).all(|(v, b)| *v >= b.0 && *v <= b.1));
+    }
+
+    #[test]
+    fn test_differential_evolution_without_parameters() {
+        let (f, x) = differential_evolution(|_| 1.5, &[], None, 40, 10, 1e-12, 7, || false);
+        assert_eq!(f, 1.5);
+        assert!(x.is_empty());
+    }
+
+    #[test]
+    fn test_differential_evolution_small_population() {
+        let bounds = [(-2.0, 2.0), (-1.0, 3.0)];
+        for pop_size in 0..4 {
+            let (f, x) =
+                differential_evolution(rosenbrock, &bounds, None, pop_size, 50, 1e-12, 7, || false);
+            assert!(f.is_finite() && f <= rosenbrock(&x));
+            assert!(x.iter().zip(&bounds).all(|(v, b)| *v >= b.0 && *v <= b.1));
+        }
+    }
+
+    #[test]
+    fn test_canonical_cmaes_config_uses_tuning() {
+        let mut config = OptimizationConfig::fast();
+        config.cmaes.max_evaluations = 300;
+        config.cmaes.c1 = 0.05;
+        config.cmaes.stagnation_limit = 40;
+        let canonical = canonical_cmaes_config(&config, 5);
+        assert_eq!(canonical.max_generations, 10); // 300 evaluations / 30 per generation
+        assert_eq!(canonical.c1, Some(0.05));
+        assert_eq!(canonical.c_mu, None);
+        assert_eq!(canonical.stagnation_limit, Some(40));
+        assert_eq!(canonical.min_sigma, Some(4.0 * config.cmaes.sigma0));
+
+        // `max_iterations` caps the generations too
+        config.max_iterations = 7;
+        assert_eq!(canonical_cmaes_config(&config, 5).max_generations, 7);
+    }
+
+    /// Quadratic bowl that records every point it is evaluated at
+    struct Bowl {
+        bounds: Vec<(f64, f64)>,
+        points: Mutex<Vec<Vec<f64>>>,
+    }
+
+    impl ModelCalibrator for Bowl {
+        fn model_name(&self) -> &str {
+            "bowl"
+        }
+
+        fn param_count(&self) -> usize {
+            self.bounds.len()
+        }
+
+        fn param_bounds(&self) -> &[(f64, f64)] {
+            &self.bounds
+        }
+
+        fn evaluate_objective(&self, x: &[f64], _data: &[MarketDataRow]) -> f64 {
+            self.points.lock().unwrap().push(x.to_vec());
+            x.iter()
+                .zip(&self.bounds)
+                .map(|(v, (lo, hi))| ((v - lo) / (hi - lo) - 0.3).powi(2))
+                .sum()
+        }
+
+        fn price_options(
+            &self,
+            _market_data: &[MarketDataRow],
+            _best_params: &[f64],
+            _config: &OptimizationConfig,
+        ) -> Vec<crate::calibration::types::PricingResult> {
+            Vec::new()
+        }
+
+        fn param_names(&self) -> Vec<&str> {
+            vec!["x"; self.bounds.len()]
+        }
+
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+    }
+
+    #[test]
+    fn test_cmaes_steps_scale_with_bounds() {
+        // Widths three orders of magnitude apart get the same relative first-generation spread
+        let bowl = Bowl {
+            bounds: vec![(0.0, 1.0), (0.0, 1000.0)],
+            points: Mutex::new(Vec::new()),
+        };
+        let problem = CalibrationProblem::new(&bowl, &[]);
+        let config = OptimizationConfig {
+            pop_size: 200,
+            max_gen: 1,
+            cmaes: CmaEsConfig {
+                bipop_restarts: 0,
+                ..CmaEsConfig::default()
+            },
+            ..OptimizationConfig::default()
+        };
+        run_cmaes(&problem, &config, Some(vec![0.5, 500.0])).unwrap();
+
+        let points = bowl.points.into_inner().unwrap();
+        assert_eq!(points.len(), 200);
+        let spread = |i: usize| {
+            let width = bowl.bounds[i].1 - bowl.bounds[i].0;
+            let mean = points.iter().map(|p| p[i]).sum::<f64>() / points.len() as f64;
+            let var = points.iter().map(|p| (p[i] - mean).powi(2)).sum::<f64>();
+            (var / points.len() as f64).sqrt() / width
+        };
+        let ratio = spread(0) / spread(1);
+        assert!(
+            (0.8..1.25).contains(&ratio),
+            "relative spreads {} vs {}",
+            spread(0),
+            spread(1)
+        );
+        assert!(spread(0) > 0.15, "spread {}", spread(0));
+    }
+
+    #[test]
+    fn test_levenberg_marquardt_curve_fit() {
+        // Fit y = p0 * exp(p1 * t) to exact data generated with (2.0, -0.5)
+        let ts: Vec<f64> = (0..10).map(|i| i as f64 * 0.3).collect();
+        let ys: Vec<f64> = ts.iter().map(|t| 2.0 * (-0.5 * t).exp()).collect();
+        let residuals = |p: &[f64]| -> Option<Vec<f64>> {
+            Some(
+                ts.iter()
+                    .zip(&ys)
+                    .map(|(t, y)| p[0] * (p[1] * t).exp() - y)
+                    .collect(),
+            )
+        };
+        let bounds = [(0.0, 10.0), (-5.0, 5.0)];
+        let (cost, p) =
+            levenberg_marquardt(residuals, &bounds, &[1.0, 0.0], 200, 1e-15, || false).unwrap();
+        assert!(cost < 1e-12, "cost = {}", cost);
+        assert!((p[0] - 2.0).abs() < 1e-5 && (p[1] + 0.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_lbfgsb_with_gradient_rosenbrock() {
+        let grad = |x: &[f64]| {
+            Some(vec![
+                -2.0 * (1.0 - x[0]) - 400.0 * x[0] * (x[1] - x[0] * x[0]),
+                200.0 * (x[1] - x[0] * x[0]),
+            ])
+        };
+        let bounds = [(-2.0, 2.0), (-1.0, 3.0)];
+        let (f, x) = lbfgsb_with_gradient(
+            rosenbrock,
+            grad,
+            &bounds,
+            &[-1.2, 1.0],
+            500,
+            1e-10,
+            0.0,
+            None::<fn(&[f64], f64)>,
+            || false,
+        );
+        assert!(f < 1e-12, "f = {}", f);
+        assert!((x[0] - 1.0).abs() < 1e-5 && (x[1] - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_lbfgsb_with_gradient_active_bound() {
+        // Minimum at x0 = 3 is outside the box; x1 is free
+        let f = |x: &[f64]| (x[0] - 3.0).powi(2) + (x[1] + 0.5).powi(2);
+        let grad = |x: &[f64]| Some(vec![2.0 * (x[0] - 3.0), 2.0 * (x[1] + 0.5)]);
+        let bounds = [(0.0, 1.0), (-1.0, 1.0)];
+        let (_, x) = lbfgsb_with_gradient(
+            f,
+            grad,
+            &bounds,
+            &[0.2, 0.8],
+            100,
+            1e-10,
+            0.0,
+            None::<fn(&[f64], f64)>,
+            || false,
+        );
+        assert_eq!(x[0], 1.0);
+        assert!((x[1] + 0.5).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_owned_loops_honour_stop() {
+        let bounds = [(-2.0, 2.0), (-1.0, 3.0)];
+        let calls = AtomicUsize::new(0);
+        let f = |x: &[f64]| {
+            calls.fetch_add(1, Ordering::Relaxed);
+            rosenbrock(x)
+        };
+        let stop = || calls.load(Ordering::Relaxed) >= 10;
+        let grad = |x: &[f64]| {
+            Some(vec![
+                -2.0 * (1.0 - x[0]) - 400.0 * x[0] * (x[1] - x[0] * x[0]),
+                200.0 * (x[1] - x[0] * x[0]),
+            ])
+        };
+        let residuals = |x: &[f64]| {
+            calls.fetch_add(1, Ordering::Relaxed);
+            Some(vec![1.0 - x[0], 10.0 * (x[1] - x[0] * x[0])])
+        };
+
+        // Each loop returns within one iteration of the stop flag being raised
+        nelder_mead(f, &bounds, &[-1.2, 1.0], 5000, 0.0, stop);
+        assert!(calls.swap(0, Ordering::Relaxed) < 15);
+        differential_evolution(f, &bounds, None, 6, 400, 0.0, 7, stop);
+        assert!(calls.swap(0, Ordering::Relaxed) <= 10);
+        levenberg_marquardt(residuals, &bounds, &[-1.2, 1.0], 5000, 0.0, stop).unwrap();
+        assert!(calls.swap(0, Ordering::Relaxed) < 15);
+        lbfgsb_with_gradient(
+            f,
+            grad,
+            &bounds,
+            &[-1.2, 1.0],
+            5000,
+            0.0,
+            0.0,
+            None::<fn(&[f64], f64)>,
+            stop,
+        );
+        assert!(calls.swap(0, Ordering::Relaxed) <= 10);
+    }
+
+    #[test]
+    fn test_optimizer_kind_build() {
+        assert_eq!(OptimizerKind::CmaesLbfgsb.build().name(), "cmaes_lbfgsb");
+        assert_eq!(OptimizerKind::NelderMead.build().name(), "nelder_mead");
+        assert_eq!(
+            OptimizerKind::DifferentialEvolution.build().name(),
+            "differential_evolution"
+        );
+        assert_eq!(
+            OptimizerKind::LevenbergMarquardt.build().name(),
+            "levenberg_marquardt"
+        );
+    }
+}
@@ -1,8 +1,11 @@
 use crate::calibration::config::OptimizationConfig;
-use crate::calibration::types::{MarketDataRow, ModelCalibrator};
+use crate::calibration::control::CalibrationControl;
+use crate::calibration::observer::{CalibrationEvent, CalibrationStage, LogObserver};
+use crate::calibration::optimizer::{CalibrationProblem, Optimizer};
+use crate::calibration::types::{CalibrationOutcome, MarketDataRow, ModelCalibrator};
+use std::sync::Arc;
+use std::time::Instant;
 // Note: HashMap removed as param_map is no longer used
-use cmaes_lbfgsb::cmaes::{canonical_cmaes_optimize, CmaesCanonicalConfig};
-use cmaes_lbfgsb::lbfgsb_optimize::lbfgsb_optimize;
 
 /// A simplified calibration process for surface models
 pub struct CalibrationProcess {
@@ -10,6 +13,7 @@ pub struct CalibrationProcess {
     config: OptimizationConfig,
     market_data: Vec<MarketDataRow>,
     initial_guess: Option<Vec<f64>>,
+    control: CalibrationControl,
 }
 
 impl CalibrationProcess {
@@ -23,6 +27,7 @@ impl CalibrationProcess {
             config,
             market_data,
             initial_guess: None,
+            control: CalibrationControl::default(),
         }
     }
 
@@ -32,233 +37,208 @@ impl CalibrationProcess {
         self
     }
 
+    /// Set a deadline and/or cancellation token for the run
+    pub fn with_control(mut self, control: CalibrationControl) -> Self {
+        self.control = control;
+        self
+    }
+
     /// Run the calibration process and return the best parameters
     pub fn run(&self) -> (f64, Vec<f64>) {
-        let (best_obj, best_params) = calibrate_model(
+        let outcome = self.run_detailed();
+        (outcome.objective, outcome.params)
+    }
+
+    /// Run the calibration process and return the full outcome, including truncation
+    pub fn run_detailed(&self) -> CalibrationOutcome {
+        calibrate_model(
             &*self.model,
             &self.market_data,
             &self.config,
             self.initial_guess.clone(),
-        );
-        (best_obj, best_params)
+            &self.control,
+        )
     }
 }
 
-/// Advanced optimization function combining CMA-ES for global search and L-BFGS-B for local refinement.
-/// Uses relaxed bounds and objective function for global search, then standard bounds for refinement.
+/// Calibrate a model with the optimizer backend selected in `config.optimizer`.
+///
+/// The default backend combines CMA-ES for global search with L-BFGS-B for local refinement.
+/// The run stops early when `control` asks it to; the outcome then carries the best solution
+/// evaluated so far and is marked as truncated. Progress is reported to `control.observer`,
+/// or logged through the `log` facade when there is none and `config.cmaes.verbosity > 0`.
 pub fn calibrate_model(
     model: &dyn ModelCalibrator,
     market_data: &[MarketDataRow],
     config: &OptimizationConfig,
     initial_guess: Option<Vec<f64>>,
-) -> (f64, Vec<f64>) {
-    // Standard bounds and objective used for L-BFGS-B
-    let bounds = model.param_bounds();
-    let obj_fn = |x: &[f64]| model.evaluate_objective(x, market_data);
-
-    // 1) CMA-ES approach, either a "mini CMA-ES" around the initial guess or full CMA-ES if none provided.
-    // Use relaxed bounds and objective function for the global search
-    let (best_obj, best_sol) = {
-        // Use the same bounds and objective for CMA-ES
-        let relaxed_bounds = model.param_bounds();
-        let relaxed_obj_fn = |x: &[f64]| model.evaluate_objective(x, market_data);
-
-        // Prepare CMA-ES config with all the sophisticated settings
-        let cmaes_config = CmaesCanonicalConfig {
-            population_size: config.pop_size,
-            max_generations: config.max_gen,
-            seed: config.cmaes.seed.unwrap_or(123456),
-            c1: None, // Use defaults for now - could be added to config later
-            c_mu: None,
-            c_sigma: None,
-            d_sigma: None,
-            parallel_eval: config.cmaes.parallel_eval,
-            verbosity: config.cmaes.verbosity,
-            ipop_restarts: config.cmaes.ipop_restarts,
-            ipop_increase_factor: config.cmaes.ipop_increase_factor,
-            bipop_restarts: config.cmaes.bipop_restarts,
-            total_evals_budget: config.cmaes.total_evals_budget,
-            use_subrun_budgeting: config.cmaes.use_subrun_budgeting,
-            alpha_mu: None,
-            hsig_threshold_factor: None,
-            bipop_small_population_factor: None,
-            bipop_small_budget_factor: None,
-            bipop_large_budget_factor: None,
-            bipop_large_pop_increase_factor: None,
-            max_bound_iterations: None,
-            eig_precision_threshold: None,
-            min_eig_value: None,
-            matrix_op_threshold: None,
-            stagnation_limit: None,
-            min_sigma: None,
-        };
-
-        // If we have an initial guess, check if we should run mini CMA-ES or go straight to L-BFGS-B
-        if let Some(ref guess) = initial_guess {
-            // Check if mini_cmaes_on_refinement is enabled
-            let use_mini_cmaes = config.cmaes.mini_cmaes_on_refinement;
-
-            if use_mini_cmaes {
-                if config.cmaes.verbosity > 0 {
-                    println!(
-                        "Using provided initial guess => launching mini CMA-ES around it. \
-                         Then local L-BFGS refinement."
-                    );
-                }
-
-                // Evaluate the guess if you want to log it
-                let guess_obj = relaxed_obj_fn(guess);
-                if config.cmaes.verbosity > 0 {
-                    println!("  Initial guess objective = {:.6}", guess_obj);
-                }
-
-                // Run mini-CMA-ES
-                let cmaes_result = canonical_cmaes_optimize(
-                    relaxed_obj_fn,
-                    relaxed_bounds,
-                    cmaes_config,
-                    // We pass the guess as the initial distribution center
-                    Some(guess.clone()),
-                );
-
-                // Get best solution from relaxed objective, evaluate with standard objective
-                let (_, relaxed_params) = cmaes_result.best_solution;
-                let standard_obj = obj_fn(&relaxed_params);
-                (standard_obj, relaxed_params)
-            } else {
-                // Skip mini CMA-ES and use the initial guess directly for L-BFGS-B
-                if config.cmaes.verbosity > 0 {
-                    println!(
-                        "Using provided initial guess => skipping mini CMA-ES and proceeding directly to L-BFGS-B."
-                    );
-                }
-
-                // Evaluate the guess to get initial objective value
-                let guess_obj = obj_fn(guess);
-                if config.cmaes.verbosity > 0 {
-                    println!("  Initial guess objective = {:.6}", guess_obj);
-                }
-
-                (guess_obj, guess.clone())
-            }
-        } else {
-            // Otherwise do the full CMA-ES as before
-            if config.cmaes.verbosity > 0 {
-                println!("No initial guess provided => running full CMA-ES with BIPOP restarts");
-            }
-
-            let cmaes_result =
-                canonical_cmaes_optimize(relaxed_obj_fn, relaxed_bounds, cmaes_config, None);
-
-            // Get best solution from relaxed objective, evaluate with standard objective
-            let (_, relaxed_params) = cmaes_result.best_solution;
-            let standard_obj = obj_fn(&relaxed_params);
-            (standard_obj, relaxed_params)
-        }
-    };
-
-    // 2) Local refinement of the best solution with L-BFGS-B (if enabled)
-    if config.cmaes.lbfgsb_enabled {
-        if config.cmaes.verbosity > 0 {
-            println!("Running L-BFGS-B refinement on best CMA-ES solution...");
-        }
-
-        let mut refined_solution = best_sol.clone();
-        let refine_res = lbfgsb_optimize(
-            &mut refined_solution,
-            bounds,
-            &obj_fn,
-            config.cmaes.lbfgsb_max_iterations,
-            config.tolerance,
-            if config.cmaes.verbosity >= 1 {
-                Some(|_current_x: &[f64], current_obj: f64| {
-                    println!("L-BFGS-B iteration => objective = {:.6}", current_obj);
-                })
-            } else {
-                None
-            },
-            None, // Use default config
-        );
+    control: &CalibrationControl,
+) -> CalibrationOutcome {
+    let optimizer = config.optimizer.build();
+    calibrate_model_with(
+        &*optimizer,
+        model,
+        market_data,
+        config,
+        initial_guess,
+        control,
+    )
+}
 
-        match refine_res {
-            Ok((loc_obj, loc_sol)) => {
-                if loc_obj < best_obj {
-                    if config.cmaes.verbosity > 0 {
-                        println!(
-                            "L-BFGS-B improved objective: {:.6} -> {:.6}",
-                            best_obj, loc_obj
-                        );
-                    }
-                    (loc_obj, loc_sol)
-                } else {
-                    if config.cmaes.verbosity > 0 {
-                        println!("L-BFGS-B did not improve objective, keeping CMA-ES solution");
-                    }
-                    (best_obj, best_sol)
-                }
-            }
-            Err(e) => {
-                if config.cmaes.verbosity > 0 {
-                    println!("L-BFGS-B failed: {:?}, keeping CMA-ES solution", e);
-                }
-                (best_obj, best_sol)
-            }
-        }
-    } else {
-        if config.cmaes.verbosity > 0 {
-            println!("L-BFGS-B refinement disabled, using CMA-ES solution directly");
-        }
-        (best_obj, best_sol)
-    }
+/// Calibrate a model with an explicit optimizer backend, ignoring `config.optimizer`.
+pub fn calibrate_model_with(
+    optimizer: &dyn Optimizer,
+    model: &dyn ModelCalibrator,
+    market_data: &[MarketDataRow],
+    config: &OptimizationConfig,
+    initial_guess: Option<Vec<f64>>,
+    control: &CalibrationControl,
+) -> CalibrationOutcome {
+    let started = Instant::now();
+    let control = observed_control(control, config);
+    let outcome = solve(
+        optimizer,
+        model,
+        market_data,
+        config,
+        initial_guess,
+        &control,
+    );
+    emit_finished(&control, &outcome, started);
+    outcome
 }
 
 /// Generic adaptive calibration wrapper
+///
+/// Repeats the calibration while the solution sits near a bound, expanding the bounds each
+/// time. A deadline or cancellation in `control` ends the loop after the current iteration.
 pub fn calibrate_model_adaptive(
     mut model: Box<dyn ModelCalibrator>,
     market_data: &[MarketDataRow],
     config: &OptimizationConfig,
     initial_guess: Option<Vec<f64>>,
-) -> (f64, Vec<f64>, Vec<(f64, f64)>) {
+    control: &CalibrationControl,
+) -> CalibrationOutcome {
     if !config.adaptive_bounds.enabled {
-        let (obj, params) = calibrate_model(&*model, market_data, config, initial_guess);
-        let bounds = model.param_bounds().to_vec();
-        return (obj, params, bounds);
+        return calibrate_model(&*model, market_data, config, initial_guess, control);
     }
 
+    let started = Instant::now();
+    let control = observed_control(control, config);
+    let optimizer = config.optimizer.build();
+
     let mut best_obj = f64::MAX;
     let mut best_params = Vec::new();
+    let mut evaluations = 0;
+    let mut truncated = false;
 
     for iter in 0..config.adaptive_bounds.max_iterations {
-        let (obj, params) = calibrate_model(&*model, market_data, config, initial_guess.clone());
-        if obj < best_obj {
-            best_obj = obj;
-            best_params = params.clone();
+        let stage = CalibrationStage::AdaptiveIteration {
+            iteration: iter + 1,
+        };
+        control.emit(CalibrationEvent::StageStarted { stage });
+
+        let outcome = solve(
+            &*optimizer,
+            &*model,
+            market_data,
+            config,
+            initial_guess.clone(),
+            &control,
+        );
+        evaluations += outcome.evaluations;
+        if outcome.objective < best_obj || best_params.is_empty() {
+            best_obj = outcome.objective;
+            best_params = outcome.params.clone();
+        }
+        control.emit(CalibrationEvent::StageFinished {
+            stage,
+            objective: outcome.objective,
+            evaluations,
+        });
+
+        if outcome.truncated || control.should_stop() {
+            truncated = true;
+            break;
         }
+
         let adjusted = model.expand_bounds_if_needed(
-            &params,
+            &outcome.params,
             config.adaptive_bounds.proximity_threshold,
             config.adaptive_bounds.expansion_factor,
         );
+        if !adjusted {
+            break;
+        }
+        control.emit(CalibrationEvent::BoundsExpanded {
+            iteration: iter + 1,
+            bounds: model.param_bounds().to_vec(),
+        });
+    }
 
-        if config.cmaes.verbosity > 0 {
-            if adjusted {
-                println!(
-                    "Adaptive iteration {}: Expanded bounds for next iteration",
-                    iter + 1
-                );
-            } else {
-                println!(
-                    "Adaptive iteration {}: No expansion needed, stopping early",
-                    iter + 1
-                );
+    let outcome = CalibrationOutcome {
+        objective: best_obj,
+        params: best_params,
+        bounds: model.param_bounds().to_vec(),
+        evaluations,
+        truncated,
+    };
+    emit_finished(&control, &outcome, started);
+    outcome
+}
+
+/// Run one optimizer pass, falling back to the best evaluated point when truncated
+fn solve(
+    optimizer: &dyn Optimizer,
+    model: &dyn ModelCalibrator,
+    market_data: &[MarketDataRow],
+    config: &OptimizationConfig,
+    initial_guess: Option<Vec<f64>>,
+    control: &CalibrationControl,
+) -> CalibrationOutcome {
+    let problem = CalibrationProblem::new(model, market_data).with_control(control.clone());
+    let (mut objective, mut params) =
+        optimizer.minimize(&problem, config, initial_guess.as_deref());
+
+    let truncated = problem.is_truncated();
+    if truncated || !objective.is_finite() {
+        // The optimizer only saw infinities after the stop; fall back to the best point
+        // that was actually evaluated.
+        if let Some((best_obj, best_params)) = problem.best() {
+            if !objective.is_finite() || best_obj < objective {
+                objective = best_obj;
+                params = best_params;
             }
         }
+    }
 
-        if !adjusted {
-            break;
-        }
+    CalibrationOutcome {
+        objective,
+        params,
+        bounds: model.param_bounds().to_vec(),
+        evaluations: problem.evaluations(),
+        truncated,
+    }
+}
+
+/// Attach a [`LogObserver`] when verbosity is requested and no observer was supplied
+fn observed_control(
+    control: &CalibrationControl,
+    config: &OptimizationConfig,
+) -> CalibrationControl {
+    let mut control = control.clone();
+    if !control.is_observed() && config.cmaes.verbosity > 0 {
+        control.observer = Some(Arc::new(LogObserver::new()));
     }
+    control
+}
 
-    let bounds = model.param_bounds().to_vec();
-    (best_obj, best_params, bounds)
+fn emit_finished(control: &CalibrationControl, outcome: &CalibrationOutcome, started: Instant) {
+    control.emit(CalibrationEvent::Finished {
+        objective: outcome.objective,
+        params: outcome.params.clone(),
+        evaluations: outcome.evaluations,
+        truncated: outcome.truncated,
+        elapsed: started.elapsed(),
+    });
 }
@@ -0,0 +1,339 @@
+//! Optimizer profiles loaded from TOML
+//!
+//! A profile starts from a named [`Preset`] and overrides individual fields of
+//! [`OptimizationConfig`]; nested sections map to the nested config structs:
+//!
+//! ```toml
+//! preset = "fast"
+//! pop_size = 40
+//!
+//! [cmaes]
+//! sigma0 = 0.4
+//! lbfgsb_enabled = false
+//!
+//! [adaptive_bounds]
+//! enabled = true
+//! ```
+//!
+//! Without a `preset` key the profile starts from `OptimizationConfig::default()`.
+//! Unknown keys, mistyped values and out-of-range settings are rejected with the
+//! dotted path of the offending field.
+
+use crate::calibration::config::OptimizationConfig;
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Named starting point of a profile
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Preset {
+    /// [`OptimizationConfig::production`]
+    Production,
+    /// [`OptimizationConfig::fast`]
+    Fast,
+    /// [`OptimizationConfig::research`]
+    Research,
+    /// [`OptimizationConfig::minimal`]
+    Minimal,
+}
+
+impl Preset {
+    /// All presets, in documentation order
+    pub const ALL: [Preset; 4] = [
+        Preset::Production,
+        Preset::Fast,
+        Preset::Research,
+        Preset::Minimal,
+    ];
+
+    /// Name used in profiles
+    pub fn name(self) -> &'static str {
+        match self {
+            Preset::Production => "production",
+            Preset::Fast => "fast",
+            Preset::Research => "research",
+            Preset::Minimal => "minimal",
+        }
+    }
+
+    /// Optimization config of the preset
+    pub fn config(self) -> OptimizationConfig {
+        match self {
+            Preset::Production => OptimizationConfig::production(),
+            Preset::Fast => OptimizationConfig::fast(),
+            Preset::Research => OptimizationConfig::research(),
+            Preset::Minimal => OptimizationConfig::minimal(),
+        }
+    }
+}
+
+impl fmt::Display for Preset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Preset {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Preset::ALL
+            .into_iter()
+            .find(|p| p.name() == s)
+            .ok_or_else(|| {
+                anyhow!(
+                    "unknown preset `{}` (expected one of: {})",
+                    s,
+                    Preset::ALL.map(Preset::name).join(", ")
+                )
+            })
+    }
+}
+
+/// Parse a TOML profile into a validated [`OptimizationConfig`].
+pub fn profile_from_toml(toml_str: &str) -> Result<OptimizationConfig> {
+    let table: toml::Table = toml_str
+        .parse()
+        .context("invalid TOML in optimizer profile")?;
+    profile_from_table(table, "")
+}
+
+/// Load a TOML profile from disk.
+pub fn load_profile(path: impl AsRef<Path>) -> Result<OptimizationConfig> {
+    let path = path.as_ref();
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read optimizer profile {}", path.display()))?;
+    profile_from_toml(&text).with_context(|| format!("in optimizer profile {}", path.display()))
+}
+
+/// Serialize a config as a complete TOML profile that [`profile_from_toml`] reads back
+/// unchanged.
+///
+/// TOML has no null, so an unset `cmaes.seed` is omitted and reloads as the default seed.
+pub fn profile_to_toml(config: &OptimizationConfig) -> Result<String> {
+    toml::to_string_pretty(config).context("failed to serialize optimizer profile")
+}
+
+/// Build a config from an already parsed profile table.
+///
+/// `prefix` is prepended to field paths in error messages (e.g. `"optimizer"` when the
+/// profile is a section of a larger document).
+pub(crate) fn profile_from_table(
+    mut table: toml::Table,
+    prefix: &str,
+) -> Result<OptimizationConfig> {
+    let base = match table.remove("preset") {
+        None => OptimizationConfig::default(),
+        Some(toml::Value::String(name)) => name
+            .parse::<Preset>()
+            .with_context(|| format!("invalid `{}`", join_path(prefix, "preset")))?
+            .config(),
+        Some(other) => {
+            return Err(anyhow!(
+                "`{}` must be a string, got {}",
+                join_path(prefix, "preset"),
+                other.type_str()
+            ))
+        }
+    };
+
+    let mut merged = toml::Table::try_from(&base).context("failed to serialize preset")?;
+    merge_overrides(&mut merged, table, prefix)?;
+
+    let config: OptimizationConfig = toml::Value::Table(merged)
+        .try_into()
+        .map_err(|e| anyhow!("invalid optimizer profile: {}", e))?;
+    config.validate()?;
+    Ok(config)
+}
+
+/// Deep-merge `overrides` into `base`, rejecting keys and value types that `base` does
+/// not have.
+pub(crate) fn merge_overrides(
+    base: &mut toml::Table,
+    overrides: toml::Table,
+    prefix: &str,
+) -> Result<()> {
+    for (key, value) in overrides {
+        let path = join_path(prefix, &key);
+        if !base.contains_key(&key) && is_omitted_when_empty(&path) {
+            base.insert(key, value);
+            continue;
+        }
+        let Some(slot) = base.get_mut(&key) else {
+            let mut known: Vec<&str> = base.keys().map(String::as_str).collect();
+            known.sort_unstable();
+            return Err(anyhow!(
+                "unknown field `{}` (expected one of: {})",
+                path,
+                known.join(", ")
+            ));
+        };
+        match (slot, value) {
+            // Rate curves are a number or a table; the curve deserializer checks the shape
+            (slot, value) if is_rate_curve(&path) => *slot = value,
+            (toml::Value::Table(slot), toml::Value::Table(value)) => {
+                merge_overrides(slot, value, &path)?;
+            }
+            (slot @ toml::Value::Float(_), toml::Value::Integer(i)) => {
+                *slot = toml::Value::Float(i as f64);
+            }
+            (slot, value) if slot.same_type(&value) => *slot = value,
+            (slot, value) => {
+                return Err(anyhow!(
+                    "`{}` must be {}, got {}",
+                    path,
+                    article(slot.type_str()),
+                    value.type_str()
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Fields holding a [`RateCurve`](crate::curves::RateCurve)
+fn is_rate_curve(path: &str) -> bool {
+    ["fixed_params.r", "fixed_params.q"]
+        .iter()
+        .any(|field| path == *field || path.ends_with(&format!(".{field}")))
+}
+
+/// Fields the base config leaves out while they are empty, such as a
+/// [`DividendSchedule`](crate::curves::DividendSchedule)
+fn is_omitted_when_empty(path: &str) -> bool {
+    path == "fixed_params.dividends" || path.ends_with(".fixed_params.dividends")
+}
+
+fn join_path(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{prefix}.{key}")
+    }
+}
+
+fn article(type_name: &str) -> String {
+    match type_name.chars().next() {
+        Some('a' | 'e' | 'i' | 'o' | 'u') => format!("an {type_name}"),
+        _ => format!("a {type_name}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preset_with_overrides() {
+        let config = profile_from_toml(
+            r#"
+            preset = "research"
+            pop_size = 40
+            tolerance = 1
+
+            [cmaes]
+            sigma0 = 0.4
+            seed = 7
+            "#,
+        )
+        .unwrap();
+
+        let mut expected = OptimizationConfig::research();
+        expected.pop_size = 40;
+        expected.tolerance = 1.0;
+        expected.cmaes.sigma0 = 0.4;
+        expected.cmaes.seed = Some(7);
+        assert_eq!(config, expected);
+    }
+
+    #[test]
+    fn every_preset_roundtrips() {
+        for preset in Preset::ALL {
+            let config = preset.config();
+            let text = profile_to_toml(&config).unwrap();
+            assert_eq!(profile_from_toml(&text).unwrap(), config, "{preset}");
+            let named = profile_from_toml(&format!("preset = \"{preset}\"")).unwrap();
+            assert_eq!(named, config);
+        }
+    }
+
+    #[test]
+    fn errors_name_the_field() {
+        let err = profile_from_toml("preset = \"turbo\"").unwrap_err();
+        assert!(format!("{err:#}").contains("unknown preset `turbo`"));
+
+        let err = profile_from_toml("[cmaes]\nsigma = 0.2").unwrap_err();
+        assert!(err.to_string().contains("unknown field `cmaes.sigma`"));
+
+        let err = profile_from_toml("[cmaes]\nlbfgsb_enabled = \"yes\"").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("`cmaes.lbfgsb_enabled` must be a boolean, got string"));
+
+        let err = profile_from_toml("[cmaes]\nsigma0 = -1.0").unwrap_err();
+        assert!(err.to_string().contains("`cmaes.sigma0` must be >= 0.25"));
+
+        let err = profile_from_toml("optimizer = \"simulated_annealing\"").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("unknown variant `simulated_annealing`"));
+    }
+
+    #[test]
+    fn rate_curves() {
+        let config = profile_from_toml(
+            r#"
+            [fixed_params]
+            q = 0
+
+            [fixed_params.r]
+            interpolation = "piecewise_flat"
+            pillars = [[0.25, 0.043], [1.0, 0.047]]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.fixed_params.rate(0.1), 0.043);
+        assert_eq!(config.fixed_params.rate(0.5), 0.047);
+        assert_eq!(config.fixed_params.dividend_yield(0.5), 0.0);
+        let text = profile_to_toml(&config).unwrap();
+        assert_eq!(profile_from_toml(&text).unwrap(), config);
+
+        let err = profile_from_toml("[fixed_params.r]\npillars = []").unwrap_err();
+        assert!(err.to_string().contains("at least one pillar"));
+        let err = profile_from_toml("[fixed_params]\nr = \"high\"").unwrap_err();
+        assert!(err.to_string().contains("expected a rate or a table"));
+    }
+
+    #[test]
+    fn dividend_schedules() {
+        let config = profile_from_toml(
+            r#"
+            [[fixed_params.dividends]]
+            ex_time = 0.4
+            proportional = 0.01
+
+            [[fixed_params.dividends]]
+            ex_time = 0.1
+            cash = 1.5
+            "#,
+        )
+        .unwrap();
+        let dividends = config.fixed_params.dividends.dividends();
+        assert_eq!(dividends.len(), 2);
+        assert_eq!((dividends[0].ex_time, dividends[0].cash), (0.1, 1.5));
+        assert_eq!(dividends[1].proportional, 0.01);
+        let text = profile_to_toml(&config).unwrap();
+        assert_eq!(profile_from_toml(&text).unwrap(), config);
+        assert!(!profile_to_toml(&OptimizationConfig::default())
+            .unwrap()
+            .contains("dividends"));
+
+        let err =
+            profile_from_toml("[[fixed_params.dividends]]\nex_time = 0.1\ncash = -1").unwrap_err();
+        assert!(err.to_string().contains("finite cash >= 0"));
+    }
+}
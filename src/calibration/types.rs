@@ -1,10 +1,12 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 // Note: HashMap removed as param_map is no longer used
 use crate::calibration::config::OptimizationConfig;
+use crate::curves::{DividendSchedule, RateCurve};
+use anyhow::{anyhow, Result};
 use std::any::Any;
 
 /// Minimal market data structure with only essential fields for surface calibration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MarketDataRow {
     /// Option type: "call" or "put"
     pub option_type: String,
@@ -23,15 +25,170 @@ pub struct MarketDataRow {
 }
 
 /// Fixed parameters that are not calibrated by the optimizer
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+///
+/// Rates are zero curves queried at each option's own maturity; in configs each one is a
+/// number (flat) or a curve table (see [`crate::curves`]). Discrete dividends follow the
+/// escrowed model: the forward is the carried spot less the carried value of every
+/// dividend going ex before expiry.
+#[derive(Debug, Clone, PartialEq)]
 pub struct FixedParameters {
-    pub r: f64,
-    pub q: f64,
+    /// Risk-free (discount) zero curve
+    pub r: RateCurve,
+    /// Dividend yield or borrow cost zero curve
+    pub q: RateCurve,
+    /// Discrete cash and proportional dividends, on top of the yield `q`
+    pub dividends: DividendSchedule,
 }
 
 impl Default for FixedParameters {
     fn default() -> Self {
-        Self { r: 0.02, q: 0.0 }
+        Self::flat(0.02, 0.0)
+    }
+}
+
+impl FixedParameters {
+    /// Flat risk-free rate and dividend yield
+    pub fn flat(r: f64, q: f64) -> Self {
+        Self {
+            r: RateCurve::flat(r),
+            q: RateCurve::flat(q),
+            dividends: DividendSchedule::default(),
+        }
+    }
+
+    /// Set the discrete dividend schedule
+    pub fn with_dividends(mut self, dividends: DividendSchedule) -> Self {
+        self.dividends = dividends;
+        self
+    }
+
+    /// Risk-free zero rate to maturity `t`
+    pub fn rate(&self, t: f64) -> f64 {
+        self.r.zero_rate(t)
+    }
+
+    /// Dividend (or borrow) zero yield to maturity `t`
+    pub fn dividend_yield(&self, t: f64) -> f64 {
+        self.q.zero_rate(t)
+    }
+
+    /// Cost-of-carry growth factor `e^((r(t) - q(t))·t)`
+    pub fn carry(&self, t: f64) -> f64 {
+        ((self.rate(t) - self.dividend_yield(t)) * t).exp()
+    }
+
+    /// Forward of `spot` to maturity `t`
+    ///
+    /// Without dividends this is `S·e^((r(t) - q(t))·t)`. Each dividend going ex at
+    /// `tᵢ ≤ t` scales the forward carried to `tᵢ` by `1 - proportional`, then subtracts
+    /// `cash`, before carrying on to the next ex-date.
+    pub fn forward(&self, spot: f64, t: f64) -> f64 {
+        let mut forward = spot;
+        let mut carried = 1.0;
+        for dividend in self.dividends.until(t) {
+            let carry = self.carry(dividend.ex_time);
+            forward = forward * carry / carried * (1.0 - dividend.proportional) - dividend.cash;
+            carried = carry;
+        }
+        forward * self.carry(t) / carried
+    }
+
+    /// Spot net of the dividends going ex by `t`, `F(t) / e^((r(t) - q(t))·t)`
+    ///
+    /// Equal to `spot` without discrete dividends. Log-moneyness is taken against this
+    /// level, so slices either side of an ex-date share the same forward-moneyness
+    /// coordinates up to the continuous carry.
+    pub fn dividend_adjusted_spot(&self, spot: f64, t: f64) -> f64 {
+        if self.dividends.is_empty() {
+            return spot;
+        }
+        self.forward(spot, t) / self.carry(t)
+    }
+
+    /// [Dividend-adjusted spot](Self::dividend_adjusted_spot), or an error when the
+    /// dividends going ex by `t` exceed the carried spot
+    pub(crate) fn checked_adjusted_spot(&self, spot: f64, t: f64) -> Result<f64> {
+        let adjusted = self.dividend_adjusted_spot(spot, t);
+        if adjusted <= 0.0 {
+            return Err(anyhow!(
+                "Non-positive dividend-adjusted spot {} for spot {} at t={}: dividends exceed the carried spot",
+                adjusted,
+                spot,
+                t
+            ));
+        }
+        Ok(adjusted)
+    }
+
+    /// Log-moneyness `ln(K / S̃(t))` of `strike` against the
+    /// [dividend-adjusted spot](Self::dividend_adjusted_spot)
+    pub fn log_moneyness(&self, strike: f64, spot: f64, t: f64) -> f64 {
+        (strike / self.dividend_adjusted_spot(spot, t)).ln()
+    }
+
+    /// Replace each row's `underlying_price` with its
+    /// [dividend-adjusted spot](Self::dividend_adjusted_spot) at the row's maturity.
+    ///
+    /// Spot log-moneyness of the adjusted rows is [`log_moneyness`](Self::log_moneyness) of
+    /// the original ones, which is what calibration fits. Rows are returned unchanged
+    /// without discrete dividends; errors if dividends leave a row's adjusted spot
+    /// non-positive.
+    pub fn adjust_for_dividends(&self, mut data: Vec<MarketDataRow>) -> Result<Vec<MarketDataRow>> {
+        if !self.dividends.is_empty() {
+            for row in &mut data {
+                row.underlying_price =
+                    self.checked_adjusted_spot(row.underlying_price, row.years_to_exp)?;
+            }
+        }
+        Ok(data)
+    }
+}
+
+/// Field layout shared by human-readable formats
+#[derive(Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct FixedParametersFields {
+    r: RateCurve,
+    q: RateCurve,
+    #[serde(skip_serializing_if = "DividendSchedule::is_empty")]
+    dividends: DividendSchedule,
+}
+
+impl Default for FixedParametersFields {
+    fn default() -> Self {
+        let FixedParameters { r, q, dividends } = FixedParameters::default();
+        Self { r, q, dividends }
+    }
+}
+
+/// Human-readable formats omit an empty dividend schedule, keeping configs and their hashes
+/// unchanged from before dividends existed; binary formats always write all three fields.
+impl Serialize for FixedParameters {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            FixedParametersFields {
+                r: self.r.clone(),
+                q: self.q.clone(),
+                dividends: self.dividends.clone(),
+            }
+            .serialize(serializer)
+        } else {
+            (&self.r, &self.q, &self.dividends).serialize(serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for FixedParameters {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            let FixedParametersFields { r, q, dividends } =
+                FixedParametersFields::deserialize(deserializer)?;
+            Ok(Self { r, q, dividends })
+        } else {
+            let (r, q, dividends) =
+                <(RateCurve, RateCurve, DividendSchedule)>::deserialize(deserializer)?;
+            Ok(Self { r, q, dividends })
+        }
     }
 }
 
@@ -49,6 +206,20 @@ pub trait ModelCalibrator: Send + Sync {
     /// Given a parameter vector `x` and data, returns the objective value
     fn evaluate_objective(&self, x: &[f64], data: &[MarketDataRow]) -> f64;
 
+    /// Analytic gradient of [`evaluate_objective`](Self::evaluate_objective) with respect
+    /// to `x`. Gradient-based refinement uses it instead of finite differences when
+    /// available; returns `None` if the model has no analytic gradient or `x` is invalid.
+    fn evaluate_gradient(&self, _x: &[f64], _data: &[MarketDataRow]) -> Option<Vec<f64>> {
+        None
+    }
+
+    /// Weighted residual vector whose squared norm the objective is built from.
+    /// Used by least-squares backends such as Levenberg–Marquardt; returns `None`
+    /// when the model does not expose residuals or `x` is not a valid parameter set.
+    fn evaluate_residuals(&self, _x: &[f64], _data: &[MarketDataRow]) -> Option<Vec<f64>> {
+        None
+    }
+
     // Note: relaxed_param_bounds and relaxed_evaluate_objective removed
     // as they were redundant with param_bounds and evaluate_objective
 
@@ -91,7 +262,7 @@ pub trait ModelCalibrator: Send + Sync {
 }
 
 /// Lightweight struct to hold the essential pricing results for each option
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PricingResult {
     /// Option type: "call" or "put"
     pub option_type: String,
@@ -106,3 +277,19 @@ pub struct PricingResult {
     /// Model implied volatility (as decimal)
     pub model_iv: f64,
 }
+
+/// Result of a calibration run
+#[derive(Debug, Clone)]
+pub struct CalibrationOutcome {
+    /// Final objective value (lower is better)
+    pub objective: f64,
+    /// Best parameter vector found
+    pub params: Vec<f64>,
+    /// Parameter bounds in effect at the end of the run
+    pub bounds: Vec<(f64, f64)>,
+    /// Objective evaluations used
+    pub evaluations: usize,
+    /// True if the run stopped early because its deadline passed or it was cancelled;
+    /// `params` is then the best solution evaluated before stopping
+    pub truncated: bool,
+}
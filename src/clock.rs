@@ -0,0 +1,452 @@
+//! Variance clocks: wall-clock time to variance time
+//!
+//! `years_to_exp` is ACT/365 calendar time, so a weekend accrues as much variance as two
+//! trading days and a 0DTE option expiring after the close looks as long-dated as one
+//! expiring before the open. A [`VarianceClock`] weights wall-clock time by how much
+//! variance it carries:
+//!
+//! - a weight per weekday (Monday first), e.g. `0.3` for Saturday and Sunday
+//! - holidays with their own weight
+//! - an intraday profile of `(start hour, weight)` segments, normalised to average one
+//!   over the day, for options expiring within hours
+//!
+//! Variance time is scaled so that a full week accrues `7 / 365` years, keeping annualised
+//! volatilities on the calendar scale. The default clock is plain ACT/365.
+//!
+//! In configs the clock is a table, with every field optional:
+//!
+//! ```toml
+//! [temporal.clock]
+//! weekday_weights = [1, 1, 1, 1, 1, 0.3, 0.3]
+//! holidays = ["2025-12-25", "2026-01-01"]
+//! holiday_weight = 0.3
+//! intraday = [[0, 0.6], [13.5, 2.5], [20, 0.6]]
+//! utc_offset_hours = 0
+//! ```
+//!
+//! [`Tenor`]s name target maturities in wall-clock terms: minutes, hours, days or an
+//! absolute expiry time.
+
+use crate::calibration::types::MarketDataRow;
+use crate::io::{parse_timestamp, ExpiryDate, SECONDS_PER_YEAR};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Maps wall-clock intervals to variance time in years
+///
+/// ```rust
+/// use surface_lib::VarianceClock;
+///
+/// let friday = 1_736_467_200; // 2025-01-10 00:00 UTC
+/// let monday = friday + 3 * 86_400;
+/// let clock = VarianceClock::weekends(0.0);
+///
+/// // Friday to Monday is one trading day, scaled so a week is still 7/365 years
+/// let t = clock.variance_time(friday, monday);
+/// assert!((t - 7.0 / 5.0 / 365.0).abs() < 1e-12);
+/// assert!((clock.variance_time(friday, friday + 7 * 86_400) - 7.0 / 365.0).abs() < 1e-12);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct VarianceClock {
+    /// Variance weight of each weekday, Monday first
+    pub weekday_weights: [f64; 7],
+    /// Dates weighted by `holiday_weight` instead of their weekday weight
+    pub holidays: Vec<ExpiryDate>,
+    /// Variance weight of a holiday
+    pub holiday_weight: f64,
+    /// `(start hour, weight)` segments covering the day from hour 0, in increasing order;
+    /// empty for a uniform day
+    pub intraday: Vec<(f64, f64)>,
+    /// Offset of the local day from UTC in hours, e.g. `-5.0` for New York winter time
+    pub utc_offset_hours: f64,
+}
+
+impl Default for VarianceClock {
+    fn default() -> Self {
+        Self::calendar()
+    }
+}
+
+impl VarianceClock {
+    /// ACT/365 calendar time
+    pub fn calendar() -> Self {
+        Self {
+            weekday_weights: [1.0; 7],
+            holidays: Vec::new(),
+            holiday_weight: 1.0,
+            intraday: Vec::new(),
+            utc_offset_hours: 0.0,
+        }
+    }
+
+    /// Business-day clock: weekdays weigh one, Saturday and Sunday `weekend_weight`
+    pub fn weekends(weekend_weight: f64) -> Self {
+        let mut weekday_weights = [1.0; 7];
+        weekday_weights[5] = weekend_weight;
+        weekday_weights[6] = weekend_weight;
+        Self {
+            weekday_weights,
+            holiday_weight: weekend_weight,
+            ..Self::calendar()
+        }
+    }
+
+    /// Set the holidays and their weight
+    pub fn with_holidays(mut self, holidays: Vec<ExpiryDate>, weight: f64) -> Self {
+        self.holidays = holidays;
+        self.holiday_weight = weight;
+        self
+    }
+
+    /// Set the intraday profile of `(start hour, weight)` segments
+    pub fn with_intraday(mut self, intraday: Vec<(f64, f64)>) -> Self {
+        self.intraday = intraday;
+        self
+    }
+
+    /// Set the offset of the local day from UTC in hours
+    pub fn with_utc_offset(mut self, hours: f64) -> Self {
+        self.utc_offset_hours = hours;
+        self
+    }
+
+    /// Check weights, the intraday profile and the UTC offset
+    pub fn validate(&self) -> Result<()> {
+        let weight_ok = |w: f64| w.is_finite() && w >= 0.0;
+        if !self.weekday_weights.iter().all(|&w| weight_ok(w))
+            || self.weekday_weights.iter().sum::<f64>() <= 0.0
+        {
+            return Err(anyhow!(
+                "weekday_weights {:?} must be finite, >= 0 and not all zero",
+                self.weekday_weights
+            ));
+        }
+        if !weight_ok(self.holiday_weight) {
+            return Err(anyhow!(
+                "holiday_weight {} must be finite and >= 0",
+                self.holiday_weight
+            ));
+        }
+        if !(self.utc_offset_hours.is_finite() && self.utc_offset_hours.abs() <= 14.0) {
+            return Err(anyhow!(
+                "utc_offset_hours {} must be within [-14, 14]",
+                self.utc_offset_hours
+            ));
+        }
+        if self.intraday.is_empty() {
+            return Ok(());
+        }
+        if self.intraday[0].0 != 0.0 {
+            return Err(anyhow!("intraday profile must start at hour 0"));
+        }
+        if let Some(&(hour, weight)) = self
+            .intraday
+            .iter()
+            .find(|&&(hour, weight)| !((0.0..24.0).contains(&hour) && weight_ok(weight)))
+        {
+            return Err(anyhow!(
+                "intraday segment (hour={}, weight={}) must start in [0, 24) with a finite \
+                 weight >= 0",
+                hour,
+                weight
+            ));
+        }
+        if self.intraday.windows(2).any(|w| w[1].0 <= w[0].0) {
+            return Err(anyhow!("intraday segments must start at increasing hours"));
+        }
+        if self.intraday_mass() <= 0.0 {
+            return Err(anyhow!("intraday weights must not all be zero"));
+        }
+        Ok(())
+    }
+
+    /// Whether the clock is plain ACT/365 calendar time
+    pub fn is_calendar(&self) -> bool {
+        let w = self.weekday_weights[0];
+        self.weekday_weights.iter().all(|&x| x == w)
+            && (self.holidays.is_empty() || self.holiday_weight == w)
+            && self.intraday.iter().all(|&(_, x)| x == self.intraday[0].1)
+    }
+
+    /// Variance time in years from `from` to `to` (Unix seconds); negative if `to < from`
+    pub fn variance_time(&self, from: i64, to: i64) -> f64 {
+        if self.is_calendar() {
+            return (to - from) as f64 / SECONDS_PER_YEAR;
+        }
+        if to < from {
+            return -self.variance_time(to, from);
+        }
+        let offset = (self.utc_offset_hours * 3600.0).round() as i64;
+        let (start, end) = (from + offset, to + offset);
+        let mut weighted_days = 0.0;
+        let mut day = start.div_euclid(SECONDS_PER_DAY);
+        while day * SECONDS_PER_DAY < end {
+            let day_start = day * SECONDS_PER_DAY;
+            let a = start.max(day_start) - day_start;
+            let b = end.min(day_start + SECONDS_PER_DAY) - day_start;
+            weighted_days +=
+                self.day_weight(day) * (self.day_fraction(b as f64) - self.day_fraction(a as f64));
+            day += 1;
+        }
+        let mean_weight = self.weekday_weights.iter().sum::<f64>() / 7.0;
+        weighted_days / mean_weight / 365.0
+    }
+
+    /// Rows with `years_to_exp` in variance time from `valuation_time` to each expiration
+    ///
+    /// `market_iv` is rescaled so total variance `σ²·t` is unchanged; an SVI slice fit to
+    /// the result gives calendar-annualised vols through
+    /// [`SVISlice::calendar_implied_vol`](crate::models::svi::svi_model::SVISlice::calendar_implied_vol).
+    /// Rows at or past expiry are returned unchanged.
+    pub fn to_variance_time(
+        &self,
+        data: &[MarketDataRow],
+        valuation_time: i64,
+    ) -> Vec<MarketDataRow> {
+        data.iter()
+            .map(|row| {
+                let tau = self.variance_time(valuation_time, row.expiration);
+                let mut row = row.clone();
+                if tau > 0.0 && row.years_to_exp > 0.0 {
+                    row.market_iv *= (row.years_to_exp / tau).sqrt();
+                    row.years_to_exp = tau;
+                }
+                row
+            })
+            .collect()
+    }
+
+    fn day_weight(&self, day: i64) -> f64 {
+        if self.is_holiday(day) {
+            self.holiday_weight
+        } else {
+            // 1970-01-01 was a Thursday
+            self.weekday_weights[(day + 3).rem_euclid(7) as usize]
+        }
+    }
+
+    fn is_holiday(&self, day: i64) -> bool {
+        self.holidays
+            .iter()
+            .any(|d| d.timestamp_at(0).div_euclid(SECONDS_PER_DAY) == day)
+    }
+
+    /// Share of a day's variance accrued by `seconds` after local midnight
+    fn day_fraction(&self, seconds: f64) -> f64 {
+        if self.intraday.is_empty() {
+            return seconds / SECONDS_PER_DAY as f64;
+        }
+        let hours = seconds / 3600.0;
+        let mut accrued = 0.0;
+        for (i, &(start, weight)) in self.intraday.iter().enumerate() {
+            let end = self.intraday.get(i + 1).map_or(24.0, |s| s.0);
+            if hours <= start {
+                break;
+            }
+            accrued += weight * (hours.min(end) - start);
+        }
+        accrued / self.intraday_mass()
+    }
+
+    /// Weight-hours of the intraday profile over a whole day
+    fn intraday_mass(&self) -> f64 {
+        self.intraday
+            .iter()
+            .enumerate()
+            .map(|(i, &(start, weight))| {
+                let end = self.intraday.get(i + 1).map_or(24.0, |s| s.0);
+                weight * (end - start)
+            })
+            .sum()
+    }
+}
+
+/// Valuation time implied by rows whose `years_to_exp` is ACT/365 from a common snapshot,
+/// the median of `expiration - years_to_exp` in Unix seconds
+pub fn implied_valuation_time(data: &[MarketDataRow]) -> Option<i64> {
+    let mut times: Vec<i64> = data
+        .iter()
+        .filter(|r| r.years_to_exp.is_finite())
+        .map(|r| r.expiration - (r.years_to_exp * SECONDS_PER_YEAR).round() as i64)
+        .collect();
+    if times.is_empty() {
+        return None;
+    }
+    times.sort_unstable();
+    Some(times[times.len() / 2])
+}
+
+/// Target maturity measured on the wall clock
+///
+/// Parses from `30m`, `4h`, `7d`, or an expiry time accepted by
+/// [`parse_timestamp`](crate::io::parse_timestamp) such as `2025-03-28T08:00:00Z`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tenor {
+    /// Minutes from valuation
+    Minutes(u32),
+    /// Hours from valuation
+    Hours(u32),
+    /// Calendar days from valuation
+    Days(i32),
+    /// Absolute expiry time in Unix seconds
+    At(i64),
+}
+
+impl Tenor {
+    /// Expiry time for a valuation at `valuation_time` (Unix seconds)
+    pub fn expiration(&self, valuation_time: i64) -> i64 {
+        match *self {
+            Tenor::Minutes(m) => valuation_time + m as i64 * 60,
+            Tenor::Hours(h) => valuation_time + h as i64 * 3600,
+            Tenor::Days(d) => valuation_time + d as i64 * SECONDS_PER_DAY,
+            Tenor::At(ts) => ts,
+        }
+    }
+
+    /// ACT/365 time to expiry in years
+    pub fn calendar_years(&self, valuation_time: i64) -> f64 {
+        match *self {
+            Tenor::Days(d) => d as f64 / 365.0,
+            _ => (self.expiration(valuation_time) - valuation_time) as f64 / SECONDS_PER_YEAR,
+        }
+    }
+
+    /// Whole calendar days to expiry, rounded down (0 for sub-day tenors)
+    pub fn whole_days(&self, valuation_time: i64) -> i32 {
+        match *self {
+            Tenor::Days(d) => d,
+            _ => (self.expiration(valuation_time) - valuation_time).div_euclid(SECONDS_PER_DAY)
+                as i32,
+        }
+    }
+
+    /// Whether the expiry depends on the valuation time only through a fixed offset
+    pub fn is_relative(&self) -> bool {
+        !matches!(self, Tenor::At(_))
+    }
+}
+
+impl fmt::Display for Tenor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Tenor::Minutes(m) => write!(f, "{}m", m),
+            Tenor::Hours(h) => write!(f, "{}h", h),
+            Tenor::Days(d) => write!(f, "{}d", d),
+            Tenor::At(ts) => write!(f, "{}", ts),
+        }
+    }
+}
+
+impl FromStr for Tenor {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let relative = s.char_indices().last().and_then(|(i, unit)| {
+            let n = &s[..i];
+            match unit {
+                'm' => n.parse().ok().map(Tenor::Minutes),
+                'h' => n.parse().ok().map(Tenor::Hours),
+                'd' => n.parse().ok().map(Tenor::Days),
+                _ => None,
+            }
+        });
+        match relative {
+            Some(tenor) => Ok(tenor),
+            None => parse_timestamp(s).map(Tenor::At).map_err(|_| {
+                anyhow!(
+                    "invalid tenor `{}` (expected e.g. 30m, 4h, 7d or 2025-03-28T08:00:00Z)",
+                    s
+                )
+            }),
+        }
+    }
+}
+
+impl Serialize for Tenor {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Tenor {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2025-01-06 00:00 UTC, a Monday
+    const MONDAY: i64 = 1_736_121_600;
+
+    #[test]
+    fn weights_and_intraday_profile() {
+        let calendar = VarianceClock::calendar();
+        assert!(calendar.is_calendar());
+        assert_eq!(calendar.variance_time(MONDAY, MONDAY + 86_400), 1.0 / 365.0);
+
+        let clock = VarianceClock::weekends(0.5)
+            .with_holidays(vec!["2025-01-08".parse().unwrap()], 0.0)
+            .with_intraday(vec![(0.0, 0.0), (12.0, 2.0)]);
+        clock.validate().unwrap();
+        assert!(!clock.is_calendar());
+        let mean = 6.0 / 7.0;
+        let day = |days: f64| days / mean / 365.0;
+
+        // Tuesday morning carries no variance, the afternoon a full day's
+        let tuesday = MONDAY + 86_400;
+        assert_eq!(clock.variance_time(tuesday, tuesday + 12 * 3600), 0.0);
+        assert!((clock.variance_time(tuesday, tuesday + 18 * 3600) - day(0.5)).abs() < 1e-15);
+        // Wednesday is a holiday; Saturday and Sunday weigh a half each
+        assert!((clock.variance_time(tuesday, tuesday + 2 * 86_400) - day(1.0)).abs() < 1e-15);
+        let week = clock.variance_time(MONDAY, MONDAY + 7 * 86_400);
+        assert!((week - day(5.0)).abs() < 1e-15);
+        assert_eq!(clock.variance_time(MONDAY + 86_400, MONDAY), -day(1.0));
+
+        // Shifting the local day moves the intraday window
+        let new_york = clock.clone().with_utc_offset(-5.0);
+        let expected = day(7.0 / 12.0);
+        let t = new_york.variance_time(tuesday + 17 * 3600, tuesday + 24 * 3600);
+        assert!((t - expected).abs() < 1e-15);
+
+        let text = toml::to_string(&clock).unwrap();
+        assert_eq!(toml::from_str::<VarianceClock>(&text).unwrap(), clock);
+        let parsed: VarianceClock = toml::from_str(
+            "weekday_weights = [1, 1, 1, 1, 1, 0.5, 0.5]\nholidays = [\"2025-01-08\"]",
+        )
+        .unwrap();
+        assert_eq!(parsed.holidays, clock.holidays);
+        assert_eq!(parsed.weekday_weights[5], 0.5);
+
+        assert!(VarianceClock::weekends(-1.0).validate().is_err());
+        assert!(clock.with_intraday(vec![(1.0, 1.0)]).validate().is_err());
+    }
+
+    #[test]
+    fn tenors() {
+        assert_eq!("30m".parse::<Tenor>().unwrap(), Tenor::Minutes(30));
+        assert_eq!("4h".parse::<Tenor>().unwrap(), Tenor::Hours(4));
+        assert_eq!("7d".parse::<Tenor>().unwrap(), Tenor::Days(7));
+        let at: Tenor = "2025-01-10T08:00:00Z".parse().unwrap();
+        assert_eq!(at, Tenor::At(MONDAY + 4 * 86_400 + 8 * 3600));
+        assert!("soon".parse::<Tenor>().is_err());
+
+        assert_eq!(Tenor::Days(30).calendar_years(MONDAY), 30.0 / 365.0);
+        assert_eq!(Tenor::Hours(36).whole_days(MONDAY), 1);
+        assert_eq!(at.whole_days(MONDAY), 4);
+        assert_eq!(Tenor::Minutes(90).expiration(MONDAY), MONDAY + 5400);
+        for tenor in [Tenor::Minutes(5), Tenor::Hours(4), Tenor::Days(7), at] {
+            let json = serde_json::to_string(&tenor).unwrap();
+            assert_eq!(serde_json::from_str::<Tenor>(&json).unwrap(), tenor);
+        }
+    }
+}
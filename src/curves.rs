@@ -0,0 +1,422 @@
+//! Zero-rate term structures and discrete dividends
+//!
+//! A [`RateCurve`] maps a maturity `t` (years) to a continuously compounded zero rate
+//! `z(t)`, so the discount factor to `t` is `e^(-z(t)·t)`. [`FixedParameters`] holds one
+//! curve for the risk-free rate and one for the dividend yield (or borrow cost); pricers
+//! and delta calculations query both at each option's own maturity. Single stocks can add a
+//! [`DividendSchedule`] of cash and proportional dividends paid at their ex-dates.
+//!
+//! In configs and profiles a curve is either a number (a flat rate) or a table:
+//!
+//! ```toml
+//! [fixed_params]
+//! q = 0.0
+//!
+//! [fixed_params.r]
+//! interpolation = "piecewise_flat"
+//! pillars = [[0.25, 0.043], [0.5, 0.045], [1.0, 0.047]]
+//!
+//! [[fixed_params.dividends]]
+//! ex_time = 0.12
+//! cash = 0.85
+//!
+//! [[fixed_params.dividends]]
+//! ex_time = 0.37
+//! proportional = 0.004
+//! ```
+//!
+//! [`FixedParameters`]: crate::calibration::types::FixedParameters
+
+use anyhow::{anyhow, Result};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+/// How zero rates are interpolated between pillars
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CurveInterpolation {
+    /// `z(t)` is the rate of the first pillar at or after `t`
+    PiecewiseFlat,
+    /// `z(t)` is linear between pillars
+    #[default]
+    Linear,
+}
+
+/// Continuously compounded zero-rate curve
+///
+/// Pillars are `(t, rate)` pairs sorted by maturity. Rates are held flat before the first
+/// and after the last pillar; a single pillar is a flat curve.
+///
+/// ```rust
+/// use surface_lib::{CurveInterpolation, RateCurve};
+///
+/// let curve = RateCurve::new(vec![(0.5, 0.04), (1.0, 0.05)], CurveInterpolation::Linear)?;
+/// assert!((curve.zero_rate(0.75) - 0.045).abs() < 1e-12);
+/// assert_eq!(curve.zero_rate(0.1), 0.04);
+/// assert!((curve.discount_factor(1.0) - (-0.05f64).exp()).abs() < 1e-12);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateCurve {
+    pillars: Vec<(f64, f64)>,
+    interpolation: CurveInterpolation,
+}
+
+impl RateCurve {
+    /// Build from `(t, rate)` pillars in any order.
+    ///
+    /// Pillars need finite maturities ≥ 0, distinct maturities and finite rates.
+    pub fn new(mut pillars: Vec<(f64, f64)>, interpolation: CurveInterpolation) -> Result<Self> {
+        if pillars.is_empty() {
+            return Err(anyhow!("rate curve needs at least one pillar"));
+        }
+        if let Some(&(t, rate)) = pillars
+            .iter()
+            .find(|(t, rate)| !(t.is_finite() && *t >= 0.0 && rate.is_finite()))
+        {
+            return Err(anyhow!(
+                "rate curve pillar (t={}, rate={}) must have a finite t >= 0 and a finite rate",
+                t,
+                rate
+            ));
+        }
+        pillars.sort_by(|a, b| a.0.total_cmp(&b.0));
+        if let Some(pair) = pillars.windows(2).find(|w| w[0].0 == w[1].0) {
+            return Err(anyhow!(
+                "rate curve has duplicate pillar at t={}",
+                pair[0].0
+            ));
+        }
+        Ok(Self {
+            pillars,
+            interpolation,
+        })
+    }
+
+    /// The same rate at every maturity
+    pub fn flat(rate: f64) -> Self {
+        Self {
+            pillars: vec![(0.0, rate)],
+            interpolation: CurveInterpolation::default(),
+        }
+    }
+
+    /// `(t, rate)` pillars sorted by maturity
+    pub fn pillars(&self) -> &[(f64, f64)] {
+        &self.pillars
+    }
+
+    /// Interpolation between pillars
+    pub fn interpolation(&self) -> CurveInterpolation {
+        self.interpolation
+    }
+
+    /// Whether the curve is a single flat rate
+    pub fn is_flat(&self) -> bool {
+        self.pillars.len() == 1
+    }
+
+    /// Whether every pillar rate is finite (always true for curves built by [`new`](Self::new))
+    pub fn is_finite(&self) -> bool {
+        self.pillars.iter().all(|(_, rate)| rate.is_finite())
+    }
+
+    /// Zero rate to maturity `t`
+    pub fn zero_rate(&self, t: f64) -> f64 {
+        let pillars = &self.pillars;
+        let Some(next) = pillars.iter().position(|&(ti, _)| ti >= t) else {
+            return pillars[pillars.len() - 1].1;
+        };
+        if next == 0 {
+            return pillars[0].1;
+        }
+        let (t1, r1) = pillars[next - 1];
+        let (t2, r2) = pillars[next];
+        match self.interpolation {
+            CurveInterpolation::PiecewiseFlat => r2,
+            CurveInterpolation::Linear => r1 + (r2 - r1) * (t - t1) / (t2 - t1),
+        }
+    }
+
+    /// Discount factor `e^(-z(t)·t)`
+    pub fn discount_factor(&self, t: f64) -> f64 {
+        (-self.zero_rate(t) * t).exp()
+    }
+}
+
+impl Default for RateCurve {
+    fn default() -> Self {
+        Self::flat(0.0)
+    }
+}
+
+impl From<f64> for RateCurve {
+    fn from(rate: f64) -> Self {
+        Self::flat(rate)
+    }
+}
+
+impl fmt::Display for RateCurve {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let [(_, rate)] = self.pillars[..] {
+            return write!(f, "{}", rate);
+        }
+        let interpolation = match self.interpolation {
+            CurveInterpolation::PiecewiseFlat => "piecewise flat",
+            CurveInterpolation::Linear => "linear",
+        };
+        write!(f, "{} [", interpolation)?;
+        for (i, (t, rate)) in self.pillars.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{}: {}", t, rate)?;
+        }
+        f.write_str("]")
+    }
+}
+
+/// A discrete dividend going ex at `ex_time`
+///
+/// On the ex-date the share price drops by `proportional` of its value, then by `cash`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Dividend {
+    /// Ex-dividend time in years from valuation
+    pub ex_time: f64,
+    /// Cash amount per share
+    #[serde(default)]
+    pub cash: f64,
+    /// Fraction of the share price, in `[0, 1)`
+    #[serde(default)]
+    pub proportional: f64,
+}
+
+impl Dividend {
+    /// Cash dividend of `amount` per share
+    pub fn cash(ex_time: f64, amount: f64) -> Self {
+        Self {
+            ex_time,
+            cash: amount,
+            proportional: 0.0,
+        }
+    }
+
+    /// Proportional dividend of `fraction` of the share price
+    pub fn proportional(ex_time: f64, fraction: f64) -> Self {
+        Self {
+            ex_time,
+            cash: 0.0,
+            proportional: fraction,
+        }
+    }
+}
+
+/// Discrete dividends sorted by ex-date
+///
+/// ```rust
+/// use surface_lib::{Dividend, DividendSchedule};
+///
+/// let schedule = DividendSchedule::new(vec![
+///     Dividend::proportional(0.6, 0.01),
+///     Dividend::cash(0.1, 1.5),
+/// ])?;
+/// assert_eq!(schedule.dividends()[0].ex_time, 0.1);
+/// assert_eq!(schedule.until(0.5).count(), 1);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(try_from = "Vec<Dividend>", into = "Vec<Dividend>")]
+pub struct DividendSchedule {
+    dividends: Vec<Dividend>,
+}
+
+impl DividendSchedule {
+    /// Build from dividends in any order.
+    ///
+    /// Ex-times must be finite and ≥ 0, cash amounts finite and ≥ 0 and proportional
+    /// fractions in `[0, 1)`.
+    pub fn new(mut dividends: Vec<Dividend>) -> Result<Self> {
+        if let Some(d) = dividends.iter().find(|d| {
+            !(d.ex_time.is_finite()
+                && d.ex_time >= 0.0
+                && d.cash.is_finite()
+                && d.cash >= 0.0
+                && (0.0..1.0).contains(&d.proportional))
+        }) {
+            return Err(anyhow!(
+                "dividend (ex_time={}, cash={}, proportional={}) must have a finite ex_time >= 0, \
+                 a finite cash >= 0 and a proportional fraction in [0, 1)",
+                d.ex_time,
+                d.cash,
+                d.proportional
+            ));
+        }
+        dividends.sort_by(|a, b| a.ex_time.total_cmp(&b.ex_time));
+        Ok(Self { dividends })
+    }
+
+    /// Dividends sorted by ex-date
+    pub fn dividends(&self) -> &[Dividend] {
+        &self.dividends
+    }
+
+    /// Whether the schedule has no dividends
+    pub fn is_empty(&self) -> bool {
+        self.dividends.is_empty()
+    }
+
+    /// Dividends going ex at or before `t`
+    pub fn until(&self, t: f64) -> impl Iterator<Item = &Dividend> {
+        self.dividends.iter().take_while(move |d| d.ex_time <= t)
+    }
+}
+
+impl TryFrom<Vec<Dividend>> for DividendSchedule {
+    type Error = anyhow::Error;
+
+    fn try_from(dividends: Vec<Dividend>) -> Result<Self> {
+        Self::new(dividends)
+    }
+}
+
+impl From<DividendSchedule> for Vec<Dividend> {
+    fn from(schedule: DividendSchedule) -> Self {
+        schedule.dividends
+    }
+}
+
+/// Table form of a curve in human-readable formats
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CurveTable {
+    #[serde(default)]
+    interpolation: CurveInterpolation,
+    pillars: Vec<(f64, f64)>,
+}
+
+/// Human-readable formats write flat curves as a bare number, keeping configs and their
+/// hashes unchanged from when rates were plain numbers; binary formats always write the
+/// interpolation and pillars.
+impl Serialize for RateCurve {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            if let [(_, rate)] = self.pillars[..] {
+                return serializer.serialize_f64(rate);
+            }
+            CurveTable {
+                interpolation: self.interpolation,
+                pillars: self.pillars.clone(),
+            }
+            .serialize(serializer)
+        } else {
+            (self.interpolation, &self.pillars).serialize(serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for RateCurve {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            #[derive(Deserialize)]
+            #[serde(untagged)]
+            enum Repr {
+                Flat(f64),
+                Table(CurveTable),
+            }
+            match Repr::deserialize(deserializer).map_err(|_| {
+                D::Error::custom(
+                    "expected a rate or a table with `pillars` ([[t, rate], ...]) and an \
+                     optional `interpolation` (\"linear\" or \"piecewise_flat\")",
+                )
+            })? {
+                Repr::Flat(rate) => Ok(Self::flat(rate)),
+                Repr::Table(table) => {
+                    Self::new(table.pillars, table.interpolation).map_err(D::Error::custom)
+                }
+            }
+        } else {
+            let (interpolation, pillars) =
+                <(CurveInterpolation, Vec<(f64, f64)>)>::deserialize(deserializer)?;
+            Self::new(pillars, interpolation).map_err(D::Error::custom)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interpolation_and_extrapolation() {
+        let pillars = vec![(1.0, 0.05), (0.5, 0.04)];
+        let linear = RateCurve::new(pillars.clone(), CurveInterpolation::Linear).unwrap();
+        let stepped = RateCurve::new(pillars, CurveInterpolation::PiecewiseFlat).unwrap();
+
+        assert_eq!(linear.pillars(), &[(0.5, 0.04), (1.0, 0.05)]);
+        assert!((linear.zero_rate(0.75) - 0.045).abs() < 1e-15);
+        assert_eq!(stepped.zero_rate(0.75), 0.05);
+        for curve in [&linear, &stepped] {
+            assert_eq!(curve.zero_rate(0.0), 0.04);
+            assert_eq!(curve.zero_rate(0.5), 0.04);
+            assert_eq!(curve.zero_rate(1.0), 0.05);
+            assert_eq!(curve.zero_rate(3.0), 0.05);
+        }
+        assert_eq!(RateCurve::flat(0.03).zero_rate(7.0), 0.03);
+        assert!((RateCurve::flat(0.03).discount_factor(2.0) - (-0.06f64).exp()).abs() < 1e-15);
+
+        assert!(RateCurve::new(vec![], CurveInterpolation::Linear).is_err());
+        assert!(RateCurve::new(vec![(0.5, f64::NAN)], CurveInterpolation::Linear).is_err());
+        assert!(RateCurve::new(vec![(0.5, 0.1), (0.5, 0.2)], CurveInterpolation::Linear).is_err());
+    }
+
+    #[test]
+    fn serde_forms() {
+        let flat: RateCurve = serde_json::from_str("0.02").unwrap();
+        assert_eq!(flat, RateCurve::flat(0.02));
+        assert_eq!(serde_json::to_string(&flat).unwrap(), "0.02");
+
+        let curve: RateCurve = serde_json::from_str(
+            r#"{"interpolation": "piecewise_flat", "pillars": [[1, 0.05], [0.5, 0.04]]}"#,
+        )
+        .unwrap();
+        assert_eq!(curve.interpolation(), CurveInterpolation::PiecewiseFlat);
+        let json = serde_json::to_string(&curve).unwrap();
+        assert_eq!(serde_json::from_str::<RateCurve>(&json).unwrap(), curve);
+
+        for c in [&flat, &curve] {
+            let bytes = bincode::serialize(c).unwrap();
+            assert_eq!(&bincode::deserialize::<RateCurve>(&bytes).unwrap(), c);
+        }
+
+        let err = serde_json::from_str::<RateCurve>(r#"{"pillars": []}"#).unwrap_err();
+        assert!(err.to_string().contains("at least one pillar"));
+        assert!(serde_json::from_str::<RateCurve>(r#""flat""#).is_err());
+    }
+
+    #[test]
+    fn dividend_schedules() {
+        let schedule: DividendSchedule = serde_json::from_str(
+            r#"[{"ex_time": 0.5, "proportional": 0.02}, {"ex_time": 0.1, "cash": 1.0}]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            schedule.dividends(),
+            &[Dividend::cash(0.1, 1.0), Dividend::proportional(0.5, 0.02)]
+        );
+        assert_eq!(schedule.until(0.1).count(), 1);
+        assert_eq!(schedule.until(0.09).count(), 0);
+        let bytes = bincode::serialize(&schedule).unwrap();
+        assert_eq!(
+            bincode::deserialize::<DividendSchedule>(&bytes).unwrap(),
+            schedule
+        );
+
+        assert!(DividendSchedule::new(vec![Dividend::proportional(0.1, 1.0)]).is_err());
+        assert!(DividendSchedule::new(vec![Dividend::cash(-0.1, 1.0)]).is_err());
+        assert!(
+            serde_json::from_str::<DividendSchedule>(r#"[{"ex_time": 0.1, "amount": 1}]"#).is_err()
+        );
+    }
+}
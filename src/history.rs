@@ -0,0 +1,531 @@
+//! Time-indexed history of fixed-tenor metrics and SVI parameters
+//!
+//! A [`HistoryStore`] keeps one [`HistoryEntry`] per snapshot, in time order: the
+//! [`FixedTimeMetrics`] built from the snapshot and, optionally, its calibrated SVI slices.
+//! Entries are appended as snapshots arrive, queried by time range and resampled to a
+//! coarser grid (e.g. daily closes). On top of the store:
+//!
+//! - [`HistoryStore::percentile_rank`] and [`HistoryStore::z_score`] of the latest ATM IV,
+//!   risk reversal or butterfly of a tenor against its history
+//! - [`HistoryStore::vol_cone`]: the distribution of each tenor's ATM IV
+//! - [`HistoryStore::implied_realized_spread`]: ATM IV minus close-to-close realised
+//!   volatility over the tenor's trailing window of a price history
+//!
+//! Stores persist as JSON Lines, one entry per line, so a running process can append to
+//! the same file.
+//!
+//! ```rust
+//! use surface_lib::history::{HistoryEntry, HistoryMetric, HistoryStore};
+//! use surface_lib::{FixedTimeMetrics, Tenor};
+//!
+//! # fn metrics(atm_iv: f64) -> Vec<FixedTimeMetrics> {
+//! #     vec![FixedTimeMetrics { tenor: Tenor::Days(30), tte_days: 30, tte_years: 30.0 / 365.0,
+//! #         forward: 100.0, atm_iv, event_variance: 0.0, delta_metrics: vec![],
+//! #         delta_smile: vec![], moneyness_smile: vec![] }]
+//! # }
+//! let mut store = HistoryStore::new();
+//! for (day, atm_iv) in [0.55, 0.60, 0.50, 0.65].into_iter().enumerate() {
+//!     store.append(HistoryEntry::new(day as i64 * 86_400, metrics(atm_iv)))?;
+//! }
+//! let rank = store.percentile_rank(Tenor::Days(30), HistoryMetric::AtmIv).unwrap();
+//! assert_eq!(rank, 1.0); // the latest 30d ATM IV is the highest
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use crate::clock::Tenor;
+use crate::models::linear_iv::FixedTimeMetrics;
+use crate::models::svi::svi_model::SVIParams;
+use crate::realized::{realized_vol, trailing_window, Bar, RealizedEstimator};
+use crate::snapshot::{SliceRecord, SurfaceRecord};
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// Fixed-tenor metrics and SVI slices observed at one time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// Observation time (Unix seconds)
+    pub time: i64,
+    /// Fixed-tenor metrics of the snapshot
+    pub metrics: Vec<FixedTimeMetrics>,
+    /// Calibrated SVI slices of the snapshot
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub slices: Vec<SliceRecord>,
+}
+
+impl HistoryEntry {
+    /// Entry without SVI slices
+    pub fn new(time: i64, metrics: Vec<FixedTimeMetrics>) -> Self {
+        Self {
+            time,
+            metrics,
+            slices: Vec::new(),
+        }
+    }
+
+    /// Set the SVI slices
+    pub fn with_slices(mut self, slices: Vec<SliceRecord>) -> Self {
+        self.slices = slices;
+        self
+    }
+
+    /// Set the SVI slices from a stored surface
+    pub fn with_surface(self, record: &SurfaceRecord) -> Self {
+        self.with_slices(record.slices.clone())
+    }
+
+    /// Metrics of `tenor`, if the snapshot has them
+    pub fn tenor(&self, tenor: Tenor) -> Option<&FixedTimeMetrics> {
+        self.metrics.iter().find(|m| m.tenor == tenor)
+    }
+}
+
+/// Fixed-tenor metric tracked over time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HistoryMetric {
+    /// ATM implied volatility
+    AtmIv,
+    /// Risk reversal at a delta level, e.g. `0.25`
+    RiskReversal(f64),
+    /// Butterfly at a delta level, e.g. `0.25`
+    Butterfly(f64),
+}
+
+impl HistoryMetric {
+    /// Value of the metric in `metrics`, if present
+    pub fn value(self, metrics: &FixedTimeMetrics) -> Option<f64> {
+        let delta_metric = |level: f64| {
+            metrics
+                .delta_metrics
+                .iter()
+                .find(|dm| (dm.delta_level - level).abs() < 1e-6)
+        };
+        match self {
+            HistoryMetric::AtmIv => Some(metrics.atm_iv),
+            HistoryMetric::RiskReversal(level) => delta_metric(level).map(|dm| dm.risk_reversal),
+            HistoryMetric::Butterfly(level) => delta_metric(level).map(|dm| dm.butterfly),
+        }
+    }
+}
+
+/// Distribution of one tenor's ATM IV over a history
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VolCone {
+    pub tenor: Tenor,
+    /// Number of entries with this tenor
+    pub observations: usize,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    /// `(quantile, ATM IV)` pairs in the requested order
+    pub quantiles: Vec<(f64, f64)>,
+    /// ATM IV of the latest entry with this tenor
+    pub latest: f64,
+}
+
+/// Implied against realised volatility at one entry
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VolSpread {
+    /// Entry time (Unix seconds)
+    pub time: i64,
+    /// ATM IV of the tenor
+    pub implied: f64,
+    /// Annualised close-to-close volatility over the tenor's trailing window
+    pub realized: f64,
+    /// `implied - realized`
+    pub spread: f64,
+}
+
+/// Append-only, time-ordered store of [`HistoryEntry`]s
+#[derive(Debug, Clone, Default)]
+pub struct HistoryStore {
+    entries: Vec<HistoryEntry>,
+}
+
+impl HistoryStore {
+    /// Empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store of entries in any order; fails on duplicate times
+    pub fn from_entries(mut entries: Vec<HistoryEntry>) -> Result<Self> {
+        entries.sort_by_key(|e| e.time);
+        if let Some(pair) = entries.windows(2).find(|w| w[0].time == w[1].time) {
+            return Err(anyhow!("Duplicate history entry at time {}", pair[0].time));
+        }
+        Ok(Self { entries })
+    }
+
+    /// Append an entry later than every stored one
+    pub fn append(&mut self, entry: HistoryEntry) -> Result<()> {
+        if let Some(latest) = self.entries.last() {
+            if entry.time <= latest.time {
+                return Err(anyhow!(
+                    "History entry at {} is not after the latest entry at {}",
+                    entry.time,
+                    latest.time
+                ));
+            }
+        }
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    /// Entries in time order
+    pub fn entries(&self) -> &[HistoryEntry] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Most recent entry
+    pub fn latest(&self) -> Option<&HistoryEntry> {
+        self.entries.last()
+    }
+
+    /// Entries with `from <= time < to`
+    pub fn range(&self, from: i64, to: i64) -> HistoryStore {
+        let start = self.entries.partition_point(|e| e.time < from);
+        let end = self.entries.partition_point(|e| e.time < to).max(start);
+        Self {
+            entries: self.entries[start..end].to_vec(),
+        }
+    }
+
+    /// The last entry of each `interval`-second bucket counted from the Unix epoch,
+    /// e.g. `86_400` for daily closes at 00:00 UTC
+    pub fn resample(&self, interval: i64) -> Result<HistoryStore> {
+        if interval <= 0 {
+            return Err(anyhow!(
+                "Resampling interval must be positive (got {})",
+                interval
+            ));
+        }
+        let mut entries: Vec<HistoryEntry> = Vec::new();
+        for entry in &self.entries {
+            match entries.last_mut() {
+                Some(last) if last.time.div_euclid(interval) == entry.time.div_euclid(interval) => {
+                    *last = entry.clone();
+                }
+                _ => entries.push(entry.clone()),
+            }
+        }
+        Ok(Self { entries })
+    }
+
+    /// `(time, value)` of `metric` for `tenor`, skipping entries without it
+    pub fn series(&self, tenor: Tenor, metric: HistoryMetric) -> Vec<(i64, f64)> {
+        self.entries
+            .iter()
+            .filter_map(|e| Some((e.time, metric.value(e.tenor(tenor)?)?)))
+            .collect()
+    }
+
+    /// `(time, params)` of the slice expiring at `expiration` in each entry that has it
+    pub fn param_series(&self, expiration: i64) -> Vec<(i64, SVIParams)> {
+        self.entries
+            .iter()
+            .filter_map(|e| {
+                e.slices
+                    .iter()
+                    .find(|s| s.expiration == Some(expiration))
+                    .map(|s| (e.time, s.params.clone()))
+            })
+            .collect()
+    }
+
+    /// Share of earlier observations of `metric` for `tenor` below the latest one, from
+    /// 0 (a new low) to 1 (a new high); `None` with fewer than two observations
+    pub fn percentile_rank(&self, tenor: Tenor, metric: HistoryMetric) -> Option<f64> {
+        let series = self.series(tenor, metric);
+        let (&(_, latest), earlier) = series.split_last()?;
+        if earlier.is_empty() {
+            return None;
+        }
+        let below = earlier.iter().filter(|(_, v)| *v < latest).count();
+        Some(below as f64 / earlier.len() as f64)
+    }
+
+    /// Standard score of the latest observation of `metric` for `tenor` against the mean
+    /// and sample standard deviation of all its observations; `None` with fewer than two
+    /// observations or no dispersion
+    pub fn z_score(&self, tenor: Tenor, metric: HistoryMetric) -> Option<f64> {
+        let values: Vec<f64> = self
+            .series(tenor, metric)
+            .into_iter()
+            .map(|(_, v)| v)
+            .collect();
+        let &latest = values.last()?;
+        let (mean, std_dev) = mean_and_std_dev(&values)?;
+        (std_dev > 0.0).then(|| (latest - mean) / std_dev)
+    }
+
+    /// Volatility cone of ATM IV for every tenor in the store, sorted by time to expiry
+    ///
+    /// `quantiles` are fractions in `[0, 1]`, interpolated linearly between observations.
+    pub fn vol_cone(&self, quantiles: &[f64]) -> Result<Vec<VolCone>> {
+        if let Some(q) = quantiles.iter().find(|q| !(0.0..=1.0).contains(*q)) {
+            return Err(anyhow!("Quantiles must lie in [0, 1] (got {})", q));
+        }
+        // Tenors with the TTE of their latest observation
+        let mut tenors: Vec<(Tenor, f64)> = Vec::new();
+        for m in self.entries.iter().rev().flat_map(|e| &e.metrics) {
+            if !tenors.iter().any(|(t, _)| *t == m.tenor) {
+                tenors.push((m.tenor, m.tte_years));
+            }
+        }
+        tenors.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+        Ok(tenors
+            .into_iter()
+            .map(|(tenor, _)| {
+                let values: Vec<f64> = self
+                    .series(tenor, HistoryMetric::AtmIv)
+                    .into_iter()
+                    .map(|(_, v)| v)
+                    .collect();
+                let latest = values[values.len() - 1];
+                let mut sorted = values.clone();
+                sorted.sort_by(f64::total_cmp);
+                VolCone {
+                    tenor,
+                    observations: values.len(),
+                    min: sorted[0],
+                    max: sorted[sorted.len() - 1],
+                    mean: values.iter().sum::<f64>() / values.len() as f64,
+                    quantiles: quantiles
+                        .iter()
+                        .map(|&q| (q, quantile(&sorted, q)))
+                        .collect(),
+                    latest,
+                }
+            })
+            .collect())
+    }
+
+    /// ATM IV of `tenor` minus the realised volatility of `prices` over the window of the
+    /// same length ending at each entry
+    ///
+    /// `prices` are `(Unix seconds, price)` observations in any order; the last price
+    /// at each time is used. Realised volatility is close-to-close (see
+    /// [`crate::realized`]) over the prices in the window, starting from the last price
+    /// at or before its start. Entries whose window holds fewer than two prices, or whose
+    /// tenor is a fixed expiry already past, are skipped.
+    pub fn implied_realized_spread(&self, tenor: Tenor, prices: &[(i64, f64)]) -> Vec<VolSpread> {
+        let mut prices: Vec<(i64, f64)> = prices
+            .iter()
+            .copied()
+            .filter(|(_, p)| p.is_finite() && *p > 0.0)
+            .collect();
+        prices.sort_by_key(|(t, _)| *t);
+        let mut bars: Vec<Bar> = Vec::with_capacity(prices.len());
+        for (time, price) in prices {
+            match bars.last_mut() {
+                Some(bar) if bar.time == time => *bar = Bar::flat(time, price),
+                _ => bars.push(Bar::flat(time, price)),
+            }
+        }
+
+        self.series(tenor, HistoryMetric::AtmIv)
+            .into_iter()
+            .filter_map(|(time, implied)| {
+                let window = tenor.expiration(time) - time;
+                if window <= 0 {
+                    return None;
+                }
+                let prices = trailing_window(&bars, time - window, time);
+                let realized = realized_vol(prices, RealizedEstimator::CloseToClose).ok()?;
+                Some(VolSpread {
+                    time,
+                    implied,
+                    realized,
+                    spread: implied - realized,
+                })
+            })
+            .collect()
+    }
+
+    /// JSON Lines encoding, one entry per line
+    pub fn to_jsonl(&self) -> Result<String> {
+        let mut out = String::new();
+        for entry in &self.entries {
+            out.push_str(&serde_json::to_string(entry)?);
+            out.push('\n');
+        }
+        Ok(out)
+    }
+
+    /// Load a JSON Lines store; blank lines are skipped
+    pub fn from_jsonl(jsonl: &str) -> Result<Self> {
+        let entries = jsonl
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                serde_json::from_str(line).with_context(|| format!("history line {}", i + 1))
+            })
+            .collect::<Result<Vec<HistoryEntry>>>()?;
+        Self::from_entries(entries)
+    }
+}
+
+/// Mean and sample standard deviation; `None` with fewer than two values
+fn mean_and_std_dev(values: &[f64]) -> Option<(f64, f64)> {
+    if values.len() < 2 {
+        return None;
+    }
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let variance = values.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / (n - 1.0);
+    Some((mean, variance.sqrt()))
+}
+
+/// Quantile `q` of sorted values, interpolating linearly between order statistics
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let position = q * (sorted.len() - 1) as f64;
+    let lower = position.floor() as usize;
+    let upper = position.ceil() as usize;
+    sorted[lower] + (position - lower as f64) * (sorted[upper] - sorted[lower])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::linear_iv::DeltaMetrics;
+
+    const DAY: i64 = 86_400;
+
+    fn metrics(days: i32, atm_iv: f64, rr: f64) -> FixedTimeMetrics {
+        FixedTimeMetrics {
+            tenor: Tenor::Days(days),
+            tte_days: days,
+            tte_years: days as f64 / 365.0,
+            forward: 100.0,
+            atm_iv,
+            event_variance: 0.0,
+            delta_metrics: vec![DeltaMetrics {
+                delta_level: 0.25,
+                risk_reversal: rr,
+                butterfly: 0.01,
+            }],
+            delta_smile: Vec::new(),
+            moneyness_smile: Vec::new(),
+        }
+    }
+
+    fn store() -> HistoryStore {
+        let mut store = HistoryStore::new();
+        for (i, atm) in [0.50, 0.55, 0.45, 0.60, 0.52].into_iter().enumerate() {
+            let time = i as i64 * DAY;
+            store
+                .append(HistoryEntry::new(
+                    time,
+                    vec![
+                        metrics(30, atm, -0.02 * i as f64),
+                        metrics(7, atm + 0.05, 0.0),
+                    ],
+                ))
+                .unwrap();
+        }
+        store
+    }
+
+    #[test]
+    fn append_range_and_resample() {
+        let mut store = store();
+        assert!(store
+            .append(HistoryEntry::new(2 * DAY, Vec::new()))
+            .is_err());
+        assert_eq!(store.range(DAY, 3 * DAY).len(), 2);
+        assert!(store.range(3 * DAY, DAY).is_empty());
+
+        // Two intraday entries on day 5 collapse to the later one
+        store
+            .append(HistoryEntry::new(
+                5 * DAY + 3600,
+                vec![metrics(30, 0.7, 0.0)],
+            ))
+            .unwrap();
+        store
+            .append(HistoryEntry::new(
+                5 * DAY + 7200,
+                vec![metrics(30, 0.8, 0.0)],
+            ))
+            .unwrap();
+        let daily = store.resample(DAY).unwrap();
+        assert_eq!(daily.len(), 6);
+        assert_eq!(daily.latest().unwrap().time, 5 * DAY + 7200);
+        assert!(store.resample(0).is_err());
+
+        let jsonl = store.to_jsonl().unwrap();
+        assert_eq!(jsonl.lines().count(), store.len());
+        let loaded = HistoryStore::from_jsonl(&jsonl).unwrap();
+        assert_eq!(
+            loaded.series(Tenor::Days(30), HistoryMetric::AtmIv),
+            store.series(Tenor::Days(30), HistoryMetric::AtmIv)
+        );
+        let duplicated = format!("{jsonl}{}", jsonl.lines().next().unwrap());
+        assert!(HistoryStore::from_jsonl(&duplicated).is_err());
+    }
+
+    #[test]
+    fn ranks_scores_and_cones() {
+        let store = store();
+        let tenor = Tenor::Days(30);
+        // 0.52 is above 0.50 and 0.45 of the four earlier observations
+        assert_eq!(
+            store.percentile_rank(tenor, HistoryMetric::AtmIv),
+            Some(0.5)
+        );
+        assert_eq!(
+            store.percentile_rank(tenor, HistoryMetric::RiskReversal(0.25)),
+            Some(0.0)
+        );
+        assert_eq!(
+            store.series(tenor, HistoryMetric::Butterfly(0.1)),
+            Vec::new()
+        );
+        let z = store.z_score(tenor, HistoryMetric::AtmIv).unwrap();
+        let values = [0.50, 0.55, 0.45, 0.60, 0.52];
+        let (mean, sd) = mean_and_std_dev(&values).unwrap();
+        assert!((z - (0.52 - mean) / sd).abs() < 1e-12);
+        assert_eq!(store.z_score(tenor, HistoryMetric::Butterfly(0.25)), None);
+
+        let cones = store.vol_cone(&[0.0, 0.5, 0.75]).unwrap();
+        assert_eq!(cones.len(), 2);
+        assert_eq!(cones[0].tenor, Tenor::Days(7));
+        let cone = &cones[1];
+        assert_eq!((cone.observations, cone.min, cone.max), (5, 0.45, 0.60));
+        assert_eq!(cone.quantiles[0], (0.0, 0.45));
+        assert!((cone.quantiles[1].1 - 0.52).abs() < 1e-12);
+        assert!((cone.quantiles[2].1 - 0.55).abs() < 1e-12);
+        assert_eq!(cone.latest, 0.52);
+        assert!(store.vol_cone(&[1.5]).is_err());
+    }
+
+    #[test]
+    fn implied_minus_realized() {
+        // Alternating ±1% daily moves: realised vol is 1% · sqrt(365)
+        let prices: Vec<(i64, f64)> = (-40..=5)
+            .map(|i| (i * DAY, if i % 2 == 0 { 100.0 } else { 101.0 }))
+            .collect();
+        let store = store();
+        let spreads = store.implied_realized_spread(Tenor::Days(7), &prices);
+        assert_eq!(spreads.len(), 5);
+        let move_sq = (101.0f64 / 100.0).ln().powi(2);
+        let expected = (7.0 * move_sq / (7.0 / 365.0)).sqrt();
+        for s in &spreads {
+            assert!((s.realized - expected).abs() < 1e-12, "{s:?}");
+            assert!((s.spread - (s.implied - s.realized)).abs() < 1e-15);
+        }
+        assert!(store
+            .implied_realized_spread(Tenor::Days(7), &prices[..1])
+            .is_empty());
+    }
+}
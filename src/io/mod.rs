@@ -0,0 +1,555 @@
+//! Option chain snapshot ingestion
+//!
+//! [`SnapshotReader`] loads CSV snapshots into [`MarketDataRow`]s. Column names are
+//! configurable through [`ColumnMap`]; the defaults match the snapshot files in
+//! `tests/data`:
+//!
+//! ```text
+//! symbol,snapshot_ts,option_type,strike_price,expiration_ts,years_to_exp,bid_iv,ask_iv,mark_iv,underlying_price,vega,...
+//! BTC-10JAN25-100000-C,2025-01-01 00:00:00+02:00,call,100000.0,1736496000,0.0258,54.67,55.63,55.01,94064.8,48.85,...
+//! ```
+//!
+//! - Implied volatilities are converted to decimals according to [`IvUnit`]
+//! - `years_to_exp` is derived as ACT/365 from the expiration and snapshot times when both
+//!   are known, so every quote of an expiry shares one maturity; otherwise the
+//!   `years_to_exp` column is used
+//! - Option type, strike and expiration fall back to the [symbol](symbol) when their
+//!   columns are absent or empty
+//!
+//! Timestamps are Unix seconds (or milliseconds), or ISO 8601 date-times such as
+//! `2025-01-01 00:00:00+02:00` or `2025-01-01T08:00:00Z`.
+
+pub mod symbol;
+
+pub use symbol::{parse_symbol, ExpiryDate, ParsedSymbol, SymbolFormat};
+
+use crate::calibration::types::MarketDataRow;
+use anyhow::{anyhow, Context, Result};
+use std::path::Path;
+
+/// Seconds in an ACT/365 year
+pub const SECONDS_PER_YEAR: f64 = 365.0 * 86_400.0;
+
+/// Unit of the implied-volatility columns
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IvUnit {
+    /// 55.0 means 55% (exchange snapshots)
+    #[default]
+    Percent,
+    /// 0.55 means 55%
+    Decimal,
+}
+
+/// CSV column names; `None` disables a column
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnMap {
+    /// Exchange symbol, used for the fields below when their columns are missing
+    pub symbol: Option<String>,
+    /// "call"/"put" or "C"/"P"
+    pub option_type: Option<String>,
+    pub strike_price: Option<String>,
+    /// Expiration timestamp
+    pub expiration: Option<String>,
+    /// Snapshot timestamp
+    pub snapshot: Option<String>,
+    /// Time to expiry in years, used when the expiration or snapshot time is unknown
+    pub years_to_exp: Option<String>,
+    pub underlying_price: String,
+    /// Implied volatility to calibrate to
+    pub iv: String,
+    pub bid_iv: Option<String>,
+    pub ask_iv: Option<String>,
+    /// Vega for weighting; missing or non-positive values become 1.0
+    pub vega: Option<String>,
+}
+
+impl Default for ColumnMap {
+    fn default() -> Self {
+        Self {
+            symbol: Some("symbol".to_string()),
+            option_type: Some("option_type".to_string()),
+            strike_price: Some("strike_price".to_string()),
+            expiration: Some("expiration_ts".to_string()),
+            snapshot: Some("snapshot_ts".to_string()),
+            years_to_exp: Some("years_to_exp".to_string()),
+            underlying_price: "underlying_price".to_string(),
+            iv: "mark_iv".to_string(),
+            bid_iv: Some("bid_iv".to_string()),
+            ask_iv: Some("ask_iv".to_string()),
+            vega: Some("vega".to_string()),
+        }
+    }
+}
+
+/// A quote loaded from a snapshot
+#[derive(Debug, Clone)]
+pub struct OptionQuote {
+    pub symbol: Option<String>,
+    /// Snapshot time as Unix seconds, when known
+    pub snapshot_ts: Option<i64>,
+    /// Bid implied volatility (decimal)
+    pub bid_iv: Option<f64>,
+    /// Ask implied volatility (decimal)
+    pub ask_iv: Option<f64>,
+    pub row: MarketDataRow,
+}
+
+impl OptionQuote {
+    /// Contract terms of the quote's symbol, when it has a recognised one
+    pub fn parsed_symbol(&self) -> Option<ParsedSymbol> {
+        self.symbol.as_deref().and_then(|s| parse_symbol(s).ok())
+    }
+}
+
+/// Configurable CSV snapshot loader
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use surface_lib::io::{ColumnMap, IvUnit, SnapshotReader, SymbolFormat};
+///
+/// let quotes = SnapshotReader::new()
+///     .with_columns(ColumnMap {
+///         iv: "markVol".to_string(),
+///         option_type: None, // taken from the symbol
+///         ..ColumnMap::default()
+///     })
+///     .with_iv_unit(IvUnit::Decimal)
+///     .with_symbol_format(SymbolFormat::Okx)
+///     .read_path("okx_chain.csv")?;
+/// let data: Vec<_> = quotes.into_iter().map(|q| q.row).collect();
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SnapshotReader {
+    columns: ColumnMap,
+    iv_unit: IvUnit,
+    symbol_format: Option<SymbolFormat>,
+    snapshot_time: Option<i64>,
+    delimiter: Option<u8>,
+}
+
+impl SnapshotReader {
+    /// Reader with the default columns, percent IVs and symbol format detection
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use custom column names
+    pub fn with_columns(mut self, columns: ColumnMap) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    /// Set the unit of the IV columns
+    pub fn with_iv_unit(mut self, iv_unit: IvUnit) -> Self {
+        self.iv_unit = iv_unit;
+        self
+    }
+
+    /// Parse symbols in a fixed format instead of detecting it per symbol
+    pub fn with_symbol_format(mut self, format: SymbolFormat) -> Self {
+        self.symbol_format = Some(format);
+        self
+    }
+
+    /// Snapshot time (Unix seconds) for files without a snapshot column
+    pub fn with_snapshot_time(mut self, snapshot_ts: i64) -> Self {
+        self.snapshot_time = Some(snapshot_ts);
+        self
+    }
+
+    /// Field delimiter (default `,`)
+    pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = Some(delimiter);
+        self
+    }
+
+    /// Load a snapshot file.
+    pub fn read_path(&self, path: impl AsRef<Path>) -> Result<Vec<OptionQuote>> {
+        let path = path.as_ref();
+        let file = std::fs::File::open(path)
+            .with_context(|| format!("failed to open snapshot {}", path.display()))?;
+        self.read(file)
+            .with_context(|| format!("in snapshot {}", path.display()))
+    }
+
+    /// Load a snapshot from any reader.
+    ///
+    /// Quotes without a positive, finite implied volatility and quotes that expired before
+    /// the snapshot are skipped (and logged); malformed values are errors.
+    pub fn read<R: std::io::Read>(&self, reader: R) -> Result<Vec<OptionQuote>> {
+        let mut csv = csv::ReaderBuilder::new()
+            .delimiter(self.delimiter.unwrap_or(b','))
+            .trim(csv::Trim::All)
+            .from_reader(reader);
+        let layout = Layout::new(csv.headers()?, &self.columns)?;
+
+        let mut quotes = Vec::new();
+        let (mut no_iv, mut expired) = (0usize, 0usize);
+        for (i, record) in csv.records().enumerate() {
+            let record = record?;
+            // Header is line 1
+            let line = i + 2;
+            match self
+                .parse_record(&layout, &record)
+                .with_context(|| format!("line {}", line))?
+            {
+                Parsed::Quote(quote) => quotes.push(*quote),
+                Parsed::NoIv => no_iv += 1,
+                Parsed::Expired => expired += 1,
+            }
+        }
+        if no_iv + expired > 0 {
+            log::warn!(
+                loaded = quotes.len(), no_iv, expired;
+                "skipped snapshot quotes without a usable implied volatility or already expired"
+            );
+        }
+        Ok(quotes)
+    }
+
+    fn parse_record(&self, layout: &Layout, record: &csv::StringRecord) -> Result<Parsed> {
+        let field = |idx: Option<usize>| {
+            idx.and_then(|i| record.get(i))
+                .filter(|value| !value.is_empty())
+        };
+        let number = |idx: Option<usize>, name: &Option<String>| -> Result<Option<f64>> {
+            field(idx)
+                .map(|value| {
+                    value.parse::<f64>().map_err(|_| {
+                        anyhow!(
+                            "invalid `{}` value `{}`",
+                            name.as_deref().unwrap_or_default(),
+                            value
+                        )
+                    })
+                })
+                .transpose()
+        };
+        let columns = &self.columns;
+        let iv_scale = match self.iv_unit {
+            IvUnit::Percent => 0.01,
+            IvUnit::Decimal => 1.0,
+        };
+
+        let iv = number(layout.iv, &Some(columns.iv.clone()))?.unwrap_or(f64::NAN) * iv_scale;
+        if !(iv > 0.0 && iv.is_finite()) {
+            return Ok(Parsed::NoIv);
+        }
+        let quote_iv = |value: Option<f64>| {
+            value
+                .map(|v| v * iv_scale)
+                .filter(|v| *v > 0.0 && v.is_finite())
+        };
+
+        let symbol = field(layout.symbol).map(str::to_string);
+        let mut parsed_symbol = None;
+        let mut from_symbol = |what: &str| -> Result<ParsedSymbol> {
+            if parsed_symbol.is_none() {
+                let symbol = symbol
+                    .as_deref()
+                    .ok_or_else(|| anyhow!("no {} column and no symbol", what))?;
+                parsed_symbol = Some(match self.symbol_format {
+                    Some(format) => format.parse(symbol),
+                    None => parse_symbol(symbol),
+                }?);
+            }
+            Ok(parsed_symbol.clone().unwrap())
+        };
+
+        let option_type = match field(layout.option_type) {
+            Some(value) => normalize_option_type(value)?,
+            None => from_symbol("option type")?.option_type,
+        };
+        let strike_price = match number(layout.strike_price, &columns.strike_price)? {
+            Some(strike) => strike,
+            None => from_symbol("strike")?.strike,
+        };
+        let expiration = match field(layout.expiration) {
+            Some(value) => Some(parse_timestamp(value).with_context(|| {
+                format!(
+                    "invalid `{}`",
+                    columns.expiration.as_deref().unwrap_or_default()
+                )
+            })?),
+            None if symbol.is_some() => Some(from_symbol("expiration")?.expiration_ts),
+            None => None,
+        };
+        let snapshot_ts = match field(layout.snapshot) {
+            Some(value) => Some(parse_timestamp(value).with_context(|| {
+                format!(
+                    "invalid `{}`",
+                    columns.snapshot.as_deref().unwrap_or_default()
+                )
+            })?),
+            None => self.snapshot_time,
+        };
+
+        let years_to_exp = match (expiration, snapshot_ts) {
+            (Some(exp), Some(snap)) => (exp - snap) as f64 / SECONDS_PER_YEAR,
+            _ => number(layout.years_to_exp, &columns.years_to_exp)?.ok_or_else(|| {
+                anyhow!("cannot determine years_to_exp: need expiration and snapshot times, or a years_to_exp column")
+            })?,
+        };
+        if years_to_exp <= 0.0 {
+            return Ok(Parsed::Expired);
+        }
+
+        let underlying_price = number(
+            layout.underlying_price,
+            &Some(columns.underlying_price.clone()),
+        )?
+        .ok_or_else(|| anyhow!("missing `{}` value", columns.underlying_price))?;
+        let vega = number(layout.vega, &columns.vega)?
+            .filter(|v| *v > 0.0)
+            .unwrap_or(1.0);
+
+        Ok(Parsed::Quote(Box::new(OptionQuote {
+            symbol,
+            snapshot_ts,
+            bid_iv: quote_iv(number(layout.bid_iv, &columns.bid_iv)?),
+            ask_iv: quote_iv(number(layout.ask_iv, &columns.ask_iv)?),
+            row: MarketDataRow {
+                option_type,
+                strike_price,
+                underlying_price,
+                years_to_exp,
+                market_iv: iv,
+                vega,
+                // Without an expiration time, quotes are grouped by their rounded maturity
+                expiration: expiration.unwrap_or((years_to_exp * SECONDS_PER_YEAR).round() as i64),
+            },
+        })))
+    }
+}
+
+/// Load a snapshot with the default [`SnapshotReader`] settings.
+pub fn load_snapshot(path: impl AsRef<Path>) -> Result<Vec<MarketDataRow>> {
+    Ok(SnapshotReader::new()
+        .read_path(path)?
+        .into_iter()
+        .map(|q| q.row)
+        .collect())
+}
+
+/// Outcome of parsing one CSV record
+enum Parsed {
+    Quote(Box<OptionQuote>),
+    NoIv,
+    Expired,
+}
+
+/// Column indices resolved against the header
+struct Layout {
+    symbol: Option<usize>,
+    option_type: Option<usize>,
+    strike_price: Option<usize>,
+    expiration: Option<usize>,
+    snapshot: Option<usize>,
+    years_to_exp: Option<usize>,
+    underlying_price: Option<usize>,
+    iv: Option<usize>,
+    bid_iv: Option<usize>,
+    ask_iv: Option<usize>,
+    vega: Option<usize>,
+}
+
+impl Layout {
+    fn new(headers: &csv::StringRecord, columns: &ColumnMap) -> Result<Self> {
+        let find = |name: &Option<String>| {
+            name.as_deref()
+                .and_then(|name| headers.iter().position(|h| h == name))
+        };
+        let require = |name: &String| {
+            headers
+                .iter()
+                .position(|h| h == name)
+                .map(Some)
+                .ok_or_else(|| anyhow!("missing column `{}`", name))
+        };
+        Ok(Self {
+            symbol: find(&columns.symbol),
+            option_type: find(&columns.option_type),
+            strike_price: find(&columns.strike_price),
+            expiration: find(&columns.expiration),
+            snapshot: find(&columns.snapshot),
+            years_to_exp: find(&columns.years_to_exp),
+            underlying_price: require(&columns.underlying_price)?,
+            iv: require(&columns.iv)?,
+            bid_iv: find(&columns.bid_iv),
+            ask_iv: find(&columns.ask_iv),
+            vega: find(&columns.vega),
+        })
+    }
+}
+
+fn normalize_option_type(value: &str) -> Result<String> {
+    match value.to_ascii_lowercase().as_str() {
+        "call" | "c" => Ok("call".to_string()),
+        "put" | "p" => Ok("put".to_string()),
+        _ => Err(anyhow!(
+            "invalid option type `{}` (expected call/put or C/P)",
+            value
+        )),
+    }
+}
+
+/// Parse Unix seconds, Unix milliseconds (values above 10^11) or an ISO 8601 date-time
+/// (`2025-01-01 00:00:00+02:00`, `2025-01-01T08:00:00.5Z`, `2025-01-01`) into Unix seconds.
+pub fn parse_timestamp(value: &str) -> Result<i64> {
+    let value = value.trim();
+    if let Ok(n) = value.parse::<i64>() {
+        return Ok(if n.abs() > 100_000_000_000 {
+            n / 1000
+        } else {
+            n
+        });
+    }
+    if let Ok(x) = value.parse::<f64>() {
+        if x.is_finite() {
+            return Ok(x.floor() as i64);
+        }
+    }
+    parse_iso_datetime(value).ok_or_else(|| anyhow!("invalid timestamp `{}`", value))
+}
+
+fn parse_iso_datetime(value: &str) -> Option<i64> {
+    let date: ExpiryDate = value.get(..10)?.parse().ok()?;
+    if value.as_bytes().get(4) != Some(&b'-') {
+        return None;
+    }
+    let mut seconds = date.timestamp_at(0);
+    let rest = &value[10..];
+    if rest.is_empty() {
+        return Some(seconds);
+    }
+    let rest = rest.strip_prefix(['T', ' '])?;
+
+    // Time of day, optional fraction, then Z / ±HH:MM / nothing (UTC)
+    let (time, offset) = match rest.find(['Z', '+', '-']) {
+        Some(i) => rest.split_at(i),
+        None => (rest, ""),
+    };
+    let time = time.split('.').next()?;
+    let mut hms = time.split(':');
+    let hour: i64 = hms.next()?.parse().ok()?;
+    let minute: i64 = hms.next()?.parse().ok()?;
+    let second: i64 = hms.next().map_or(Some(0), |s| s.parse().ok())?;
+    if hms.next().is_some() || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+    seconds += hour * 3600 + minute * 60 + second;
+
+    match offset {
+        "" | "Z" => {}
+        _ => {
+            let sign = if offset.starts_with('-') { -1 } else { 1 };
+            let digits = offset[1..].replace(':', "");
+            if digits.len() != 4 {
+                return None;
+            }
+            let hours: i64 = digits[..2].parse().ok()?;
+            let minutes: i64 = digits[2..].parse().ok()?;
+            seconds -= sign * (hours * 3600 + minutes * 60);
+        }
+    }
+    Some(seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_timestamps() {
+        assert_eq!(parse_timestamp("1736496000").unwrap(), 1736496000);
+        assert_eq!(parse_timestamp("1736496000000").unwrap(), 1736496000);
+        assert_eq!(
+            parse_timestamp("2025-01-01 00:00:00+02:00").unwrap(),
+            1735682400
+        );
+        assert_eq!(
+            parse_timestamp("2025-01-10T08:00:00.250Z").unwrap(),
+            1736496000
+        );
+        assert_eq!(
+            parse_timestamp("2025-01-10T03:00-05:00").unwrap(),
+            1736496000
+        );
+        assert_eq!(parse_timestamp("2025-01-10").unwrap(), 1736467200);
+        assert!(parse_timestamp("2025-01-10T25:00:00Z").is_err());
+        assert!(parse_timestamp("yesterday").is_err());
+    }
+
+    #[test]
+    fn loads_default_columns() {
+        let quotes = SnapshotReader::new()
+            .read_path("tests/data/options_snapshots_20250101.csv")
+            .unwrap();
+        assert_eq!(quotes.len(), 792);
+
+        let first = &quotes[0];
+        assert_eq!(first.symbol.as_deref(), Some("BTC-10JAN25-100000-C"));
+        assert_eq!(first.snapshot_ts, Some(1735682400));
+        assert_eq!(first.row.option_type, "call");
+        assert_eq!(first.row.expiration, 1736496000);
+        assert!((first.row.market_iv - 0.5501).abs() < 1e-12);
+        assert!((first.bid_iv.unwrap() - 0.5467).abs() < 1e-12);
+        // ACT/365 from the snapshot time, identical for every quote of the expiry
+        assert!((first.row.years_to_exp - 813600.0 / SECONDS_PER_YEAR).abs() < 1e-15);
+        assert!(quotes
+            .iter()
+            .filter(|q| q.row.expiration == 1736496000)
+            .all(|q| q.row.years_to_exp == first.row.years_to_exp));
+        assert!(quotes.iter().all(|q| q.row.vega > 0.0));
+    }
+
+    #[test]
+    fn custom_columns_and_symbol_fallbacks() {
+        let csv = "instrument;vol;fwd;bid\n\
+                   BTC-USD-250110-100000-C;0.55;94000;\n\
+                   BTC-USD-250110-90000-P;0.6;94000;0.58\n\
+                   BTC-USD-250110-95000-P;0;94000;\n";
+        let reader = SnapshotReader::new()
+            .with_columns(ColumnMap {
+                symbol: Some("instrument".to_string()),
+                iv: "vol".to_string(),
+                underlying_price: "fwd".to_string(),
+                bid_iv: Some("bid".to_string()),
+                ..ColumnMap::default()
+            })
+            .with_iv_unit(IvUnit::Decimal)
+            .with_symbol_format(SymbolFormat::Okx)
+            .with_snapshot_time(1735682400)
+            .with_delimiter(b';');
+        let quotes = reader.read(csv.as_bytes()).unwrap();
+
+        // The zero-IV quote is skipped
+        assert_eq!(quotes.len(), 2);
+        assert_eq!(quotes[1].row.option_type, "put");
+        assert_eq!(quotes[1].row.strike_price, 90000.0);
+        assert_eq!(quotes[1].row.expiration, 1736496000);
+        assert_eq!(quotes[1].row.vega, 1.0);
+        assert_eq!(quotes[1].bid_iv, Some(0.58));
+        assert_eq!(quotes[0].bid_iv, None);
+        assert!((quotes[0].row.years_to_exp - 813600.0 / SECONDS_PER_YEAR).abs() < 1e-15);
+
+        let err = SnapshotReader::new()
+            .read("symbol,mark_iv\nBTC-10JAN25-1-C,50\n".as_bytes())
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("missing column `underlying_price`"));
+
+        let err = SnapshotReader::new()
+            .read("symbol,mark_iv,underlying_price\nBTC,50,1\n".as_bytes())
+            .unwrap_err();
+        assert!(format!("{err:#}").contains("line 2: unrecognised option symbol `BTC`"));
+
+        let err = SnapshotReader::new()
+            .read("option_type,mark_iv,underlying_price,strike_price,years_to_exp\ncall,abc,1,1,0.1\n".as_bytes())
+            .unwrap_err();
+        assert!(format!("{err:#}").contains("invalid `mark_iv` value `abc`"));
+    }
+}
@@ -0,0 +1,419 @@
+//! Exchange option symbol parsing
+//!
+//! Supported formats:
+//!
+//! | Format | Example | Expiry time |
+//! |--------|---------|-------------|
+//! | Deribit | `BTC-10JAN25-100000-C`, `XRP_USDC-10JAN25-2d5-P` | 08:00 UTC |
+//! | OKX | `BTC-USD-250110-100000-C` | 08:00 UTC |
+//! | CME | `BTCF5 C100000`, `ETHH25 P3200` | last Friday of the month, 16:00 London |
+//!
+//! CME symbols only carry the contract month; the expiry day follows the monthly
+//! crypto options convention (last Friday of the month). For products with another
+//! expiry rule, provide an expiration column instead of relying on the symbol.
+
+use anyhow::{anyhow, Result};
+use std::fmt;
+use std::str::FromStr;
+
+const MONTHS: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+
+/// CME futures month codes, January to December
+const CME_MONTH_CODES: [char; 12] = ['F', 'G', 'H', 'J', 'K', 'M', 'N', 'Q', 'U', 'V', 'X', 'Z'];
+
+/// Exchange symbol convention
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolFormat {
+    /// `BTC-10JAN25-100000-C`
+    Deribit,
+    /// `BTC-USD-250110-100000-C`
+    Okx,
+    /// `BTCF5 C100000`
+    Cme,
+}
+
+impl SymbolFormat {
+    /// Parse `symbol` in this format.
+    pub fn parse(self, symbol: &str) -> Result<ParsedSymbol> {
+        let parsed = match self {
+            SymbolFormat::Deribit => parse_deribit(symbol),
+            SymbolFormat::Okx => parse_okx(symbol),
+            SymbolFormat::Cme => parse_cme(symbol),
+        };
+        parsed.ok_or_else(|| anyhow!("`{}` is not a valid {} option symbol", symbol, self))
+    }
+
+    /// Format of `symbol`, judged from its shape
+    pub fn detect(symbol: &str) -> Option<Self> {
+        if symbol.trim().contains(' ') {
+            return Some(SymbolFormat::Cme);
+        }
+        match symbol.split('-').count() {
+            4 => Some(SymbolFormat::Deribit),
+            5 => Some(SymbolFormat::Okx),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for SymbolFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SymbolFormat::Deribit => "Deribit",
+            SymbolFormat::Okx => "OKX",
+            SymbolFormat::Cme => "CME",
+        })
+    }
+}
+
+/// Calendar date of an expiry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ExpiryDate {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl ExpiryDate {
+    /// Validated date
+    pub fn new(year: i32, month: u32, day: u32) -> Result<Self> {
+        if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+            return Err(anyhow!("invalid date {:04}-{:02}-{:02}", year, month, day));
+        }
+        Ok(Self { year, month, day })
+    }
+
+    /// Unix seconds at `hour`:00 UTC on this date
+    pub fn timestamp_at(&self, hour: u32) -> i64 {
+        days_from_civil(self.year, self.month, self.day) * 86_400 + hour as i64 * 3_600
+    }
+
+    /// Deribit-style code, e.g. `10JAN25`
+    pub fn code(&self) -> String {
+        format!(
+            "{}{}{:02}",
+            self.day,
+            MONTHS[self.month as usize - 1],
+            self.year.rem_euclid(100)
+        )
+    }
+
+    /// Day of the week, 0 = Monday
+    fn weekday(&self) -> i64 {
+        (days_from_civil(self.year, self.month, self.day) + 3).rem_euclid(7)
+    }
+
+    /// Last occurrence of `weekday` (0 = Monday) in a month
+    fn last_weekday(year: i32, month: u32, weekday: i64) -> Self {
+        let last = Self {
+            year,
+            month,
+            day: days_in_month(year, month),
+        };
+        let back = (last.weekday() - weekday).rem_euclid(7) as u32;
+        Self {
+            day: last.day - back,
+            ..last
+        }
+    }
+}
+
+impl fmt::Display for ExpiryDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// Accepts `10JAN25` (Deribit), `250110` (OKX) and `2025-01-10` (ISO).
+impl FromStr for ExpiryDate {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let date = if let Some((y, rest)) = s.split_once('-') {
+            rest.split_once('-').and_then(|(m, d)| {
+                ExpiryDate::new(y.parse().ok()?, m.parse().ok()?, d.parse().ok()?).ok()
+            })
+        } else {
+            parse_deribit_date(s).or_else(|| parse_okx_date(s))
+        };
+        date.ok_or_else(|| {
+            anyhow!(
+                "invalid expiry `{}` (expected e.g. 10JAN25, 250110 or 2025-01-10)",
+                s
+            )
+        })
+    }
+}
+
+/// Serialized as the ISO form `2025-01-10`
+impl serde::Serialize for ExpiryDate {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for ExpiryDate {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Contract terms encoded in an option symbol
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedSymbol {
+    pub format: SymbolFormat,
+    /// Underlying, e.g. `BTC` (Deribit), `BTC-USD` (OKX) or the futures root `BTC` (CME)
+    pub underlying: String,
+    pub expiry: ExpiryDate,
+    /// Expiry as Unix seconds, at the exchange's expiry time
+    pub expiration_ts: i64,
+    pub strike: f64,
+    /// "call" or "put"
+    pub option_type: String,
+}
+
+/// Parse a Deribit, OKX or CME option symbol, detecting the format from its shape.
+///
+/// # Example
+///
+/// ```rust
+/// use surface_lib::io::{parse_symbol, SymbolFormat};
+///
+/// let parsed = parse_symbol("BTC-10JAN25-100000-C")?;
+/// assert_eq!(parsed.format, SymbolFormat::Deribit);
+/// assert_eq!(parsed.expiration_ts, 1736496000); // 2025-01-10 08:00 UTC
+/// assert_eq!(parsed.strike, 100000.0);
+/// assert_eq!(parsed.option_type, "call");
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn parse_symbol(symbol: &str) -> Result<ParsedSymbol> {
+    let format = SymbolFormat::detect(symbol)
+        .ok_or_else(|| anyhow!("unrecognised option symbol `{}`", symbol))?;
+    format.parse(symbol)
+}
+
+fn parse_deribit(symbol: &str) -> Option<ParsedSymbol> {
+    let [underlying, date, strike, kind] = split_n::<4>(symbol.trim(), '-')?;
+    let expiry = parse_deribit_date(date)?;
+    Some(ParsedSymbol {
+        format: SymbolFormat::Deribit,
+        underlying: non_empty(underlying)?.to_string(),
+        expiry,
+        expiration_ts: expiry.timestamp_at(8),
+        strike: parse_strike(&strike.replace(['d', 'D'], "."))?,
+        option_type: option_type(kind)?,
+    })
+}
+
+fn parse_okx(symbol: &str) -> Option<ParsedSymbol> {
+    let [base, quote, date, strike, kind] = split_n::<5>(symbol.trim(), '-')?;
+    let expiry = parse_okx_date(date)?;
+    Some(ParsedSymbol {
+        format: SymbolFormat::Okx,
+        underlying: format!("{}-{}", non_empty(base)?, non_empty(quote)?),
+        expiry,
+        expiration_ts: expiry.timestamp_at(8),
+        strike: parse_strike(strike)?,
+        option_type: option_type(kind)?,
+    })
+}
+
+fn parse_cme(symbol: &str) -> Option<ParsedSymbol> {
+    let mut parts = symbol.split_whitespace();
+    let (contract, option) = (parts.next()?, parts.next()?);
+    if parts.next().is_some() {
+        return None;
+    }
+
+    let digits = contract
+        .chars()
+        .rev()
+        .take_while(char::is_ascii_digit)
+        .count();
+    let (head, year) = contract.split_at(contract.len() - digits);
+    // Single-digit years are taken to be in the 2020s
+    let year: i32 = match digits {
+        1 => 2020 + year.parse::<i32>().ok()?,
+        2 => 2000 + year.parse::<i32>().ok()?,
+        _ => return None,
+    };
+    let code = head.chars().last()?;
+    let month = CME_MONTH_CODES.iter().position(|&c| c == code)? as u32 + 1;
+    let root = non_empty(&head[..head.len() - 1])?;
+
+    let expiry = ExpiryDate::last_weekday(year, month, 4);
+    let kind = option.get(..1)?;
+    Some(ParsedSymbol {
+        format: SymbolFormat::Cme,
+        underlying: root.to_string(),
+        expiry,
+        expiration_ts: expiry.timestamp_at(if is_british_summer_time(&expiry) {
+            15
+        } else {
+            16
+        }),
+        strike: parse_strike(&option[1..])?,
+        option_type: option_type(kind)?,
+    })
+}
+
+fn split_n<const N: usize>(s: &str, sep: char) -> Option<[&str; N]> {
+    let parts: Vec<&str> = s.split(sep).collect();
+    parts.try_into().ok()
+}
+
+fn non_empty(s: &str) -> Option<&str> {
+    (!s.is_empty()).then_some(s)
+}
+
+fn parse_strike(s: &str) -> Option<f64> {
+    s.parse::<f64>().ok().filter(|k| *k > 0.0 && k.is_finite())
+}
+
+fn option_type(kind: &str) -> Option<String> {
+    match kind.to_ascii_uppercase().as_str() {
+        "C" | "CALL" => Some("call".to_string()),
+        "P" | "PUT" => Some("put".to_string()),
+        _ => None,
+    }
+}
+
+/// `10JAN25`
+fn parse_deribit_date(s: &str) -> Option<ExpiryDate> {
+    let split = s.find(|c: char| !c.is_ascii_digit())?;
+    let (day, rest) = s.split_at(split);
+    if rest.len() != 5 || !rest.is_ascii() {
+        return None;
+    }
+    let (month, year) = rest.split_at(3);
+    let month = MONTHS.iter().position(|m| m.eq_ignore_ascii_case(month))? as u32 + 1;
+    if !year.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    ExpiryDate::new(2000 + year.parse::<i32>().ok()?, month, day.parse().ok()?).ok()
+}
+
+/// `250110`
+fn parse_okx_date(s: &str) -> Option<ExpiryDate> {
+    if s.len() != 6 || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    ExpiryDate::new(
+        2000 + s[..2].parse::<i32>().ok()?,
+        s[2..4].parse().ok()?,
+        s[4..].parse().ok()?,
+    )
+    .ok()
+}
+
+/// Whether London is on BST on `date` (last Sunday of March to last Sunday of October)
+fn is_british_summer_time(date: &ExpiryDate) -> bool {
+    let start = ExpiryDate::last_weekday(date.year, 3, 6);
+    let end = ExpiryDate::last_weekday(date.year, 10, 6);
+    *date >= start && *date < end
+}
+
+fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01 of a proleptic Gregorian date (H. Hinnant's algorithm)
+pub(crate) fn days_from_civil(year: i32, month: u32, day: u32) -> i64 {
+    let year = year as i64 - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let month = month as i64;
+    let doy = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_exchange_formats() {
+        let deribit = parse_symbol("BTC-10JAN25-100000-C").unwrap();
+        assert_eq!(deribit.underlying, "BTC");
+        assert_eq!(deribit.expiry, ExpiryDate::new(2025, 1, 10).unwrap());
+        assert_eq!(deribit.expiration_ts, 1736496000);
+        assert_eq!(deribit.expiry.code(), "10JAN25");
+
+        let decimal = parse_symbol("XRP_USDC-7FEB25-2d5-P").unwrap();
+        assert_eq!(decimal.strike, 2.5);
+        assert_eq!(decimal.option_type, "put");
+
+        let okx = parse_symbol("BTC-USD-250110-100000-P").unwrap();
+        assert_eq!(okx.format, SymbolFormat::Okx);
+        assert_eq!(okx.underlying, "BTC-USD");
+        assert_eq!(okx.expiration_ts, 1736496000);
+        assert_eq!(okx.option_type, "put");
+
+        // Last Friday of January 2025 is the 31st; 16:00 London = 16:00 UTC in winter
+        let cme = parse_symbol("BTCF5 C100000").unwrap();
+        assert_eq!(cme.format, SymbolFormat::Cme);
+        assert_eq!(cme.underlying, "BTC");
+        assert_eq!(cme.expiry, ExpiryDate::new(2025, 1, 31).unwrap());
+        assert_eq!(cme.expiration_ts, 1738339200);
+        // June 2025: last Friday the 27th, during BST
+        let summer = parse_symbol("ETHM25 P3200.5").unwrap();
+        assert_eq!(summer.expiry, ExpiryDate::new(2025, 6, 27).unwrap());
+        assert_eq!(summer.expiration_ts, 1751036400);
+        assert_eq!(summer.strike, 3200.5);
+    }
+
+    #[test]
+    fn rejects_malformed_symbols() {
+        for symbol in [
+            "BTC-10JAN25-100000",
+            "BTC-32JAN25-100000-C",
+            "BTC-10JAX25-100000-C",
+            "BTC-10JAN25-abc-C",
+            "BTC-10JAN25-100000-X",
+            "BTC-USD-251310-100000-C",
+            "BTCA5 C100000",
+            "F5 C100000",
+            "BTCF5 X100000",
+            "BTC-10ABé5-100-C",
+            "BTCé5 C100000",
+            "BTCF5 é100000",
+            "BTC",
+        ] {
+            assert!(parse_symbol(symbol).is_err(), "{symbol}");
+        }
+        let err = SymbolFormat::Okx.parse("BTC-10JAN25-100000-C").unwrap_err();
+        assert!(err.to_string().contains("not a valid OKX option symbol"));
+    }
+
+    #[test]
+    fn expiry_date_forms() {
+        let date = ExpiryDate::new(2024, 2, 29).unwrap();
+        for s in ["29FEB24", "240229", "2024-02-29", "29feb24"] {
+            assert_eq!(s.parse::<ExpiryDate>().unwrap(), date, "{s}");
+        }
+        assert!("29FEB25".parse::<ExpiryDate>().is_err());
+        assert!("10ABé5".parse::<ExpiryDate>().is_err());
+        assert_eq!(date.to_string(), "2024-02-29");
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2000, 3, 1), 11017);
+    }
+}
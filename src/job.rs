@@ -0,0 +1,321 @@
+//! Calibration job specifications
+//!
+//! A [`JobSpec`] bundles everything needed to run a calibration from a single TOML
+//! document: where the quotes come from, the model, the optimizer profile, parameter
+//! bounds, [`SviModelParams`], regularisation and the fixed-tenor temporal settings.
+//!
+//! ```toml
+//! name = "btc-daily"
+//! model = "svi"
+//! reg_lambda = 0.01
+//!
+//! [data]
+//! path = "snapshots/options_20250101.csv"
+//! format = "csv"
+//! expirations = [1736496000]      # empty or omitted = all expiries
+//!
+//! [optimizer]                     # an optimizer profile, see `calibration::profile`
+//! preset = "production"
+//! pop_size = 40
+//!
+//! [bounds]                        # any omitted parameter keeps its default interval
+//! rho = [-0.95, 0.5]
+//! sigma = [0.005, 1.5]
+//!
+//! [model_params]
+//! atm_boost_factor = 15.0
+//! use_vega_weighting = false
+//!
+//! [temporal]
+//! fixed_days = [7, 14, 30]
+//! ```
+//!
+//! Only `[data]` is required. Unknown sections and fields, mistyped values and
+//! out-of-range settings are reported with the section and field they belong to.
+
+use crate::calibration::config::OptimizationConfig;
+use crate::calibration::profile::{merge_overrides, profile_from_table};
+use crate::model_params::SviModelParams;
+use crate::models::linear_iv::TemporalConfig;
+use crate::models::svi::svi_calibrator::SVIParamBounds;
+use crate::snapshot::ModelType;
+use crate::CalibrationParams;
+use anyhow::{anyhow, Context, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// File format of a data source
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DataFormat {
+    /// Comma-separated option snapshot
+    #[default]
+    Csv,
+}
+
+/// Where the quotes of a job come from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DataSource {
+    /// Path to the quotes; relative paths in a spec file are resolved against its directory
+    pub path: PathBuf,
+    #[serde(default)]
+    pub format: DataFormat,
+    /// Expiration timestamps to calibrate (empty = all)
+    #[serde(default)]
+    pub expirations: Vec<i64>,
+}
+
+/// Full calibration job loaded from TOML
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct JobSpec {
+    /// Optional job name for logs and reports
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub model: ModelType,
+    /// Strength of temporal regularisation (None = library default)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reg_lambda: Option<f64>,
+    pub data: DataSource,
+    pub optimizer: OptimizationConfig,
+    /// Parameter bounds (None = calibrator defaults)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bounds: Option<SVIParamBounds>,
+    pub model_params: SviModelParams,
+    /// Fixed-tenor settings for term-structure outputs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temporal: Option<TemporalConfig>,
+}
+
+/// Top-level keys of a job spec
+const SECTIONS: [&str; 8] = [
+    "name",
+    "model",
+    "reg_lambda",
+    "data",
+    "optimizer",
+    "bounds",
+    "model_params",
+    "temporal",
+];
+
+impl JobSpec {
+    /// Parse and validate a job spec.
+    pub fn from_toml(toml_str: &str) -> Result<Self> {
+        let table: toml::Table = toml_str.parse().context("invalid TOML in job spec")?;
+        Self::from_table(table).context("invalid job spec")
+    }
+
+    /// Load and validate a job spec from disk, resolving a relative data path against
+    /// the spec's directory.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read job spec {}", path.display()))?;
+        let mut spec =
+            Self::from_toml(&text).with_context(|| format!("in job spec {}", path.display()))?;
+        if spec.data.path.is_relative() {
+            if let Some(dir) = path.parent() {
+                spec.data.path = dir.join(&spec.data.path);
+            }
+        }
+        Ok(spec)
+    }
+
+    /// Serialize the spec as TOML that [`JobSpec::from_toml`] reads back unchanged.
+    pub fn to_toml(&self) -> Result<String> {
+        toml::to_string_pretty(self).context("failed to serialize job spec")
+    }
+
+    /// Check every section for out-of-range settings.
+    pub fn validate(&self) -> Result<()> {
+        if self.data.path.as_os_str().is_empty() {
+            return Err(anyhow!("[data]: `path` must not be empty"));
+        }
+        self.optimizer.validate().context("[optimizer]")?;
+        if let Some(bounds) = &self.bounds {
+            bounds.validate().context("[bounds]")?;
+        }
+        let boost = self.model_params.atm_boost_factor;
+        if !(boost >= 0.0 && boost.is_finite()) {
+            return Err(anyhow!(
+                "[model_params]: `atm_boost_factor` must be >= 0 and finite (got {})",
+                boost
+            ));
+        }
+        if let Some(lambda) = self.reg_lambda {
+            if !(lambda >= 0.0 && lambda.is_finite()) {
+                return Err(anyhow!(
+                    "`reg_lambda` must be >= 0 and finite (got {})",
+                    lambda
+                ));
+            }
+        }
+        if let Some(temporal) = &self.temporal {
+            temporal.validate().context("[temporal]")?;
+        }
+        Ok(())
+    }
+
+    /// Calibration parameters described by the spec.
+    pub fn calibration_params(&self) -> CalibrationParams {
+        CalibrationParams {
+            param_bounds: self.bounds.clone(),
+            model_params: Some(Box::new(self.model_params.clone())),
+            reg_lambda: self.reg_lambda,
+            ..CalibrationParams::default()
+        }
+    }
+
+    fn from_table(mut table: toml::Table) -> Result<Self> {
+        if let Some(key) = table.keys().find(|k| !SECTIONS.contains(&k.as_str())) {
+            return Err(anyhow!(
+                "unknown field `{}` (expected one of: {})",
+                key,
+                SECTIONS.join(", ")
+            ));
+        }
+
+        let data = section::<DataSource>(&mut table, "data")?
+            .ok_or_else(|| anyhow!("missing required section [data]"))?;
+        let optimizer = match table.remove("optimizer") {
+            None => OptimizationConfig::default(),
+            Some(toml::Value::Table(profile)) => profile_from_table(profile, "optimizer")?,
+            Some(other) => return Err(not_a_table("optimizer", &other)),
+        };
+        let bounds = match table.remove("bounds") {
+            None => None,
+            Some(toml::Value::Table(overrides)) => {
+                let mut merged = toml::Table::try_from(SVIParamBounds::default())?;
+                merge_overrides(&mut merged, overrides, "bounds")?;
+                Some(deserialize(merged.into(), "bounds")?)
+            }
+            Some(other) => return Err(not_a_table("bounds", &other)),
+        };
+
+        let spec = Self {
+            name: section(&mut table, "name")?,
+            model: section(&mut table, "model")?.unwrap_or(ModelType::Svi),
+            reg_lambda: section(&mut table, "reg_lambda")?,
+            data,
+            optimizer,
+            bounds,
+            model_params: section(&mut table, "model_params")?.unwrap_or_default(),
+            temporal: section(&mut table, "temporal")?,
+        };
+        spec.validate()?;
+        Ok(spec)
+    }
+}
+
+/// Remove and deserialize an optional top-level entry
+fn section<T: DeserializeOwned>(table: &mut toml::Table, key: &str) -> Result<Option<T>> {
+    table
+        .remove(key)
+        .map(|value| deserialize(value, key))
+        .transpose()
+}
+
+fn deserialize<T: DeserializeOwned>(value: toml::Value, key: &str) -> Result<T> {
+    let is_table = value.is_table();
+    value.try_into().map_err(|e| {
+        if is_table {
+            anyhow!("[{}]: {}", key, e)
+        } else {
+            anyhow!("`{}`: {}", key, e)
+        }
+    })
+}
+
+fn not_a_table(key: &str, value: &toml::Value) -> anyhow::Error {
+    anyhow!("`{}` must be a table, got {}", key, value.type_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: &str = r#"
+        name = "btc-daily"
+        reg_lambda = 0.01
+
+        [data]
+        path = "quotes.csv"
+        expirations = [1736496000]
+
+        [optimizer]
+        preset = "minimal"
+        pop_size = 12
+
+        [bounds]
+        rho = [-0.95, 0.5]
+
+        [model_params]
+        atm_boost_factor = 10
+
+        [temporal]
+        fixed_days = [7, 30]
+    "#;
+
+    #[test]
+    fn parses_and_roundtrips() {
+        let spec = JobSpec::from_toml(SPEC).unwrap();
+        assert_eq!(spec.name.as_deref(), Some("btc-daily"));
+        assert_eq!(spec.model, ModelType::Svi);
+        assert_eq!(spec.data.format, DataFormat::Csv);
+        assert_eq!(spec.data.expirations, vec![1736496000]);
+        assert_eq!(spec.optimizer.pop_size, 12);
+        assert_eq!(
+            spec.optimizer.max_gen,
+            OptimizationConfig::minimal().max_gen
+        );
+
+        let bounds = spec.bounds.clone().unwrap();
+        assert_eq!(bounds.rho, (-0.95, 0.5));
+        assert_eq!(bounds.a, SVIParamBounds::default().a);
+        assert_eq!(spec.model_params.atm_boost_factor, 10.0);
+        assert!(spec.model_params.use_vega_weighting);
+        assert_eq!(spec.temporal.as_ref().unwrap().fixed_days, vec![7, 30]);
+
+        let params = spec.calibration_params();
+        assert_eq!(params.param_bounds, Some(bounds));
+        assert_eq!(params.reg_lambda, Some(0.01));
+
+        let reloaded = JobSpec::from_toml(&spec.to_toml().unwrap()).unwrap();
+        assert_eq!(reloaded, spec);
+    }
+
+    #[test]
+    fn errors_name_the_section() {
+        let err = |toml: &str| format!("{:#}", JobSpec::from_toml(toml).unwrap_err());
+
+        assert!(err("[optimizer]\npreset = \"fast\"").contains("missing required section [data]"));
+        assert!(err("[data]\npath = \"q.csv\"\n[solver]\n").contains("unknown field `solver`"));
+        assert!(
+            err("[data]\npath = \"q.csv\"\nurl = \"x\"").contains("[data]: unknown field `url`")
+        );
+        assert!(
+            err("[data]\npath = \"q.csv\"\n[optimizer.cmaes]\nsigma0 = 0")
+                .contains("`cmaes.sigma0` must be >= 0.25")
+        );
+        assert!(err("[data]\npath = \"q.csv\"\n[optimizer]\npop_sise = 3")
+            .contains("unknown field `optimizer.pop_sise`"));
+        assert!(err("[data]\npath = \"q.csv\"\n[bounds]\nrho = [-1.5, 0.0]")
+            .contains("`rho` bounds (-1.5, 0) must lie inside (-1, 1)"));
+        assert!(
+            err("[data]\npath = \"q.csv\"\n[bounds]\nsigma = [0.5, 0.1]").contains("lower < upper")
+        );
+        assert!(
+            err("[data]\npath = \"q.csv\"\n[model_params]\natm_boost = 3.0")
+                .contains("[model_params]: unknown field `atm_boost`")
+        );
+        assert!(
+            err("[data]\npath = \"q.csv\"\n[temporal]\nfixed_days = [7, 7]")
+                .contains("duplicate day 7")
+        );
+        assert!(err("model = \"heston\"\n[data]\npath = \"q.csv\"").contains("unknown variant"));
+        assert!(err("reg_lambda = -1.0\n[data]\npath = \"q.csv\"")
+            .contains("`reg_lambda` must be >= 0"));
+    }
+}
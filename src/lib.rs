@@ -31,7 +31,7 @@
 //!     t: 0.0274, a: params[0], b: params[1],
 //!     rho: params[2], m: params[3], sigma: params[4]
 //! };
-//! let fixed_params = FixedParameters { r: 0.02, q: 0.0 };
+//! let fixed_params = FixedParameters::flat(0.02, 0.0);
 //!
 //! // Price options with calibrated model
 //! let pricing_results = price_with_svi(svi_params, market_data, fixed_params);
@@ -56,8 +56,22 @@
 // ================================================================================================
 
 pub mod calibration;
+pub mod clock;
+pub mod curves;
+#[cfg(feature = "serde")]
+pub mod history;
+pub mod io;
+#[cfg(feature = "serde")]
+pub mod job;
 pub mod model_params;
 pub mod models;
+#[cfg(feature = "plot")]
+pub mod plot;
+pub mod portfolio;
+pub mod realized;
+pub mod scenario;
+#[cfg(feature = "serde")]
+pub mod snapshot;
 
 // ================================================================================================
 // IMPORTS
@@ -86,33 +100,84 @@ use crate::calibration::pipeline::calibrate_model_adaptive;
 
 // Core types for market data and configuration
 pub use calibration::{
-    config::{CmaEsConfig, OptimizationConfig},
-    types::{FixedParameters, MarketDataRow, PricingResult},
+    config::{CmaEsConfig, OptimizationConfig, OptimizerKind},
+    control::{CalibrationControl, CancellationToken},
+    observer::{CalibrationEvent, CalibrationObserver, CalibrationStage},
+    optimizer::Optimizer,
+    profile::{load_profile, profile_from_toml, profile_to_toml, Preset},
+    types::{CalibrationOutcome, FixedParameters, MarketDataRow, PricingResult},
 };
 
 // SVI model types and parameters
-pub use models::svi::{svi_calibrator::SVIParamBounds, svi_model::SVIParams};
+pub use models::svi::{
+    svi_calibrator::SVIParamBounds,
+    svi_engine::{
+        RecalibrationConfig, RecalibrationEngine, RecalibrationReason, SliceDrift, SliceStatus,
+        SnapshotSubscriber, SurfaceSnapshot,
+    },
+    svi_model::{SVIModel, SVIParams, SviInterpolation},
+    svi_surface::{
+        calibrate_svi_surface, SliceDiagnostics, SliceFailure, SurfaceCalibration,
+        SurfaceCalibrationOptions,
+    },
+};
+
+// Variance clocks and wall-clock tenors
+pub use clock::{Tenor, VarianceClock};
+
+// Realised volatility estimators
+pub use realized::{
+    bars_from_ticks, realized_variance, realized_vol, realized_windows, Bar, RealizedEstimator,
+    RealizedWindow,
+};
+
+// Portfolio valuation and risk
+pub use portfolio::{Portfolio, Position, PositionRisk, RiskConfig, RiskReport, VegaBucket};
+
+// Scenario and stress analysis
+pub use scenario::{
+    run_scenarios, Scenario, ScenarioPricing, ScenarioResult, ShockedSurface, SpotDynamics,
+};
+
+// Rate and dividend curves
+pub use curves::{CurveInterpolation, Dividend, DividendSchedule, RateCurve};
 
 // Linear IV model types and functions
 pub use models::linear_iv::{
     build_fixed_time_metrics,
+    build_fixed_time_metrics_from_market_data,
+    build_fixed_time_metrics_with_forwards,
     build_linear_iv,
     build_linear_iv_from_market_data,
     compute_atm_iv,
     compute_fixed_delta_iv,
+    delta_log_moneyness,
+    implied_event_moves,
+    DeltaConvention,
     DeltaIv,
     DeltaMetrics,
+    EventMove,
     FixedTimeMetrics,
+    ForwardCurve,
     LinearIvConfig,
     LinearIvOutput,
+    SmilePoint,
     TemporalConfig,
     // Temporal interpolation types and functions
     TemporalInterpMethod,
+    VolEvent,
 };
 
 // Model parameter types
 pub use model_params::{ModelParams, SviModelParams};
 
+// Snapshot ingestion
+pub use io::{load_snapshot, parse_symbol, ColumnMap, IvUnit, OptionQuote, SnapshotReader};
+
+// Job specifications
+#[cfg(feature = "serde")]
+pub use job::{DataFormat, DataSource, JobSpec};
+
 // Model parameters for users
 
 // ================================================================================================
@@ -246,6 +311,9 @@ pub struct CalibrationParams {
     /// Strength of temporal regularisation on raw parameters (λ).
     /// None = library default (1e-2) when an initial guess is supplied.
     pub reg_lambda: Option<f64>,
+    /// Wall-clock deadline and cancellation token for the run.
+    /// The default never stops early.
+    pub control: CalibrationControl,
 }
 
 impl Default for CalibrationParams {
@@ -254,6 +322,7 @@ impl Default for CalibrationParams {
             param_bounds: None,
             model_params: Some(Box::new(model_params::SviModelParams::default())),
             reg_lambda: None,
+            control: CalibrationControl::default(),
         }
     }
 }
@@ -272,6 +341,17 @@ impl CalibrationParams {
     }
 }
 
+/// Clone type-erased model parameters if they are [`SviModelParams`]; otherwise `None`.
+pub(crate) fn clone_model_params(
+    model_params: &Option<Box<dyn ModelParams>>,
+) -> Option<Box<dyn ModelParams>> {
+    model_params.as_ref().and_then(|mp| {
+        mp.as_any()
+            .downcast_ref::<SviModelParams>()
+            .map(|p| Box::new(p.clone()) as Box<dyn ModelParams>)
+    })
+}
+
 /// Calibrate SVI model parameters to market option data.
 ///
 /// This function performs advanced optimization to fit SVI model parameters to observed market
@@ -294,6 +374,9 @@ impl CalibrationParams {
 /// - `Vec<f64>`: Optimized SVI parameters `[a, b, rho, m, sigma]`
 /// - `SVIParamBounds`: The actual bounds used during optimization (can be fed back as input)
 ///
+/// If [`CalibrationParams::control`] stops the run early, the best parameters found so far are
+/// returned; use [`calibrate_svi_detailed`] to find out whether that happened.
+///
 /// # Errors
 ///
 /// * `anyhow::Error` if the data contains multiple expirations (SVI requires single expiration)
@@ -349,9 +432,55 @@ pub fn calibrate_svi(
     calib_params: CalibrationParams,
     initial_guess: Option<Vec<f64>>,
 ) -> Result<(f64, Vec<f64>, SVIParamBounds)> {
-    // Create SVI calibrator with user-provided parameters
+    let outcome = calibrate_svi_detailed(data, config, calib_params, initial_guess)?;
+
+    // Convert the bounds vector back to SVIParamBounds
+    let used_bounds = SVIParamBounds::from(outcome.bounds.as_slice());
+
+    Ok((outcome.objective, outcome.params, used_bounds))
+}
+
+/// Calibrate SVI model parameters and return the full [`CalibrationOutcome`].
+///
+/// Behaves exactly like [`calibrate_svi`], but also reports whether the run was truncated by
+/// the deadline or cancellation token in [`CalibrationParams::control`]. A truncated outcome
+/// holds the best parameters evaluated before the run stopped.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use surface_lib::{calibrate_svi_detailed, default_configs, CalibrationControl, CalibrationParams};
+///
+/// # let market_data = vec![];
+/// let calib_params = CalibrationParams {
+///     control: CalibrationControl::new().with_timeout(Duration::from_millis(200)),
+///     ..CalibrationParams::default()
+/// };
+/// let outcome = calibrate_svi_detailed(market_data, default_configs::production(), calib_params, None)?;
+/// if outcome.truncated {
+///     println!("Deadline hit, best objective so far: {:.6}", outcome.objective);
+/// }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn calibrate_svi_detailed(
+    data: Vec<InternalMarketDataRow>,
+    config: InternalOptimizationConfig,
+    calib_params: CalibrationParams,
+    initial_guess: Option<Vec<f64>>,
+) -> Result<CalibrationOutcome> {
+    // Every quote needs a positive dividend-adjusted spot to have a moneyness to fit at
+    for row in &data {
+        config
+            .fixed_params
+            .checked_adjusted_spot(row.underlying_price, row.years_to_exp)?;
+    }
+
+    // Create SVI calibrator with user-provided parameters, fitting in dividend-adjusted
+    // moneyness so slices straddling an ex-date line up
     let mut calibrator =
-        SVIModelCalibrator::new(&data, calib_params.param_bounds, calib_params.model_params)?;
+        SVIModelCalibrator::new(&data, calib_params.param_bounds, calib_params.model_params)?
+            .with_fixed_params(config.fixed_params.clone());
 
     // If we have an initial guess, use it both as warm-start and as regularisation anchor
     if let Some(ref guess) = initial_guess {
@@ -361,13 +490,13 @@ pub fn calibrate_svi(
     }
 
     // Execute calibration using adaptive pipeline directly
-    let (best_obj, best_params, bounds_vec) =
-        calibrate_model_adaptive(Box::new(calibrator), &data, &config, initial_guess);
-
-    // Convert the bounds vector back to SVIParamBounds
-    let used_bounds = SVIParamBounds::from(bounds_vec.as_slice());
-
-    Ok((best_obj, best_params, used_bounds))
+    Ok(calibrate_model_adaptive(
+        Box::new(calibrator),
+        &data,
+        &config,
+        initial_guess,
+        &calib_params.control,
+    ))
 }
 
 /// Evaluate the SVI calibration objective for a fixed parameter set.
@@ -376,24 +505,26 @@ pub fn calibrate_svi(
 /// internally, honouring any ATM-boost and vega-weighting settings embedded in
 /// `calib_params`.  It enables external callers (e.g. live monitoring) to
 /// measure model fit quality without re-running the optimiser.
+///
+/// Pass the calibration config's `fixed_params` so quotes are scored at the same
+/// dividend-adjusted moneyness calibration fits them at; errors if dividends leave a
+/// quote's adjusted spot non-positive.
 pub fn evaluate_svi(
     data: Vec<MarketDataRow>,
     params: SVIParams,
     calib_params: CalibrationParams,
+    fixed_params: &FixedParameters,
 ) -> Result<f64> {
     use crate::calibration::types::ModelCalibrator;
-    use crate::model_params::SviModelParams;
     use models::svi::svi_calibrator::SVIModelCalibrator;
 
-    // Clone model_params if it is SviModelParams; otherwise pass None.
-    let mp_clone: Option<Box<dyn crate::model_params::ModelParams>> =
-        calib_params.model_params.as_ref().and_then(|mp| {
-            mp.as_any()
-                .downcast_ref::<SviModelParams>()
-                .map(|p| Box::new(p.clone()) as Box<dyn crate::model_params::ModelParams>)
-        });
+    for row in &data {
+        fixed_params.checked_adjusted_spot(row.underlying_price, row.years_to_exp)?;
+    }
 
-    let calibrator = SVIModelCalibrator::new(&data, calib_params.param_bounds.clone(), mp_clone)?;
+    let mp_clone = clone_model_params(&calib_params.model_params);
+    let calibrator = SVIModelCalibrator::new(&data, calib_params.param_bounds.clone(), mp_clone)?
+        .with_fixed_params(fixed_params.clone());
 
     let p_vec = vec![params.a, params.b, params.rho, params.m, params.sigma];
     Ok(ModelCalibrator::evaluate_objective(
@@ -413,7 +544,8 @@ pub fn evaluate_svi(
 ///
 /// * `params` - Calibrated SVI parameters containing the time to expiration and model coefficients
 /// * `market_data` - Option contracts to price (can be same or different from calibration data)
-/// * `fixed_params` - Market parameters including risk-free rate and dividend yield
+/// * `fixed_params` - Risk-free rate and dividend yield curves, queried at each option's
+///   `years_to_exp`
 ///
 /// # Returns
 ///
@@ -446,11 +578,8 @@ pub fn evaluate_svi(
 ///     sigma: 0.2,     // Curvature
 /// };
 ///
-/// // Market parameters
-/// let fixed_params = FixedParameters {
-///     r: 0.02,        // 2% risk-free rate
-///     q: 0.0,         // No dividend yield
-/// };
+/// // Market parameters: 2% risk-free rate, no dividend yield
+/// let fixed_params = FixedParameters::flat(0.02, 0.0);
 ///
 /// // Price options
 /// let pricing_results = price_with_svi(svi_params, market_data, fixed_params);
@@ -482,8 +611,6 @@ pub fn price_with_svi(
 ) -> Vec<PricingResult> {
     // Create SVI volatility slice from parameters
     let slice = SVISlice::new(params);
-    let r = fixed_params.r;
-    let q = fixed_params.q;
 
     // Pre-allocate results vector for efficiency
     let mut results = Vec::with_capacity(market_data.len());
@@ -494,9 +621,8 @@ pub fn price_with_svi(
             &row.option_type,
             row.strike_price,
             row.underlying_price,
-            r,
-            q,
             row.years_to_exp,
+            &fixed_params,
             &slice,
         )
         .unwrap_or(OptionPricingResult {
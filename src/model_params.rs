@@ -18,7 +18,8 @@ pub trait ModelParams: Send + Sync + std::fmt::Debug {
 }
 
 /// Parameters that influence the SVI calibrator.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct SviModelParams {
     /// Exponential weight multiplier for ATM options in the objective function.
     ///
@@ -142,7 +142,21 @@ pub fn compute_atm_iv(points: &[MarketDataRow], forward: f64, tte: f64) -> Resul
 /// Black-Scholes delta calculation with dividend yield
 /// Uses the standard normal CDF from statrs for precision
 /// x = ln(K/F), so d1 uses -x for standard Black-Scholes formula
+/// This is the spot delta; see [`bs_delta_with_convention`] for the other conventions
 pub fn bs_delta(x: f64, sigma: f64, tte: f64, is_call: bool, q: f64) -> f64 {
+    bs_delta_with_convention(x, sigma, tte, is_call, q, DeltaConvention::Spot)
+}
+
+/// Black-Scholes delta under the given [`DeltaConvention`]
+/// x = ln(K/F); q only enters through the e^(-qT) factor of the spot conventions
+pub fn bs_delta_with_convention(
+    x: f64,
+    sigma: f64,
+    tte: f64,
+    is_call: bool,
+    q: f64,
+    convention: DeltaConvention,
+) -> f64 {
     if sigma <= 0.0 || tte <= 0.0 {
         return if is_call { 0.0 } else { -1.0 };
     }
@@ -151,16 +165,108 @@ pub fn bs_delta(x: f64, sigma: f64, tte: f64, is_call: bool, q: f64) -> f64 {
     let d1 = -x / (sigma * tte.sqrt()) + 0.5 * sigma * tte.sqrt();
     let normal = Normal::new(0.0, 1.0).unwrap();
 
-    // Apply dividend yield factor e^(-q*T)
-    let fwd_factor = (-q * tte).exp();
+    // Apply dividend yield factor e^(-q*T) for spot deltas
+    let fwd_factor = if convention.is_spot() {
+        (-q * tte).exp()
+    } else {
+        1.0
+    };
 
-    if is_call {
+    if convention.is_premium_adjusted() {
+        // Premium paid in the underlying: delta - premium / spot = (K/F) N(d2) for calls
+        let d2 = d1 - sigma * tte.sqrt();
+        let strike_ratio = x.exp();
+        if is_call {
+            strike_ratio * normal.cdf(d2) * fwd_factor
+        } else {
+            -strike_ratio * normal.cdf(-d2) * fwd_factor
+        }
+    } else if is_call {
         normal.cdf(d1) * fwd_factor
     } else {
         (normal.cdf(d1) - 1.0) * fwd_factor
     }
 }
 
+/// Log-moneyness `x = ln(K/F)` at which an option with volatility `sigma` has delta
+/// `target_delta` (positive for calls, negative for puts) under `convention`
+///
+/// Spot and forward deltas invert in closed form. Premium-adjusted call deltas can match
+/// a target twice; the OTM (higher-strike) solution is returned, as in
+/// [`compute_fixed_delta_iv_with_config`].
+pub fn delta_log_moneyness(
+    target_delta: f64,
+    sigma: f64,
+    tte: f64,
+    q: f64,
+    convention: DeltaConvention,
+) -> Result<f64> {
+    if sigma <= 0.0 || tte <= 0.0 {
+        return Err(anyhow!(
+            "Volatility and time to expiration must be positive (sigma={}, tte={})",
+            sigma,
+            tte
+        ));
+    }
+    let is_call = target_delta > 0.0;
+    let sd = sigma * tte.sqrt();
+
+    if !convention.is_premium_adjusted() {
+        let fwd_factor = if convention.is_spot() {
+            (-q * tte).exp()
+        } else {
+            1.0
+        };
+        // N(d1) for calls, N(-d1) for puts
+        let probability = target_delta.abs() / fwd_factor;
+        if !(probability > 0.0 && probability < 1.0) {
+            return Err(anyhow!(
+                "target_delta={} is outside the {} delta range",
+                target_delta,
+                convention
+            ));
+        }
+        let normal = Normal::new(0.0, 1.0).unwrap();
+        let d1 = if is_call {
+            normal.inverse_cdf(probability)
+        } else {
+            -normal.inverse_cdf(probability)
+        };
+        // d1 = -x / sd + sd / 2
+        return Ok(sd * (0.5 * sd - d1));
+    }
+
+    let objective =
+        |x: f64| bs_delta_with_convention(x, sigma, tte, is_call, q, convention) - target_delta;
+    let half_width = 8.0 * sd + sd * sd + 1.0;
+    let grid: Vec<(f64, f64)> = (0..=DELTA_BRACKET_SAMPLES)
+        .map(|i| {
+            let x = -half_width + 2.0 * half_width * i as f64 / DELTA_BRACKET_SAMPLES as f64;
+            (x, objective(x))
+        })
+        .collect();
+    let is_crossing = |w: &&[(f64, f64)]| w[0].1 >= 0.0 && w[1].1 <= 0.0;
+    let bracket = if is_call {
+        grid.windows(2).rev().find(is_crossing)
+    } else {
+        grid.windows(2).find(is_crossing)
+    }
+    .ok_or_else(|| {
+        anyhow!(
+            "No {} strike for target_delta={} at sigma={}",
+            convention,
+            target_delta,
+            sigma
+        )
+    })?;
+    find_root_brent(bracket[0].0, bracket[1].0, &objective, &mut 1e-12)
+        .map_err(|_| anyhow!("Root finding failed for target_delta={}", target_delta))
+}
+
+/// Grid samples used to bracket premium-adjusted delta roots before refining with Brent's
+/// method
+const DELTA_BRACKET_SAMPLES: usize = 400;
+
 /// Solve for the log-moneyness that gives the target delta
 /// Uses Brent's method for robust convergence
 pub fn compute_fixed_delta_iv(
@@ -169,11 +275,24 @@ pub fn compute_fixed_delta_iv(
     tte: f64,
     tol: f64,
 ) -> Result<f64> {
-    compute_fixed_delta_iv_with_config(target_delta, sorted_points, tte, tol, true, 0.0)
+    compute_fixed_delta_iv_with_config(
+        target_delta,
+        sorted_points,
+        tte,
+        tol,
+        true,
+        0.0,
+        DeltaConvention::Spot,
+    )
 }
 
 /// Solve for the log-moneyness that gives the target delta with configurable extrapolation
-/// Uses Brent's method for robust convergence
+/// and delta convention
+///
+/// Spot and forward deltas are solved with Brent's method over the whole search range.
+/// Premium-adjusted call deltas are not monotone in strike and can match a target twice,
+/// so for those conventions the root is first bracketed on a grid and the OTM solution is
+/// taken: the highest-strike crossing for calls and the lowest-strike crossing for puts.
 pub fn compute_fixed_delta_iv_with_config(
     target_delta: f64,
     sorted_points: &[(f64, f64)],
@@ -181,6 +300,7 @@ pub fn compute_fixed_delta_iv_with_config(
     tol: f64,
     allow_extrapolation: bool,
     q: f64,
+    convention: DeltaConvention,
 ) -> Result<f64> {
     if sorted_points.is_empty() {
         return Err(anyhow!("No points available for delta solving"));
@@ -188,29 +308,18 @@ pub fn compute_fixed_delta_iv_with_config(
 
     let is_call = target_delta > 0.0;
 
-    // Define the objective function: bs_delta(x, sigma(x), tte, is_call, q) - target_delta
+    // Define the objective function: delta(x, sigma(x), tte, is_call, q) - target_delta
     let objective = |x: f64| -> f64 {
         let omega = match linear_interp_with_config(sorted_points, x, allow_extrapolation) {
             Some(w) if w > 0.0 => w,
             _ => {
-                // More neutral fallback values to avoid biasing the solver
-                // Return a large error to push solver away from this region
-                return if is_call {
-                    if target_delta > 0.0 {
-                        -10.0
-                    } else {
-                        10.0
-                    }
-                } else if target_delta < 0.0 {
-                    10.0
-                } else {
-                    -10.0
-                };
+                // Without a variance, treat x as far OTM: |delta| below the target
+                return if is_call { -10.0 } else { 10.0 };
             }
         };
 
         let sigma = (omega / tte).sqrt();
-        bs_delta(x, sigma, tte, is_call, q) - target_delta
+        bs_delta_with_convention(x, sigma, tte, is_call, q, convention) - target_delta
     };
 
     // Determine search bounds based on sorted points
@@ -221,8 +330,53 @@ pub fn compute_fixed_delta_iv_with_config(
     let search_min = min_x - 1.0;
     let search_max = max_x + 1.0;
 
+    // Spot and forward deltas are monotone in x, so the whole range brackets the root.
+    // Premium-adjusted call deltas rise and then fall again, so the range is scanned for
+    // the OTM crossing first
+    let (lo, hi) = if convention.is_premium_adjusted() {
+        // Both call and put objectives go from positive (ITM side for calls, OTM side for
+        // puts) to negative as x increases; the OTM crossing is the last one for calls and
+        // the first one for puts
+        let grid: Vec<(f64, f64)> = (0..=DELTA_BRACKET_SAMPLES)
+            .map(|i| {
+                let x = search_min
+                    + (search_max - search_min) * i as f64 / DELTA_BRACKET_SAMPLES as f64;
+                (x, objective(x))
+            })
+            .collect();
+        let is_crossing = |w: &&[(f64, f64)]| w[0].1 >= 0.0 && w[1].1 <= 0.0;
+        let bracket = if is_call {
+            grid.windows(2).rev().find(is_crossing)
+        } else {
+            grid.windows(2).find(is_crossing)
+        };
+        let Some(bracket) = bracket else {
+            if is_call {
+                let max_delta = grid
+                    .iter()
+                    .map(|&(_, f)| f + target_delta)
+                    .fold(f64::NEG_INFINITY, f64::max);
+                return Err(anyhow!(
+                    "target_delta={} exceeds the maximum {} call delta {:.4}",
+                    target_delta,
+                    convention,
+                    max_delta
+                ));
+            }
+            return Err(anyhow!(
+                "Root finding failed for target_delta={}: no bracket in x=[{}, {}]",
+                target_delta,
+                search_min,
+                search_max
+            ));
+        };
+        (bracket[0].0, bracket[1].0)
+    } else {
+        (search_min, search_max)
+    };
+
     // Use Brent's method to find the root
-    match find_root_brent(search_min, search_max, &objective, &mut tol.clone()) {
+    match find_root_brent(lo, hi, &objective, &mut tol.clone()) {
         Ok(x_solution) => {
             // Convert back to implied volatility
             let omega =
@@ -305,23 +459,34 @@ pub fn compute_metrics(delta_ivs: &[DeltaIv], atm_iv: f64) -> (Option<f64>, Opti
 
 /// Check if points have reasonable coverage for delta calculations
 /// Logs warnings if asymmetric coverage might affect certain deltas
-fn check_point_coverage(points: &[MarketDataRow], config: &LinearIvConfig) {
+fn check_point_coverage(points: &[MarketDataRow], tte: f64, config: &LinearIvConfig) {
     let has_calls = points.iter().any(|p| p.option_type == "call");
     let has_puts = points.iter().any(|p| p.option_type == "put");
 
     let has_negative_deltas = config.deltas.iter().any(|&d| d < 0.0);
     let has_positive_deltas = config.deltas.iter().any(|&d| d > 0.0);
 
+    let expiry = points.first().map_or(0, |p| p.expiration);
+
     if has_negative_deltas && !has_puts {
-        eprintln!("Warning: Negative deltas requested but no put options in data. Some delta calculations may fail.");
+        log::warn!(
+            expiry, tte, points = points.len();
+            "negative deltas requested but no put options in data, some delta calculations may fail"
+        );
     }
 
     if has_positive_deltas && !has_calls {
-        eprintln!("Warning: Positive deltas requested but no call options in data. Some delta calculations may fail.");
+        log::warn!(
+            expiry, tte, points = points.len();
+            "positive deltas requested but no call options in data, some delta calculations may fail"
+        );
     }
 
     if !has_calls && !has_puts {
-        eprintln!("Warning: No clear option type classification in data.");
+        log::warn!(
+            expiry, tte, points = points.len();
+            "no clear option type classification in data"
+        );
     }
 }
 
@@ -358,7 +523,7 @@ pub fn build_linear_iv(
     }
 
     // Check for potential issues with point coverage
-    check_point_coverage(points, config);
+    check_point_coverage(points, tte, config);
 
     // Compute ATM IV
     let atm_iv = compute_atm_iv(points, forward, tte)?;
@@ -376,7 +541,8 @@ pub fn build_linear_iv(
             tte,
             config.solver_tol,
             config.allow_extrapolation,
-            config.dividend_yield,
+            config.fixed_params.dividend_yield(tte),
+            config.delta_convention,
         ) {
             Ok(iv) => {
                 delta_ivs.push(DeltaIv { delta, iv });
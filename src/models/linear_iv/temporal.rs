@@ -29,6 +29,24 @@
 //! Scales volatility by √(T_target/T_base). Common approximation for
 //! short-term extrapolation when volatility is mean-reverting.
 //!
+//! # Variance Clock
+//!
+//! All three methods interpolate along the [`VarianceClock`](crate::clock::VarianceClock)
+//! of `TemporalConfig::clock`, ACT/365 by default. With a business-time clock a Monday
+//! expiry seen on Friday sits about one trading day out rather than three calendar days,
+//! so weekly and 0DTE expiries line up across weekends. IVs are still reported annualised
+//! over calendar time.
+//!
+//! # Events
+//!
+//! Earnings, FOMC meetings and token unlocks add a jump of variance to every expiry after
+//! them. Each of `TemporalConfig::events` has its variance removed from the ATM and smile
+//! IVs of later maturities before interpolation and added back to targets after the event
+//! (`FixedTimeMetrics::event_variance`), so a target just before the event does not pick
+//! up part of it. Variances that are not given are estimated from the term structure;
+//! [`implied_event_moves`] reports them as implied moves. Risk reversals and butterflies
+//! are interpolated as quoted.
+//!
 //! # Usage Pattern
 //!
 //! 1. Collect multi-maturity option chain data
@@ -58,8 +76,12 @@
 use anyhow::{anyhow, Result};
 use std::collections::HashMap;
 
-use super::interp::build_linear_iv;
+use super::interp::{
+    bs_delta_with_convention, build_linear_iv, delta_log_moneyness, linear_interp_with_config,
+    prepare_points,
+};
 use super::types::*;
+use crate::clock::implied_valuation_time;
 
 /// Floating point epsilon for temporal interpolation comparisons
 /// Generous tolerance to handle day/year conversions and accumulated rounding
@@ -67,7 +89,7 @@ const TEMPORAL_EPSILON: f64 = 1e-8;
 
 /// Group market data by time-to-expiration, returning sorted groups
 /// Each group contains all market data for a single maturity
-fn group_by_tte(data: &[MarketDataRow]) -> Vec<(f64, Vec<MarketDataRow>)> {
+pub(crate) fn group_by_tte(data: &[MarketDataRow]) -> Vec<(f64, Vec<MarketDataRow>)> {
     let mut tte_to_data: HashMap<String, Vec<MarketDataRow>> = HashMap::new();
 
     // Group by TTE with limited precision to handle floating point issues
@@ -182,54 +204,56 @@ fn temporal_interp(
 
 /// Interpolate a single metric value using the specified temporal method
 ///
-/// This function extracts a specific metric from multiple maturity outputs and
-/// interpolates it temporally using one of three supported methods. It handles
-/// the conversion between interpolation methods transparently.
+/// This function interpolates one metric, observed at multiple maturities, temporally
+/// using one of three supported methods. It handles the conversion between
+/// interpolation methods transparently.
 ///
 /// # Arguments
 ///
-/// * `tte_metrics` - Array of (TTE, output) pairs from multiple maturities
-/// * `target_tte` - Target time-to-expiration for interpolation
+/// * `observations` - (clock time, calendar TTE, metric value) triples sorted by clock time
+/// * `target_time` - Target clock time for interpolation
+/// * `target_tte` - Target calendar time-to-expiration, which IVs are annualised over
 /// * `method` - Temporal interpolation method to use
 /// * `allow_short_extrap` - Enable extrapolation below minimum observed TTE
 /// * `allow_long_extrap` - Enable extrapolation above maximum observed TTE  
-/// * `metric_extractor` - Closure to extract metric value from LinearIvOutput
 ///
 /// # Method-Specific Behavior
 ///
-/// * **LinearTte**: Direct interpolation on (TTE, metric) pairs
-/// * **LinearVariance**: Converts to total variance, interpolates, converts back
-/// * **SquareRootTime**: Scales metric by sqrt(target_tte/observed_tte) ratio
+/// * **LinearTte**: Direct interpolation on (clock time, metric) pairs
+/// * **LinearVariance**: Converts to total variance, interpolates in clock time, converts back
+/// * **SquareRootTime**: Scales metric by sqrt(target_time/observed_time) ratio
+///
+/// With the calendar clock the clock time of each observation is its `tte`.
 fn interpolate_metric_value(
-    tte_metrics: &[(f64, LinearIvOutput)],
+    observations: &[(f64, f64, f64)],
+    target_time: f64,
     target_tte: f64,
     method: TemporalInterpMethod,
     allow_short_extrap: bool,
     allow_long_extrap: bool,
-    metric_extractor: impl Fn(&LinearIvOutput) -> f64,
 ) -> Option<f64> {
-    let metric_pairs: Vec<(f64, f64)> = tte_metrics
+    let metric_pairs: Vec<(f64, f64)> = observations
         .iter()
-        .map(|(tte, output)| (*tte, metric_extractor(output)))
+        .map(|&(time, _, value)| (time, value))
         .collect();
 
     match method {
         TemporalInterpMethod::LinearTte => temporal_interp(
             &metric_pairs,
-            target_tte,
+            target_time,
             allow_short_extrap,
             allow_long_extrap,
         ),
         TemporalInterpMethod::LinearVariance => {
             // Convert to total variance (w = iv^2 * t), interpolate, then back to IV
-            let variance_pairs: Vec<(f64, f64)> = metric_pairs
+            let variance_pairs: Vec<(f64, f64)> = observations
                 .iter()
-                .map(|(tte, iv)| (*tte, iv * iv * tte))
+                .map(|&(time, tte, iv)| (time, iv * iv * tte))
                 .collect();
 
             let interpolated_variance = temporal_interp(
                 &variance_pairs,
-                target_tte,
+                target_time,
                 allow_short_extrap,
                 allow_long_extrap,
             )?;
@@ -243,16 +267,16 @@ fn interpolate_metric_value(
         TemporalInterpMethod::SquareRootTime => {
             // Scale by sqrt(t): iv_target = iv_base * sqrt(t_target / t_base)
             // Handle edge case of zero TTE
-            if target_tte <= 0.0 {
+            if target_time <= 0.0 {
                 return None;
             }
 
-            // Scale values by 1/sqrt(tte) for interpolation
+            // Scale values by 1/sqrt(t) for interpolation
             let scaled_pairs: Vec<(f64, f64)> = metric_pairs
                 .iter()
-                .filter_map(|(tte, iv)| {
-                    if *tte > 0.0 {
-                        Some((*tte, iv / tte.sqrt()))
+                .filter_map(|(time, iv)| {
+                    if *time > 0.0 {
+                        Some((*time, iv / time.sqrt()))
                     } else {
                         None // Skip invalid TTE values
                     }
@@ -265,12 +289,12 @@ fn interpolate_metric_value(
 
             let scaled_value = temporal_interp(
                 &scaled_pairs,
-                target_tte,
+                target_time,
                 allow_short_extrap,
                 allow_long_extrap,
             )?;
 
-            Some(scaled_value * target_tte.sqrt())
+            Some(scaled_value * target_time.sqrt())
         }
     }
 }
@@ -280,6 +304,10 @@ fn interpolate_metric_value(
 /// This is the main function for temporal interpolation, taking multi-maturity option
 /// data and producing interpolated volatility metrics at standardized time points.
 ///
+/// Every maturity uses the same `forward`; see [`build_fixed_time_metrics_with_forwards`]
+/// for a forward per maturity and [`build_fixed_time_metrics_from_market_data`] for
+/// forwards taken from each maturity's `underlying_price`.
+///
 /// # Arguments
 ///
 /// * `data` - Multi-maturity option chain data with consistent underlying and forward
@@ -330,6 +358,7 @@ fn interpolate_metric_value(
 ///     allow_short_extrapolate: true,
 ///     allow_long_extrapolate: false,
 ///     min_maturities: 2,
+///     ..Default::default()
 /// };
 /// let strike_config = LinearIvConfig::default();
 ///
@@ -351,80 +380,114 @@ pub fn build_fixed_time_metrics(
     forward: f64,
     temp_config: &TemporalConfig,
     strike_config: &LinearIvConfig,
+) -> Result<Vec<FixedTimeMetrics>> {
+    build_fixed_time_metrics_with_forwards(
+        data,
+        &ForwardCurve::flat(forward)?,
+        temp_config,
+        strike_config,
+    )
+}
+
+/// Build fixed time metrics with each maturity's forward taken from its market data
+///
+/// Uses [`ForwardCurve::from_market_data`]: the mean `underlying_price` of each TTE group,
+/// e.g. the futures index of each expiry. Forwards at the fixed days are interpolated
+/// from that curve.
+pub fn build_fixed_time_metrics_from_market_data(
+    data: &[MarketDataRow],
+    temp_config: &TemporalConfig,
+    strike_config: &LinearIvConfig,
 ) -> Result<Vec<FixedTimeMetrics>> {
     if data.is_empty() {
         return Err(anyhow!("No market data provided"));
     }
+    let forwards = ForwardCurve::from_market_data(data)?;
+    build_fixed_time_metrics_with_forwards(data, &forwards, temp_config, strike_config)
+}
 
-    // Group data by time-to-expiration
-    let tte_groups = group_by_tte(data);
-
-    if tte_groups.len() < temp_config.min_maturities {
-        return Err(anyhow!(
-            "Insufficient maturities: {} < {}",
-            tte_groups.len(),
-            temp_config.min_maturities
-        ));
-    }
-
-    // Build LinearIvOutput for each maturity
-    let mut maturity_outputs = Vec::new();
-
-    for (tte, group_data) in &tte_groups {
-        match build_linear_iv(group_data, forward, *tte, strike_config) {
-            Ok(output) => {
-                maturity_outputs.push((*tte, output));
-            }
-            Err(e) => {
-                return Err(anyhow!("Failed to build linear IV for TTE {}: {}", tte, e));
-            }
-        }
-    }
-
-    if maturity_outputs.is_empty() {
-        return Err(anyhow!("No valid maturity outputs produced"));
-    }
-
-    // Sort by TTE for interpolation
-    maturity_outputs.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+/// Build fixed time metrics with a forward curve
+///
+/// Same as [`build_fixed_time_metrics`], except each maturity group is interpolated in
+/// log-moneyness against `forwards.forward(tte)`, and each [`FixedTimeMetrics::forward`]
+/// is the curve's forward at the fixed day.
+pub fn build_fixed_time_metrics_with_forwards(
+    data: &[MarketDataRow],
+    forwards: &ForwardCurve,
+    temp_config: &TemporalConfig,
+    strike_config: &LinearIvConfig,
+) -> Result<Vec<FixedTimeMetrics>> {
+    let TermStructure {
+        valuation_time,
+        maturities,
+        events,
+    } = build_term_structure(data, forwards, temp_config, strike_config)?;
+    let interpolate = |observations: &[(f64, f64, f64)], target_time: f64, target_tte: f64| {
+        interpolate_metric_value(
+            observations,
+            target_time,
+            target_tte,
+            temp_config.interp_method,
+            temp_config.allow_short_extrapolate,
+            temp_config.allow_long_extrapolate,
+        )
+        .filter(|value| *value > 0.0)
+    };
 
-    let min_tte = maturity_outputs[0].0;
-    let max_tte = maturity_outputs[maturity_outputs.len() - 1].0;
+    let min_time = maturities[0].time;
+    let max_time = maturities[maturities.len() - 1].time;
 
-    // Build metrics for each requested fixed day
+    // Build metrics for each requested tenor
     let mut results = Vec::new();
 
-    for &fixed_days in &temp_config.fixed_days {
-        let target_tte = fixed_days as f64 / 365.0;
+    for tenor in temp_config.targets() {
+        let target_tte = tenor.calendar_years(valuation_time);
+        let expiration = tenor.expiration(valuation_time);
+        let target_time = clock_time(temp_config, valuation_time, target_tte, expiration);
 
         // Check if this point should be skipped due to extrapolation settings
         // Use epsilon comparison for floating point precision
-        if (target_tte - min_tte) < -TEMPORAL_EPSILON && !temp_config.allow_short_extrapolate {
+        if (target_time - min_time) < -TEMPORAL_EPSILON && !temp_config.allow_short_extrapolate {
             continue;
         }
-        if (target_tte - max_tte) > TEMPORAL_EPSILON && !temp_config.allow_long_extrapolate {
+        if (target_time - max_time) > TEMPORAL_EPSILON && !temp_config.allow_long_extrapolate {
             continue;
         }
 
-        // Interpolate ATM IV
-        let atm_iv = interpolate_metric_value(
-            &maturity_outputs,
-            target_tte,
-            temp_config.interp_method,
-            temp_config.allow_short_extrapolate,
-            temp_config.allow_long_extrapolate,
-            |output| output.atm_iv,
-        );
+        // Variance of events before the target expiry, added back to every IV
+        let event_variance: f64 = events
+            .iter()
+            .filter(|e| e.time < expiration)
+            .map(|e| e.variance)
+            .sum();
+        let with_events = |iv: f64| {
+            if event_variance > 0.0 {
+                (iv * iv + event_variance / target_tte).sqrt()
+            } else {
+                iv
+            }
+        };
+        // Diffusive IVs of one smile point across maturities, interpolated to the target
+        let smile_iv = |iv_at: &dyn Fn(&Maturity) -> Option<f64>, min_observations: usize| {
+            let observations: Vec<(f64, f64, f64)> = maturities
+                .iter()
+                .filter_map(|m| m.diffusive(iv_at(m)?))
+                .collect();
+            if observations.len() < min_observations {
+                return None;
+            }
+            interpolate(&observations, target_time, target_tte).map(with_events)
+        };
 
-        let atm_iv = match atm_iv {
-            Some(iv) if iv > 0.0 => iv,
-            _ => continue, // Skip this point if ATM IV interpolation fails
+        // Interpolate ATM IV; skip this point if it fails
+        let Some(atm_iv) = smile_iv(&|m| Some(m.output.atm_iv), 1) else {
+            continue;
         };
 
         // Collect all unique delta levels across all maturities
         let mut all_delta_levels = std::collections::HashSet::new();
-        for (_, output) in &maturity_outputs {
-            for delta_metric in &output.delta_metrics {
+        for m in &maturities {
+            for delta_metric in &m.output.delta_metrics {
                 // Use limited precision for delta matching
                 let delta_key = format!("{:.6}", delta_metric.delta_level);
                 all_delta_levels.insert(delta_key);
@@ -438,25 +501,25 @@ pub fn build_fixed_time_metrics(
             let delta_level: f64 = delta_key.parse().unwrap();
 
             // Extract RR and BF values for this delta across all maturities
-            let rr_values: Vec<(f64, f64)> = maturity_outputs
+            let rr_values: Vec<(f64, f64)> = maturities
                 .iter()
-                .filter_map(|(tte, output)| {
-                    output
+                .filter_map(|m| {
+                    m.output
                         .delta_metrics
                         .iter()
                         .find(|dm| (dm.delta_level - delta_level).abs() < 1e-6)
-                        .map(|dm| (*tte, dm.risk_reversal))
+                        .map(|dm| (m.time, dm.risk_reversal))
                 })
                 .collect();
 
-            let bf_values: Vec<(f64, f64)> = maturity_outputs
+            let bf_values: Vec<(f64, f64)> = maturities
                 .iter()
-                .filter_map(|(tte, output)| {
-                    output
+                .filter_map(|m| {
+                    m.output
                         .delta_metrics
                         .iter()
                         .find(|dm| (dm.delta_level - delta_level).abs() < 1e-6)
-                        .map(|dm| (*tte, dm.butterfly))
+                        .map(|dm| (m.time, dm.butterfly))
                 })
                 .collect();
 
@@ -465,14 +528,14 @@ pub fn build_fixed_time_metrics(
                 // Interpolate RR and BF for this delta level
                 let rr = temporal_interp(
                     &rr_values,
-                    target_tte,
+                    target_time,
                     temp_config.allow_short_extrapolate,
                     temp_config.allow_long_extrapolate,
                 );
 
                 let bf = temporal_interp(
                     &bf_values,
-                    target_tte,
+                    target_time,
                     temp_config.allow_short_extrapolate,
                     temp_config.allow_long_extrapolate,
                 );
@@ -490,16 +553,316 @@ pub fn build_fixed_time_metrics(
         // Sort delta metrics by delta level for consistency
         delta_metrics.sort_by(|a, b| a.delta_level.partial_cmp(&b.delta_level).unwrap());
 
+        let forward = forwards.forward(target_tte);
+        let q = strike_config.fixed_params.dividend_yield(target_tte);
+        let convention = strike_config.delta_convention;
+
+        // Fixed-delta IVs and the strikes with those deltas
+        let mut delta_smile = Vec::new();
+        for &delta in &strike_config.deltas {
+            let Some(iv) = smile_iv(&|m| m.output.get_iv_for_delta(delta), 2) else {
+                continue;
+            };
+            if let Ok(x) = delta_log_moneyness(delta, iv, target_tte, q, convention) {
+                delta_smile.push(SmilePoint {
+                    log_moneyness: x,
+                    strike: forward * x.exp(),
+                    delta,
+                    iv,
+                });
+            }
+        }
+
+        // IVs on the moneyness grid
+        let mut moneyness_smile = Vec::new();
+        for (i, &x) in temp_config.moneyness_grid.iter().enumerate() {
+            let Some(iv) = smile_iv(&|m| m.grid_ivs[i], 2) else {
+                continue;
+            };
+            moneyness_smile.push(SmilePoint {
+                log_moneyness: x,
+                strike: forward * x.exp(),
+                delta: bs_delta_with_convention(x, iv, target_tte, x >= 0.0, q, convention),
+                iv,
+            });
+        }
+
         results.push(FixedTimeMetrics {
-            tte_days: fixed_days,
+            tenor,
+            tte_days: tenor.whole_days(valuation_time),
             tte_years: target_tte,
+            forward,
             atm_iv,
+            event_variance,
             delta_metrics,
+            delta_smile,
+            moneyness_smile,
         });
     }
 
-    // Sort results by TTE days
-    results.sort_by_key(|m| m.tte_days);
+    // Sort results by time to expiry
+    results.sort_by(|a, b| a.tte_years.total_cmp(&b.tte_years));
 
     Ok(results)
 }
+
+/// Variance of each of `temp_config.events` after the valuation time, in time order
+///
+/// Events with a configured variance are reported as given. The others are estimated
+/// from the ATM term structure, net of the known events, in clock time: the diffusive
+/// total variance at the first maturity after the event is extrapolated from the two
+/// maturities before it (or from zero through one), or, with no maturity before it,
+/// from the line through the first two maturities after it. The excess over that is
+/// the event variance.
+///
+/// ```rust,no_run
+/// use surface_lib::{implied_event_moves, ForwardCurve, LinearIvConfig, TemporalConfig, Tenor, VolEvent};
+///
+/// # let market_data: Vec<surface_lib::MarketDataRow> = vec![];
+/// let config = TemporalConfig {
+///     events: vec![VolEvent::new("earnings", Tenor::Days(10))],
+///     ..Default::default()
+/// };
+/// let forwards = ForwardCurve::from_market_data(&market_data)?;
+/// for event in implied_event_moves(&market_data, &forwards, &config, &LinearIvConfig::default())? {
+///     println!("{}: ±{:.1}%", event.name, event.implied_move * 100.0);
+/// }
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn implied_event_moves(
+    data: &[MarketDataRow],
+    forwards: &ForwardCurve,
+    temp_config: &TemporalConfig,
+    strike_config: &LinearIvConfig,
+) -> Result<Vec<EventMove>> {
+    Ok(build_term_structure(data, forwards, temp_config, strike_config)?.events)
+}
+
+/// One maturity of the term structure
+struct Maturity {
+    /// Position on the interpolation axis
+    time: f64,
+    expiration: i64,
+    output: LinearIvOutput,
+    /// IVs at `TemporalConfig::moneyness_grid`, where the smile can be read
+    grid_ivs: Vec<Option<f64>>,
+    /// Total variance of the events before this expiry
+    event_variance: f64,
+}
+
+impl Maturity {
+    /// ATM total variance net of event variance
+    fn atm_variance(&self) -> f64 {
+        self.output.atm_iv * self.output.atm_iv * self.output.tte - self.event_variance
+    }
+
+    /// (clock time, TTE, IV) observation with the event variance removed from `iv`, if
+    /// any variance is left
+    fn diffusive(&self, iv: f64) -> Option<(f64, f64, f64)> {
+        let tte = self.output.tte;
+        if self.event_variance == 0.0 {
+            return Some((self.time, tte, iv));
+        }
+        let variance = iv * iv * tte - self.event_variance;
+        (variance > 0.0).then(|| (self.time, tte, (variance / tte).sqrt()))
+    }
+}
+
+/// Maturities on the clock axis with the events they include
+struct TermStructure {
+    valuation_time: i64,
+    /// Sorted by clock time
+    maturities: Vec<Maturity>,
+    events: Vec<EventMove>,
+}
+
+fn build_term_structure(
+    data: &[MarketDataRow],
+    forwards: &ForwardCurve,
+    temp_config: &TemporalConfig,
+    strike_config: &LinearIvConfig,
+) -> Result<TermStructure> {
+    if data.is_empty() {
+        return Err(anyhow!("No market data provided"));
+    }
+
+    // Group data by time-to-expiration
+    let tte_groups = group_by_tte(data);
+
+    if tte_groups.len() < temp_config.min_maturities {
+        return Err(anyhow!(
+            "Insufficient maturities: {} < {}",
+            tte_groups.len(),
+            temp_config.min_maturities
+        ));
+    }
+
+    temp_config.clock.validate()?;
+    let valuation_time = temp_config
+        .valuation_time
+        .or_else(|| implied_valuation_time(data))
+        .unwrap_or_default();
+
+    // Build LinearIvOutput and the grid smile for each maturity
+    let mut maturities = Vec::new();
+
+    for (tte, group_data) in &tte_groups {
+        let forward = forwards.forward(*tte);
+        match build_linear_iv(group_data, forward, *tte, strike_config) {
+            Ok(output) => {
+                let expiration = group_data[0].expiration;
+                let points = prepare_points(group_data, forward, *tte);
+                let grid_ivs = temp_config
+                    .moneyness_grid
+                    .iter()
+                    .map(|&x| {
+                        linear_interp_with_config(&points, x, strike_config.allow_extrapolation)
+                            .filter(|w| *w > 0.0)
+                            .map(|w| (w / tte).sqrt())
+                    })
+                    .collect();
+                maturities.push(Maturity {
+                    time: clock_time(temp_config, valuation_time, *tte, expiration),
+                    expiration,
+                    output,
+                    grid_ivs,
+                    event_variance: 0.0,
+                });
+            }
+            Err(e) => {
+                return Err(anyhow!("Failed to build linear IV for TTE {}: {}", tte, e));
+            }
+        }
+    }
+
+    if maturities.is_empty() {
+        return Err(anyhow!("No valid maturity outputs produced"));
+    }
+
+    // Sort by clock time for interpolation
+    maturities.sort_by(|a, b| {
+        a.time
+            .total_cmp(&b.time)
+            .then(a.expiration.cmp(&b.expiration))
+    });
+
+    let events = remove_event_variance(&mut maturities, &temp_config.events, valuation_time)?;
+
+    Ok(TermStructure {
+        valuation_time,
+        maturities,
+        events,
+    })
+}
+
+/// Position on the interpolation axis of a maturity with calendar time `tte`
+fn clock_time(temp_config: &TemporalConfig, valuation_time: i64, tte: f64, expiration: i64) -> f64 {
+    if temp_config.clock.is_calendar() {
+        tte
+    } else {
+        temp_config.clock.variance_time(valuation_time, expiration)
+    }
+}
+
+/// Attribute the variance of `events` to every later maturity, estimating the variances
+/// that are not given; events at or before `valuation_time` are ignored
+fn remove_event_variance(
+    maturities: &mut [Maturity],
+    events: &[VolEvent],
+    valuation_time: i64,
+) -> Result<Vec<EventMove>> {
+    let mut pending: Vec<(i64, &VolEvent)> = events
+        .iter()
+        .map(|e| (e.time.expiration(valuation_time), e))
+        .filter(|(time, _)| *time > valuation_time)
+        .collect();
+    pending.sort_by_key(|(time, _)| *time);
+
+    // Known variances first, so estimates only see diffusive variance
+    for (time, event) in &pending {
+        if let Some(variance) = event.variance {
+            subtract_event_variance(maturities, *time, variance, &event.name)?;
+        }
+    }
+
+    let mut moves = Vec::with_capacity(pending.len());
+    for (i, (time, event)) in pending.iter().enumerate() {
+        let variance = match event.variance {
+            Some(variance) => variance,
+            None => {
+                let next_unknown = pending[i + 1..]
+                    .iter()
+                    .find(|(_, e)| e.variance.is_none())
+                    .map(|(t, _)| *t);
+                let variance =
+                    estimate_event_variance(maturities, *time, next_unknown).map_err(|e| {
+                        anyhow!("Cannot estimate variance of event `{}`: {}", event.name, e)
+                    })?;
+                subtract_event_variance(maturities, *time, variance, &event.name)?;
+                variance
+            }
+        };
+        moves.push(EventMove {
+            name: event.name.clone(),
+            time: *time,
+            variance,
+            implied_move: variance.sqrt(),
+            estimated: event.variance.is_none(),
+        });
+    }
+    Ok(moves)
+}
+
+fn subtract_event_variance(
+    maturities: &mut [Maturity],
+    event_time: i64,
+    variance: f64,
+    name: &str,
+) -> Result<()> {
+    if variance == 0.0 {
+        return Ok(());
+    }
+    for m in maturities.iter_mut().filter(|m| m.expiration > event_time) {
+        if m.atm_variance() - variance <= 0.0 {
+            return Err(anyhow!(
+                "Event `{}` variance {} exceeds the ATM total variance of expiry {}",
+                name,
+                variance,
+                m.expiration
+            ));
+        }
+        m.event_variance += variance;
+    }
+    Ok(())
+}
+
+/// Excess ATM total variance at the first maturity after `event_time` over the diffusive
+/// variance extrapolated in clock time, using no maturity after `next_unknown`
+fn estimate_event_variance(
+    maturities: &[Maturity],
+    event_time: i64,
+    next_unknown: Option<i64>,
+) -> Result<f64> {
+    let total_variance = |m: &Maturity| (m.time, m.atm_variance());
+    let split = maturities.partition_point(|m| m.expiration <= event_time);
+    let before: Vec<(f64, f64)> = maturities[..split].iter().map(total_variance).collect();
+    let after: Vec<(f64, f64)> = maturities[split..]
+        .iter()
+        .take_while(|m| next_unknown.is_none_or(|t| m.expiration <= t))
+        .map(total_variance)
+        .collect();
+    let Some(&(t_after, w_after)) = after.first() else {
+        return Err(anyhow!(
+            "no maturity expires after it and before the next event without a variance"
+        ));
+    };
+    let diffusive = match (before.as_slice(), after.as_slice()) {
+        ([.., (t1, w1), (t2, w2)], _) if t2 - t1 > TEMPORAL_EPSILON => {
+            w2 + (w2 - w1) / (t2 - t1) * (t_after - t2)
+        }
+        ([.., (t, w)], _) if *t > 0.0 => w * t_after / t,
+        ([], [(t1, w1), (t2, w2), ..]) if t2 - t1 > TEMPORAL_EPSILON => (w2 - w1) / (t2 - t1) * t1,
+        _ => return Err(anyhow!("needs a maturity before it or two after it")),
+    };
+    Ok((w_after - diffusive.max(0.0)).max(0.0))
+}
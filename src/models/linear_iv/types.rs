@@ -1,6 +1,101 @@
+use crate::calibration::types::FixedParameters;
+use crate::clock::{Tenor, VarianceClock};
+use anyhow::{anyhow, Result};
+use std::fmt;
+use std::str::FromStr;
+
 // Re-export MarketDataRow from calibration types for consistency
 pub use crate::calibration::types::MarketDataRow;
 
+/// Convention of the deltas in [`LinearIvConfig::deltas`]
+///
+/// With `x = ln(K/F)`, `D = e^(-qT)` and `d1`, `d2` the Black-Scholes terms:
+///
+/// | Convention | Call | Put |
+/// |---|---|---|
+/// | `Spot` | `D·N(d1)` | `-D·N(-d1)` |
+/// | `Forward` | `N(d1)` | `-N(-d1)` |
+/// | `PremiumAdjustedSpot` | `D·(K/F)·N(d2)` | `-D·(K/F)·N(-d2)` |
+/// | `PremiumAdjustedForward` | `(K/F)·N(d2)` | `-(K/F)·N(-d2)` |
+///
+/// Premium-adjusted deltas are quoted when the premium is paid in the underlying (crypto
+/// options, FX pairs with a foreign-currency premium). The premium-adjusted call delta is
+/// not monotone in strike: it rises from 0 to a maximum below 1 and falls back to 0, so
+/// each target below the maximum has an ITM and an OTM solution. The OTM one is used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum DeltaConvention {
+    /// Spot delta, `e^(-qT)·N(d1)`
+    #[default]
+    Spot,
+    /// Forward delta, `N(d1)`
+    Forward,
+    /// Premium-adjusted spot delta, `e^(-qT)·(K/F)·N(d2)`
+    PremiumAdjustedSpot,
+    /// Premium-adjusted forward delta, `(K/F)·N(d2)`
+    PremiumAdjustedForward,
+}
+
+impl DeltaConvention {
+    /// All conventions, in documentation order
+    pub const ALL: [DeltaConvention; 4] = [
+        DeltaConvention::Spot,
+        DeltaConvention::Forward,
+        DeltaConvention::PremiumAdjustedSpot,
+        DeltaConvention::PremiumAdjustedForward,
+    ];
+
+    /// Name used in configs and on the command line
+    pub fn name(self) -> &'static str {
+        match self {
+            DeltaConvention::Spot => "spot",
+            DeltaConvention::Forward => "forward",
+            DeltaConvention::PremiumAdjustedSpot => "premium_adjusted_spot",
+            DeltaConvention::PremiumAdjustedForward => "premium_adjusted_forward",
+        }
+    }
+
+    /// Whether the premium is included in the delta
+    pub fn is_premium_adjusted(self) -> bool {
+        matches!(
+            self,
+            DeltaConvention::PremiumAdjustedSpot | DeltaConvention::PremiumAdjustedForward
+        )
+    }
+
+    /// Whether the delta is discounted by the dividend yield, `e^(-qT)`
+    pub fn is_spot(self) -> bool {
+        matches!(
+            self,
+            DeltaConvention::Spot | DeltaConvention::PremiumAdjustedSpot
+        )
+    }
+}
+
+impl fmt::Display for DeltaConvention {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for DeltaConvention {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        DeltaConvention::ALL
+            .into_iter()
+            .find(|c| c.name() == s)
+            .ok_or_else(|| {
+                anyhow!(
+                    "unknown delta convention `{}` (expected one of: {})",
+                    s,
+                    DeltaConvention::ALL.map(DeltaConvention::name).join(", ")
+                )
+            })
+    }
+}
+
 /// Configuration for linear IV interpolation
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -13,10 +108,12 @@ pub struct LinearIvConfig {
     pub min_points: usize,
     /// Allow extrapolation beyond market data range
     pub allow_extrapolation: bool,
-    /// Risk-free interest rate (default: 0.0)
-    pub risk_free_rate: f64,
-    /// Dividend yield (default: 0.0)
-    pub dividend_yield: f64,
+    /// Risk-free rate and dividend yield curves, queried at each expiry's TTE
+    /// (default: flat 0.0 for both)
+    pub fixed_params: FixedParameters,
+    /// Convention of `deltas` (default: spot)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub delta_convention: DeltaConvention,
 }
 
 impl Default for LinearIvConfig {
@@ -26,8 +123,8 @@ impl Default for LinearIvConfig {
             solver_tol: 1e-6,
             min_points: 3,
             allow_extrapolation: true,
-            risk_free_rate: 0.0,
-            dividend_yield: 0.0,
+            fixed_params: FixedParameters::flat(0.0, 0.0),
+            delta_convention: DeltaConvention::Spot,
         }
     }
 }
@@ -108,10 +205,25 @@ pub enum TemporalInterpMethod {
 ///     allow_short_extrapolate: true,  // Enable 1d extrapolation
 ///     allow_long_extrapolate: false,  // Conservative on long end
 ///     min_maturities: 3,              // Require good coverage
+///     ..Default::default()
 /// };
 /// ```
-#[derive(Debug, Clone)]
+///
+/// Sub-day and dated tenors, interpolated in business time:
+///
+/// ```rust
+/// # use surface_lib::{Tenor, TemporalConfig, VarianceClock};
+/// let config = TemporalConfig {
+///     fixed_days: vec![1, 7],
+///     tenors: vec![Tenor::Hours(4), "2025-03-28T08:00:00Z".parse()?],
+///     clock: VarianceClock::weekends(0.3),
+///     ..Default::default()
+/// };
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
 pub struct TemporalConfig {
     /// Fixed days to interpolate to (e.g., [1, 3, 7, 14, 30])
     ///
@@ -143,6 +255,36 @@ pub struct TemporalConfig {
     /// Minimum number of distinct maturities needed for interpolation.
     /// Must be ≥ 2 for any interpolation. Higher values provide better stability.
     pub min_maturities: usize,
+
+    /// Additional targets in minutes, hours or at fixed expiry times
+    ///
+    /// Interpolated alongside `fixed_days`, e.g. `["4h", "2025-03-28T08:00:00Z"]`.
+    pub tenors: Vec<Tenor>,
+
+    /// Clock that maturities are interpolated in
+    ///
+    /// Maturities and targets are placed at their [variance time](VarianceClock::variance_time)
+    /// from the valuation time; the default calendar clock interpolates in ACT/365.
+    pub clock: VarianceClock,
+
+    /// Valuation time (Unix seconds) for the clock and dated tenors
+    ///
+    /// Defaults to the time implied by the data's `expiration` and `years_to_exp`
+    /// (see [`implied_valuation_time`](crate::clock::implied_valuation_time)).
+    pub valuation_time: Option<i64>,
+
+    /// Scheduled events (earnings, FOMC, token unlocks) carrying a variance jump
+    ///
+    /// Their variance is removed from the ATM level of every maturity expiring after them
+    /// before interpolation and added back to targets after them, so the diffusive term
+    /// structure is interpolated without steps.
+    pub events: Vec<VolEvent>,
+
+    /// Log-moneyness points `ln(K/F)` at which each target's smile is reported
+    ///
+    /// Each maturity's smile is read at the same forward moneyness and the IVs are
+    /// interpolated in time like the ATM level (see [`FixedTimeMetrics::moneyness_smile`]).
+    pub moneyness_grid: Vec<f64>,
 }
 
 impl Default for TemporalConfig {
@@ -153,6 +295,11 @@ impl Default for TemporalConfig {
             allow_short_extrapolate: false,
             allow_long_extrapolate: true,
             min_maturities: 2,
+            tenors: Vec::new(),
+            clock: VarianceClock::default(),
+            valuation_time: None,
+            events: Vec::new(),
+            moneyness_grid: Vec::new(),
         }
     }
 }
@@ -182,6 +329,225 @@ impl TemporalConfig {
     pub fn monthly() -> Self {
         Self::from_days(vec![30, 60, 90, 120])
     }
+
+    /// Check that the ladder is non-empty with distinct positive days, that
+    /// `min_maturities` allows interpolation (≥ 2), that the moneyness grid is finite,
+    /// that event variances are non-negative and that the clock is valid
+    pub fn validate(&self) -> Result<()> {
+        if self.fixed_days.is_empty() && self.tenors.is_empty() {
+            return Err(anyhow!(
+                "TemporalConfig validation: `fixed_days` and `tenors` must not both be empty"
+            ));
+        }
+        if let Some(day) = self.fixed_days.iter().find(|&&d| d <= 0) {
+            return Err(anyhow!(
+                "TemporalConfig validation: `fixed_days` entries must be > 0 (got {})",
+                day
+            ));
+        }
+        let mut days = self.fixed_days.clone();
+        days.sort_unstable();
+        if let Some(pair) = days.windows(2).find(|w| w[0] == w[1]) {
+            return Err(anyhow!(
+                "TemporalConfig validation: `fixed_days` contains duplicate day {}",
+                pair[0]
+            ));
+        }
+        if self.min_maturities < 2 {
+            return Err(anyhow!(
+                "TemporalConfig validation: `min_maturities` must be >= 2 (got {})",
+                self.min_maturities
+            ));
+        }
+        if let Some(x) = self.moneyness_grid.iter().find(|x| !x.is_finite()) {
+            return Err(anyhow!(
+                "TemporalConfig validation: `moneyness_grid` entries must be finite (got {})",
+                x
+            ));
+        }
+        if let Some(event) = self
+            .events
+            .iter()
+            .find(|e| e.variance.is_some_and(|v| !v.is_finite() || v < 0.0))
+        {
+            return Err(anyhow!(
+                "TemporalConfig validation: event `{}` variance must be finite and >= 0 (got {})",
+                event.name,
+                event.variance.unwrap_or_default()
+            ));
+        }
+        self.clock
+            .validate()
+            .map_err(|e| anyhow!("TemporalConfig validation: `clock`: {}", e))
+    }
+
+    /// Every target: `fixed_days` as [`Tenor::Days`], then `tenors`
+    pub fn targets(&self) -> impl Iterator<Item = Tenor> + '_ {
+        self.fixed_days
+            .iter()
+            .map(|&d| Tenor::Days(d))
+            .chain(self.tenors.iter().copied())
+    }
+}
+
+/// A scheduled event with a jump in variance
+///
+/// `variance` is the total variance of the event's log move, e.g. `0.0025` for a 5%
+/// standard deviation; when omitted it is estimated from the term structure. In configs:
+///
+/// ```toml
+/// [[temporal.events]]
+/// name = "earnings"
+/// time = "2025-01-30T21:00:00Z"
+/// variance = 0.0025
+/// ```
+///
+/// ```rust
+/// # use surface_lib::{Tenor, VolEvent};
+/// let fomc = VolEvent::new("FOMC", "2025-01-29T19:00:00Z".parse()?);
+/// let earnings = VolEvent::new("earnings", Tenor::Days(10)).with_move(0.05);
+/// assert!((earnings.variance.unwrap() - 0.0025).abs() < 1e-12);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
+pub struct VolEvent {
+    /// Label reported with the implied move
+    pub name: String,
+    /// When the event happens; relative tenors count from the valuation time
+    pub time: Tenor,
+    /// Total variance of the event move, estimated from the term structure when `None`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub variance: Option<f64>,
+}
+
+impl VolEvent {
+    /// Event whose variance is estimated from the term structure
+    pub fn new(name: impl Into<String>, time: Tenor) -> Self {
+        Self {
+            name: name.into(),
+            time,
+            variance: None,
+        }
+    }
+
+    /// Set the total variance of the event move
+    pub fn with_variance(mut self, variance: f64) -> Self {
+        self.variance = Some(variance);
+        self
+    }
+
+    /// Set the variance from the standard deviation of the event's log move
+    pub fn with_move(self, implied_move: f64) -> Self {
+        self.with_variance(implied_move * implied_move)
+    }
+}
+
+/// Forward prices by time to expiration
+///
+/// Interpolates `ln F` linearly in TTE between pillars, i.e. a constant implied carry
+/// rate between adjacent maturities, and extends the nearest segment's carry beyond the
+/// first and last pillars. A single pillar gives a flat curve.
+///
+/// ```rust
+/// # use surface_lib::ForwardCurve;
+/// let curve = ForwardCurve::new(vec![(0.1, 100.0), (0.3, 102.0)])?;
+/// assert!((curve.forward(0.2) - (100.0f64 * 102.0).sqrt()).abs() < 1e-9);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForwardCurve {
+    /// (TTE, forward) pillars sorted by TTE
+    points: Vec<(f64, f64)>,
+}
+
+impl ForwardCurve {
+    /// Build from (TTE, forward) pillars in any order.
+    ///
+    /// Pillars need finite TTEs ≥ 0, distinct TTEs and finite positive forwards.
+    pub fn new(mut points: Vec<(f64, f64)>) -> Result<Self> {
+        if points.is_empty() {
+            return Err(anyhow!("ForwardCurve needs at least one pillar"));
+        }
+        if let Some(&(tte, forward)) = points
+            .iter()
+            .find(|(tte, f)| !(tte.is_finite() && *tte >= 0.0 && f.is_finite() && *f > 0.0))
+        {
+            return Err(anyhow!(
+                "ForwardCurve pillar (tte={}, forward={}) must have tte >= 0 and forward > 0",
+                tte,
+                forward
+            ));
+        }
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        if let Some(pair) = points.windows(2).find(|w| w[1].0 - w[0].0 < 1e-12) {
+            return Err(anyhow!(
+                "ForwardCurve has duplicate pillar at tte={}",
+                pair[0].0
+            ));
+        }
+        Ok(Self { points })
+    }
+
+    /// The same forward at every maturity
+    pub fn flat(forward: f64) -> Result<Self> {
+        Self::new(vec![(0.0, forward)])
+    }
+
+    /// One pillar per maturity of `data`, at the mean `underlying_price` of its rows.
+    ///
+    /// Maturities are grouped as in [`build_fixed_time_metrics`](super::build_fixed_time_metrics),
+    /// so each group's pillar reproduces its own forward exactly.
+    pub fn from_market_data(data: &[MarketDataRow]) -> Result<Self> {
+        let points = super::temporal::group_by_tte(data)
+            .into_iter()
+            .map(|(tte, rows)| {
+                let mean = rows.iter().map(|r| r.underlying_price).sum::<f64>() / rows.len() as f64;
+                (tte, mean)
+            })
+            .collect();
+        Self::new(points)
+    }
+
+    /// One pillar per maturity of `data`, treating `underlying_price` as spot and carrying
+    /// its mean to each maturity with [`FixedParameters::forward`].
+    ///
+    /// Use this when quotes reference the spot of a dividend-paying stock rather than a
+    /// per-expiry forward: each pillar nets out the discrete dividends going ex before its
+    /// maturity.
+    pub fn from_spot_data(data: &[MarketDataRow], fixed_params: &FixedParameters) -> Result<Self> {
+        let points = super::temporal::group_by_tte(data)
+            .into_iter()
+            .map(|(tte, rows)| {
+                let spot = rows.iter().map(|r| r.underlying_price).sum::<f64>() / rows.len() as f64;
+                (tte, fixed_params.forward(spot, tte))
+            })
+            .collect();
+        Self::new(points)
+    }
+
+    /// Pillars sorted by TTE
+    pub fn points(&self) -> &[(f64, f64)] {
+        &self.points
+    }
+
+    /// Forward at `tte`
+    pub fn forward(&self, tte: f64) -> f64 {
+        let points = &self.points;
+        if points.len() == 1 {
+            return points[0].1;
+        }
+        // Segment containing tte, or the nearest one for extrapolation
+        let i = points
+            .windows(2)
+            .position(|w| tte <= w[1].0)
+            .unwrap_or(points.len() - 2);
+        let (t1, f1) = points[i];
+        let (t2, f2) = points[i + 1];
+        let w = (tte - t1) / (t2 - t1);
+        (f1.ln() + w * (f2.ln() - f1.ln())).exp()
+    }
 }
 
 /// Metrics for a specific fixed time-to-expiration point
@@ -202,7 +568,8 @@ impl TemporalConfig {
 ///
 /// ```rust
 /// # use surface_lib::FixedTimeMetrics;
-/// # let metrics = FixedTimeMetrics { tte_days: 30, tte_years: 30.0/365.0, atm_iv: 0.2, delta_metrics: vec![] };
+/// # use surface_lib::Tenor;
+/// # let metrics = FixedTimeMetrics { tenor: Tenor::Days(30), tte_days: 30, tte_years: 30.0/365.0, forward: 100.0, atm_iv: 0.2, event_variance: 0.0, delta_metrics: vec![], delta_smile: vec![], moneyness_smile: vec![] };
 /// println!("30d expiry: ATM IV = {:.1}%", metrics.atm_iv * 100.0);
 ///
 /// for dm in &metrics.delta_metrics {
@@ -215,18 +582,29 @@ impl TemporalConfig {
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FixedTimeMetrics {
+    /// Requested target, a `fixed_days` entry or one of `TemporalConfig::tenors`
+    pub tenor: Tenor,
+
     /// Time to expiration in calendar days
     ///
     /// Integer representation of the standardized expiry, matching the
-    /// requested `fixed_days` from `TemporalConfig`.
+    /// requested `fixed_days` from `TemporalConfig`; whole days, rounded down, for
+    /// other tenors.
     pub tte_days: i32,
 
     /// Time to expiration in years (day count = ACT/365)
     ///
-    /// Precise fractional representation used for all calculations.
-    /// Computed as `tte_days / 365.0` using actual/365 day count convention.
+    /// Computed as `tte_days / 365.0` for day tenors, otherwise from the tenor's
+    /// expiry time. Volatilities are annualised over this time whatever the clock.
     pub tte_years: f64,
 
+    /// Forward price at this time-to-expiration
+    ///
+    /// Interpolated from the forward curve used for the maturity groups
+    /// (see [`ForwardCurve::forward`]); equal to the input forward when a single
+    /// forward is given.
+    pub forward: f64,
+
     /// ATM implied volatility at this time-to-expiration
     ///
     /// Annualized implied volatility at the money (log-moneyness = 0),
@@ -234,10 +612,60 @@ pub struct FixedTimeMetrics {
     /// primary volatility level for this expiry.
     pub atm_iv: f64,
 
+    /// Total variance of the [events](TemporalConfig::events) before this expiry
+    ///
+    /// Included in `atm_iv`; zero without events.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub event_variance: f64,
+
     /// Delta metrics (RR and BF) for all available delta levels
     ///
     /// Risk reversal and butterfly metrics for symmetric delta pairs
     /// (e.g., ±10δ, ±25δ). Only populated for delta levels that have
     /// sufficient data across the input maturities.
     pub delta_metrics: Vec<DeltaMetrics>,
+
+    /// Smile at each of `LinearIvConfig::deltas`
+    ///
+    /// The IV at each delta is interpolated in time from the maturities' fixed-delta IVs;
+    /// the strike is the one with that delta at the interpolated IV and this forward.
+    /// Deltas quoted by fewer than two maturities are omitted.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub delta_smile: Vec<SmilePoint>,
+
+    /// Smile at each of `TemporalConfig::moneyness_grid`
+    ///
+    /// Deltas are those of the OTM option: calls at or above the forward, puts below.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub moneyness_smile: Vec<SmilePoint>,
+}
+
+/// One point of a constant-maturity smile
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SmilePoint {
+    /// Log-moneyness `ln(K/F)` against the target's forward
+    pub log_moneyness: f64,
+    /// Strike, `F·e^x`
+    pub strike: f64,
+    /// Delta under `LinearIvConfig::delta_convention`
+    pub delta: f64,
+    /// Implied volatility
+    pub iv: f64,
+}
+
+/// Variance of a [`VolEvent`] and the move it implies
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EventMove {
+    /// Event name
+    pub name: String,
+    /// Event time (Unix seconds)
+    pub time: i64,
+    /// Total variance of the event move
+    pub variance: f64,
+    /// Standard deviation of the event's log move, `sqrt(variance)`
+    pub implied_move: f64,
+    /// Whether the variance was estimated from the term structure
+    pub estimated: bool,
 }
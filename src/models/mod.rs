@@ -20,6 +20,7 @@ pub mod traits {
 
 /// Utility functions for option pricing and calculations
 pub mod utils {
+    use crate::calibration::types::FixedParameters;
     use crate::models::traits::SurfaceModel;
     use anyhow::{anyhow, Result};
 
@@ -35,16 +36,29 @@ pub mod utils {
     }
 
     /// Price an option using a surface model
+    ///
+    /// The risk-free rate and dividend yield are the zero rates of `fixed_params` at `t`.
+    /// Discrete dividends enter through the escrowed forward: the model is queried at
+    /// [`FixedParameters::log_moneyness`] and the option priced with Black-76 on
+    /// [`FixedParameters::forward`].
     pub fn price_option<T: SurfaceModel>(
         option_type: &str,
         strike: f64,
         spot: f64,
-        r: f64,
-        q: f64,
         t: f64,
+        fixed_params: &FixedParameters,
         model: &T,
     ) -> Result<OptionPricingResult> {
-        let k = log_moneyness(strike, spot);
+        let forward = fixed_params.forward(spot, t);
+        if forward <= 0.0 {
+            return Err(anyhow!(
+                "Non-positive forward {} for spot {} at t={}: dividends exceed the carried spot",
+                forward,
+                spot,
+                t
+            ));
+        }
+        let k = fixed_params.log_moneyness(strike, spot, t);
         let total_var = model.total_variance(k, t)?;
 
         if total_var <= 0.0 {
@@ -52,18 +66,20 @@ pub mod utils {
         }
 
         let model_iv = (total_var / t).sqrt();
-        let price = black_scholes_price(option_type, spot, strike, r, q, t, model_iv)?;
+        let discount = fixed_params.r.discount_factor(t);
+        let price = black76_price(option_type, forward, strike, discount, t, model_iv)?;
 
         Ok(OptionPricingResult { price, model_iv })
     }
 
-    /// Black-Scholes option pricing
-    fn black_scholes_price(
+    /// Black-76 option pricing on the forward `f`, discounted by `discount`
+    ///
+    /// With `f = S·e^((r - q)·t)` and `discount = e^(-r·t)` this is Black-Scholes.
+    fn black76_price(
         option_type: &str,
-        s: f64,
+        f: f64,
         k: f64,
-        r: f64,
-        q: f64,
+        discount: f64,
         t: f64,
         sigma: f64,
     ) -> Result<f64> {
@@ -71,12 +87,12 @@ pub mod utils {
             return Err(anyhow!("Invalid parameters: sigma={}, t={}", sigma, t));
         }
 
-        let d1 = ((s / k).ln() + (r - q + 0.5 * sigma * sigma) * t) / (sigma * t.sqrt());
+        let d1 = ((f / k).ln() + 0.5 * sigma * sigma * t) / (sigma * t.sqrt());
         let d2 = d1 - sigma * t.sqrt();
 
         let price = match option_type.to_lowercase().as_str() {
-            "call" => s * (-q * t).exp() * normal_cdf(d1) - k * (-r * t).exp() * normal_cdf(d2),
-            "put" => k * (-r * t).exp() * normal_cdf(-d2) - s * (-q * t).exp() * normal_cdf(-d1),
+            "call" => discount * (f * normal_cdf(d1) - k * normal_cdf(d2)),
+            "put" => discount * (k * normal_cdf(-d2) - f * normal_cdf(-d1)),
             _ => return Err(anyhow!("Invalid option type: {}", option_type)),
         };
 
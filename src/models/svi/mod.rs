@@ -1,2 +1,4 @@
 pub mod svi_calibrator;
+pub mod svi_engine;
 pub mod svi_model;
+pub mod svi_surface;
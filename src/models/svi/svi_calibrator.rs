@@ -7,16 +7,17 @@
 //! the ModelCalibrator trait and providing methods for parameter optimization.
 
 use crate::calibration::config::OptimizationConfig;
-use crate::calibration::types::{MarketDataRow, ModelCalibrator, PricingResult};
+use crate::calibration::types::{FixedParameters, MarketDataRow, ModelCalibrator, PricingResult};
 use crate::model_params::{ModelParams, SviModelParams};
 use crate::models::svi::svi_model::{SVIParams, SVISlice};
-use crate::models::utils::{log_moneyness, price_option, OptionPricingResult};
+use crate::models::utils::{price_option, OptionPricingResult};
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// Structure to hold parameter bounds for the SVI model calibration
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct SVIParamBounds {
     /// Vertical shift parameter bounds (controls ATM variance level)
     pub a: (f64, f64),
@@ -42,6 +43,50 @@ impl Default for SVIParamBounds {
     }
 }
 
+impl SVIParamBounds {
+    /// Check that every interval is finite, non-empty and admissible for its parameter
+    /// (`b >= 0`, `rho` inside (-1, 1), `sigma > 0`).
+    pub fn validate(&self) -> Result<()> {
+        let intervals = [
+            ("a", self.a),
+            ("b", self.b),
+            ("rho", self.rho),
+            ("m", self.m),
+            ("sigma", self.sigma),
+        ];
+        for (name, (lo, hi)) in intervals {
+            if !lo.is_finite() || !hi.is_finite() || lo >= hi {
+                return Err(anyhow!(
+                    "SVIParamBounds validation: `{}` bounds ({}, {}) must be finite with lower < upper",
+                    name,
+                    lo,
+                    hi
+                ));
+            }
+        }
+        if self.b.0 < 0.0 {
+            return Err(anyhow!(
+                "SVIParamBounds validation: `b` lower bound ({}) must be >= 0",
+                self.b.0
+            ));
+        }
+        if self.rho.0 <= -1.0 || self.rho.1 >= 1.0 {
+            return Err(anyhow!(
+                "SVIParamBounds validation: `rho` bounds ({}, {}) must lie inside (-1, 1)",
+                self.rho.0,
+                self.rho.1
+            ));
+        }
+        if self.sigma.0 <= 0.0 {
+            return Err(anyhow!(
+                "SVIParamBounds validation: `sigma` lower bound ({}) must be > 0",
+                self.sigma.0
+            ));
+        }
+        Ok(())
+    }
+}
+
 impl From<&[(f64, f64)]> for SVIParamBounds {
     fn from(bounds: &[(f64, f64)]) -> Self {
         if bounds.len() != 5 {
@@ -57,6 +102,17 @@ impl From<&[(f64, f64)]> for SVIParamBounds {
     }
 }
 
+/// Weighted total variance error of a single market point
+#[derive(Debug, Clone, Copy)]
+struct ErrorTerm {
+    /// Combined vega and ATM weight
+    weight: f64,
+    /// Log-moneyness of the point
+    k: f64,
+    /// Model minus market total variance
+    diff: f64,
+}
+
 /// Calibrator for the SVI model with 5 parameters per expiry:
 /// [a, b, rho, m, sigma]
 #[derive(Debug, Clone)]
@@ -69,6 +125,9 @@ pub struct SVIModelCalibrator {
     /// Model-specific parameters (e.g. ATM boost)
     params: SviModelParams,
 
+    /// Rates and dividends that set the moneyness each quote is fit at
+    fixed_params: FixedParameters,
+
     /// Optional previous solution for temporal regularization
     prev_solution: Option<Vec<f64>>,
     temporal_reg_lambda: f64,
@@ -171,11 +230,19 @@ impl SVIModelCalibrator {
             expiration,
             param_bounds,
             params,
+            fixed_params: FixedParameters::default(),
             prev_solution: None,
             temporal_reg_lambda: 0.0,
         })
     }
 
+    /// Fit quotes at [`FixedParameters::log_moneyness`] of these fixed parameters, so
+    /// discrete dividends shift each quote to its dividend-adjusted moneyness
+    pub fn with_fixed_params(mut self, fixed_params: FixedParameters) -> Self {
+        self.fixed_params = fixed_params;
+        self
+    }
+
     pub fn set_prev_solution(&mut self, prev_sol: Vec<f64>) {
         if prev_sol.len() == self.param_count() {
             self.prev_solution = Some(prev_sol);
@@ -185,51 +252,35 @@ impl SVIModelCalibrator {
     pub fn set_temporal_reg_lambda(&mut self, lambda: f64) {
         self.temporal_reg_lambda = lambda.max(0.0);
     }
-}
-
-impl ModelCalibrator for SVIModelCalibrator {
-    fn model_name(&self) -> &str {
-        "svi"
-    }
 
-    fn param_count(&self) -> usize {
-        self.param_bounds.len() // Should be 5
+    /// Previous solution, if temporal regularisation applies to `x`
+    fn active_prev_solution(&self, x: &[f64]) -> Option<&Vec<f64>> {
+        self.prev_solution
+            .as_ref()
+            .filter(|prev| self.temporal_reg_lambda > 0.0 && prev.len() == x.len())
     }
 
-    fn param_bounds(&self) -> &[(f64, f64)] {
-        &self.param_bounds
-    }
-
-    /// Evaluate objective function using vega-weighted RMSE on total variance with
-    /// an additional exponential ATM weighting.
-    /// x is the parameter vector [a, b, rho, m, sigma].
-    fn evaluate_objective(&self, x: &[f64], data: &[MarketDataRow]) -> f64 {
-        assert_eq!(
-            x.len(),
-            5,
-            "Input parameter vector length must be 5 for SVI model"
-        );
-
+    /// Per-point weighted total variance errors for this slice.
+    /// Returns `None` if `x` is not a valid SVI parameter set.
+    fn weighted_errors(&self, x: &[f64], data: &[MarketDataRow]) -> Option<Vec<ErrorTerm>> {
         let (exp_ts, t) = self.expiration;
 
         // 1. Build the SVI slice from the candidate parameters ----------------------------
-        let params = match SVIParams::new(t, x[0], x[1], x[2], x[3], x[4]) {
-            Ok(p) => p,
-            Err(_) => return 1.0e12, // Reject invalid parameter sets outright
-        };
+        let params = SVIParams::new(t, x[0], x[1], x[2], x[3], x[4]).ok()?;
         let slice = SVISlice::new(params);
 
-        // 2. Weighted error computation ----------------------------------------------------
-        let mut weighted_error_sum = 0.0;
-        let mut weight_sum = 0.0;
-        let mut valid_points = 0u32;
-
+        // 2. Weighted error terms ----------------------------------------------------------
+        let mut terms = Vec::with_capacity(data.len());
         for row in data {
             if row.expiration != exp_ts {
                 continue; // Keep only this slice's points
             }
 
-            let k = log_moneyness(row.strike_price, row.underlying_price);
+            let k = self.fixed_params.log_moneyness(
+                row.strike_price,
+                row.underlying_price,
+                row.years_to_exp,
+            );
             let model_iv = slice.implied_vol(k);
             let market_iv_dec = row.market_iv; // already in decimal form
 
@@ -243,7 +294,6 @@ impl ModelCalibrator for SVIModelCalibrator {
             let model_w = model_iv * model_iv * t;
             let market_w = market_iv_dec * market_iv_dec * t;
             let diff = model_w - market_w;
-            let squared_error = diff * diff;
 
             // --- Weighting scheme --------------------------------------------------------
             // 1. Vega weighting (optional)
@@ -258,14 +308,49 @@ impl ModelCalibrator for SVIModelCalibrator {
             };
             // 2. ATM emphasis – exponential decay as |k| grows.
             let atm_weight = (-self.params.atm_boost_factor * k.abs()).exp();
-            let weight = vega_weight * atm_weight;
 
-            weighted_error_sum += weight * squared_error;
-            weight_sum += weight;
-            valid_points += 1;
+            terms.push(ErrorTerm {
+                weight: vega_weight * atm_weight,
+                k,
+                diff,
+            });
         }
+        Some(terms)
+    }
+}
+
+impl ModelCalibrator for SVIModelCalibrator {
+    fn model_name(&self) -> &str {
+        "svi"
+    }
+
+    fn param_count(&self) -> usize {
+        self.param_bounds.len() // Should be 5
+    }
+
+    fn param_bounds(&self) -> &[(f64, f64)] {
+        &self.param_bounds
+    }
+
+    /// Evaluate objective function using vega-weighted RMSE on total variance with
+    /// an additional exponential ATM weighting.
+    /// x is the parameter vector [a, b, rho, m, sigma].
+    fn evaluate_objective(&self, x: &[f64], data: &[MarketDataRow]) -> f64 {
+        assert_eq!(
+            x.len(),
+            5,
+            "Input parameter vector length must be 5 for SVI model"
+        );
+
+        let terms = match self.weighted_errors(x, data) {
+            Some(terms) => terms,
+            None => return 1.0e12, // Reject invalid parameter sets outright
+        };
+
+        let weighted_error_sum: f64 = terms.iter().map(|e| e.weight * (e.diff * e.diff)).sum();
+        let weight_sum: f64 = terms.iter().map(|e| e.weight).sum();
 
-        if valid_points == 0 || weight_sum <= 1e-12 {
+        if terms.is_empty() || weight_sum <= 1e-12 {
             return 1.0e12; // Fail-safe if no usable points
         }
 
@@ -275,20 +360,103 @@ impl ModelCalibrator for SVIModelCalibrator {
         // -----------------------------------------------------------------------------------
         // Optional temporal regularisation on raw parameters
         // -----------------------------------------------------------------------------------
-        if let (Some(prev), lambda) = (&self.prev_solution, self.temporal_reg_lambda) {
-            if lambda > 0.0 && prev.len() == x.len() {
-                let penalty: f64 = x
-                    .iter()
-                    .zip(prev.iter())
-                    .map(|(v, p)| (v - p).powi(2))
-                    .sum::<f64>()
-                    * lambda;
-                obj += penalty;
-            }
+        if let Some(prev) = self.active_prev_solution(x) {
+            let penalty: f64 = x
+                .iter()
+                .zip(prev.iter())
+                .map(|(v, p)| (v - p).powi(2))
+                .sum::<f64>()
+                * self.temporal_reg_lambda;
+            obj += penalty;
         }
         obj
     }
 
+    /// Analytic gradient of the objective.
+    ///
+    /// With `S = Σ w_i d_i²`, `W = Σ w_i` and `d_i = w_model(k_i) − w_market(k_i)`, the
+    /// objective is `sqrt(S / W) + λ‖x − x_prev‖²`, so
+    /// `∂obj/∂x = (Σ w_i d_i ∂w_model/∂x) / (W · sqrt(S / W)) + 2λ(x − x_prev)`.
+    /// Points whose model variance is floored (non-positive raw SVI variance) contribute no
+    /// gradient, matching the objective which treats them as constant.
+    fn evaluate_gradient(&self, x: &[f64], data: &[MarketDataRow]) -> Option<Vec<f64>> {
+        if x.len() != 5 {
+            return None;
+        }
+
+        let terms = self.weighted_errors(x, data)?;
+        let weight_sum: f64 = terms.iter().map(|e| e.weight).sum();
+        if terms.is_empty() || weight_sum <= 1e-12 {
+            return None;
+        }
+
+        let (a, b, rho, m, sigma) = (x[0], x[1], x[2], x[3], x[4]);
+        let weighted_error_sum: f64 = terms.iter().map(|e| e.weight * (e.diff * e.diff)).sum();
+        let rmse = (weighted_error_sum / weight_sum).sqrt();
+
+        let mut grad = vec![0.0; 5];
+        if rmse > 0.0 {
+            for e in &terms {
+                let km = e.k - m;
+                let root = (km * km + sigma * sigma).sqrt();
+                if a + b * (rho * km + root) <= 0.0 {
+                    continue; // Floored variance: constant in the parameters
+                }
+                let dw = [
+                    1.0,
+                    rho * km + root,
+                    b * km,
+                    -b * (rho + km / root),
+                    b * sigma / root,
+                ];
+                let scale = e.weight * e.diff / (weight_sum * rmse);
+                for (g, d) in grad.iter_mut().zip(dw) {
+                    *g += scale * d;
+                }
+            }
+        }
+
+        if let Some(prev) = self.active_prev_solution(x) {
+            for ((g, v), p) in grad.iter_mut().zip(x).zip(prev) {
+                *g += 2.0 * self.temporal_reg_lambda * (v - p);
+            }
+        }
+        Some(grad)
+    }
+
+    /// Residuals whose squared norm is the square of the objective, so least-squares
+    /// backends minimise the same function as the others.
+    ///
+    /// The fit residuals are `sqrt(w_i / Σw) · (w_model − w_market)`, whose squared norm is
+    /// the weighted MSE. With temporal regularisation, `(rmse + λP)² = rmse² + 2λ·rmse·P +
+    /// λ²P²` with `P = ‖x − x_prev‖²` adds `sqrt(2λ·rmse) · (x − x_prev)` and `λP`.
+    fn evaluate_residuals(&self, x: &[f64], data: &[MarketDataRow]) -> Option<Vec<f64>> {
+        if x.len() != 5 {
+            return None;
+        }
+
+        let terms = self.weighted_errors(x, data)?;
+        let weight_sum: f64 = terms.iter().map(|e| e.weight).sum();
+        if terms.is_empty() || weight_sum <= 1e-12 {
+            return None;
+        }
+
+        let mut residuals: Vec<f64> = terms
+            .iter()
+            .map(|e| (e.weight / weight_sum).sqrt() * e.diff)
+            .collect();
+
+        if let Some(prev) = self.active_prev_solution(x) {
+            let lambda = self.temporal_reg_lambda;
+            let rmse = residuals.iter().map(|r| r * r).sum::<f64>().sqrt();
+            let scale = (2.0 * lambda * rmse).sqrt();
+            let penalty: f64 = x.iter().zip(prev).map(|(v, p)| (v - p).powi(2)).sum();
+            residuals.extend(x.iter().zip(prev).map(|(v, p)| scale * (v - p)));
+            residuals.push(lambda * penalty);
+        }
+        Some(residuals)
+    }
+
     // Note: create_param_map removed as param_map is no longer returned from calibration API
 
     fn price_options(
@@ -310,17 +478,15 @@ impl ModelCalibrator for SVIModelCalibrator {
         let final_params = match SVIParams::new(t, a, b, rho, m, sigma) {
             Ok(params) => params,
             Err(e) => {
-                eprintln!(
-                    "Error creating final SVIParams for pricing: {}. Using fallback parameters.",
-                    e
+                log::error!(
+                    expiry = exp_ts, tte = t, a, b, rho, m, sigma, error:% = e;
+                    "invalid calibrated SVI parameters for pricing, using fallback parameters"
                 );
                 SVIParams::new(0.1, 0.04, 0.2, -0.3, 0.0, 0.2).unwrap() // Fallback
             }
         };
         let final_slice = SVISlice::new(final_params);
 
-        let r = config.fixed_params.r;
-        let q = config.fixed_params.q;
         let mut results = Vec::with_capacity(market_data.len());
 
         for row in market_data {
@@ -336,9 +502,8 @@ impl ModelCalibrator for SVIModelCalibrator {
                         &row.option_type,
                         strike,
                         underlying,
-                        r,
-                        q,
                         t_row,
+                        &config.fixed_params,
                         &final_slice,
                     )
                 } else {
@@ -351,9 +516,9 @@ impl ModelCalibrator for SVIModelCalibrator {
                 let (model_price, model_iv) = match pricing_result {
                     Ok(pr) => (pr.price, pr.model_iv),
                     Err(e) => {
-                        eprintln!(
-                            "Error pricing option (exp={}, strike={}): {}",
-                            exp_ts, strike, e
+                        log::warn!(
+                            expiry = exp_ts, tte = t_row, strike, error:% = e;
+                            "failed to price option"
                         );
                         (0.0, 0.0)
                     }
@@ -418,3 +583,138 @@ impl ModelCalibrator for SVIModelCalibrator {
         adjusted
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_slice() -> Vec<MarketDataRow> {
+        [
+            (80.0, 0.32),
+            (90.0, 0.27),
+            (95.0, 0.245),
+            (100.0, 0.23),
+            (105.0, 0.225),
+            (110.0, 0.23),
+            (120.0, 0.25),
+        ]
+        .iter()
+        .map(|&(strike, iv)| MarketDataRow {
+            option_type: if strike < 100.0 { "put" } else { "call" }.to_string(),
+            strike_price: strike,
+            underlying_price: 100.0,
+            years_to_exp: 0.25,
+            market_iv: iv,
+            vega: 10.0 + strike / 20.0,
+            expiration: 1,
+        })
+        .collect()
+    }
+
+    fn assert_gradient_matches(calibrator: &SVIModelCalibrator, x: &[f64], data: &[MarketDataRow]) {
+        let grad = calibrator.evaluate_gradient(x, data).expect("gradient");
+        for i in 0..x.len() {
+            let h = 1e-6;
+            let mut xp = x.to_vec();
+            let mut xm = x.to_vec();
+            xp[i] += h;
+            xm[i] -= h;
+            let fd = (calibrator.evaluate_objective(&xp, data)
+                - calibrator.evaluate_objective(&xm, data))
+                / (2.0 * h);
+            assert!(
+                (grad[i] - fd).abs() < 1e-6 * fd.abs().max(1e-3),
+                "component {}: analytic {} vs finite difference {}",
+                i,
+                grad[i],
+                fd
+            );
+        }
+    }
+
+    #[test]
+    fn test_analytic_gradient_matches_finite_differences() {
+        let data = sample_slice();
+        let calibrator = SVIModelCalibrator::new(&data, None, None).unwrap();
+        assert_gradient_matches(&calibrator, &[0.01, 0.15, -0.4, 0.05, 0.2], &data);
+    }
+
+    #[test]
+    fn test_analytic_gradient_with_regularisation_and_flat_weights() {
+        let data = sample_slice();
+        let params = SviModelParams {
+            atm_boost_factor: 0.0,
+            use_vega_weighting: false,
+        };
+        let mut calibrator = SVIModelCalibrator::new(&data, None, Some(Box::new(params))).unwrap();
+        calibrator.set_prev_solution(vec![0.0, 0.2, -0.3, 0.0, 0.25]);
+        calibrator.set_temporal_reg_lambda(0.05);
+        assert_gradient_matches(&calibrator, &[0.005, 0.12, -0.5, -0.02, 0.3], &data);
+    }
+
+    #[test]
+    fn test_levenberg_marquardt_with_regularisation() {
+        use crate::calibration::optimizer::{
+            CalibrationProblem, LevenbergMarquardtOptimizer, Optimizer,
+        };
+
+        let data = sample_slice();
+        let mut calibrator = SVIModelCalibrator::new(&data, None, None).unwrap();
+        calibrator.set_prev_solution(vec![0.0, 0.2, -0.3, 0.0, 0.25]);
+        calibrator.set_temporal_reg_lambda(0.05);
+
+        // The residuals' norm is the regularised RMSE objective
+        let x0 = [0.005, 0.12, -0.5, -0.02, 0.3];
+        let norm = calibrator
+            .evaluate_residuals(&x0, &data)
+            .unwrap()
+            .iter()
+            .map(|r| r * r)
+            .sum::<f64>()
+            .sqrt();
+        assert!((norm - calibrator.evaluate_objective(&x0, &data)).abs() < 1e-12);
+
+        // LM lands on a stationary point of that objective
+        let problem = CalibrationProblem::new(&calibrator, &data);
+        let config = OptimizationConfig {
+            tolerance: 1e-15,
+            ..OptimizationConfig::default()
+        };
+        let (obj, x) = LevenbergMarquardtOptimizer.minimize(&problem, &config, Some(&x0));
+        assert_eq!(obj, calibrator.evaluate_objective(&x, &data));
+        assert!(obj < calibrator.evaluate_objective(&x0, &data));
+        let grad = calibrator.evaluate_gradient(&x, &data).unwrap();
+        assert!(grad.iter().all(|g| g.abs() < 1e-3), "gradient {:?}", grad);
+    }
+
+    #[test]
+    fn test_dividend_adjusted_moneyness() {
+        use crate::curves::{Dividend, DividendSchedule};
+
+        let data = sample_slice();
+        let schedule = DividendSchedule::new(vec![Dividend::cash(0.1, 3.0)]).unwrap();
+        let fixed = FixedParameters::flat(0.03, 0.0).with_dividends(schedule);
+        let calibrator = SVIModelCalibrator::new(&data, None, None)
+            .unwrap()
+            .with_fixed_params(fixed.clone());
+
+        // Raw quotes fit with the dividends match adjusted quotes fit at spot moneyness
+        let adjusted = fixed.adjust_for_dividends(data.clone()).unwrap();
+        let spot_calibrator = SVIModelCalibrator::new(&adjusted, None, None).unwrap();
+        let x = [0.005, 0.12, -0.5, -0.02, 0.3];
+        let objective = calibrator.evaluate_objective(&x, &data);
+        assert!((objective - spot_calibrator.evaluate_objective(&x, &adjusted)).abs() < 1e-15);
+        assert_ne!(objective, spot_calibrator.evaluate_objective(&x, &data));
+        assert_gradient_matches(&calibrator, &x, &data);
+    }
+
+    #[test]
+    fn test_gradient_rejects_invalid_parameters() {
+        let data = sample_slice();
+        let calibrator = SVIModelCalibrator::new(&data, None, None).unwrap();
+        // b <= 0 is not a valid SVI slice
+        assert!(calibrator
+            .evaluate_gradient(&[0.01, -0.1, -0.4, 0.0, 0.2], &data)
+            .is_none());
+    }
+}
@@ -0,0 +1,526 @@
+// src/models/svi/svi_engine.rs
+
+//! Streaming incremental SVI recalibration
+//!
+//! [`RecalibrationEngine`] keeps the latest quotes and the last SVI solution per expiry.
+//! Each [`step`](RecalibrationEngine::step) measures how far the market has moved away from
+//! every stored solution and recalibrates only the slices whose fit error or inputs changed
+//! by more than the configured thresholds, warm-starting from (and regularising towards) the
+//! previous solution. Whenever at least one slice is recalibrated a new [`SurfaceSnapshot`]
+//! is published to the subscribers.
+
+use crate::calibration::config::OptimizationConfig;
+use crate::calibration::types::MarketDataRow;
+use crate::models::svi::svi_model::{SVIModel, SVIParams};
+use crate::models::svi::svi_surface::SliceFailure;
+use crate::{calibrate_svi_detailed, clone_model_params, evaluate_svi, CalibrationParams};
+use anyhow::Result;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+/// Objective values at or above this level mean no valid SVI parameters were found
+const INVALID_OBJECTIVE: f64 = 1e12;
+
+/// Thresholds and settings for [`RecalibrationEngine`]
+#[derive(Debug, Clone)]
+pub struct RecalibrationConfig {
+    /// Optimization settings for every recalibration
+    pub optimization: OptimizationConfig,
+    /// Recalibrate when the current fit error exceeds the error at calibration time by this
+    /// relative amount (0.25 = 25% worse)
+    pub objective_drift: f64,
+    /// Recalibrate whenever the current fit error exceeds this absolute level
+    pub max_objective: Option<f64>,
+    /// Recalibrate when the RMS change of market IVs since the last fit exceeds this level
+    pub iv_change_threshold: f64,
+    /// Recalibrate when this fraction of the quotes was added or removed since the last fit
+    pub quote_set_change: f64,
+    /// Expiries with fewer quotes are not calibrated
+    pub min_points: usize,
+    /// Temporal regularisation strength towards the previous solution
+    pub reg_lambda: f64,
+    /// Tolerance passed to [`SVIModel::new`] for calendar-arbitrage checks
+    pub calendar_arbitrage_tolerance: f64,
+    /// Maximum number of slices recalibrated concurrently (None = available parallelism)
+    pub max_threads: Option<usize>,
+}
+
+impl Default for RecalibrationConfig {
+    fn default() -> Self {
+        Self {
+            optimization: OptimizationConfig::fast(),
+            objective_drift: 0.25,
+            max_objective: None,
+            iv_change_threshold: 0.005,
+            quote_set_change: 0.2,
+            min_points: 5,
+            reg_lambda: 1e-2,
+            calendar_arbitrage_tolerance: 1e-6,
+            max_threads: None,
+        }
+    }
+}
+
+/// Why a slice was recalibrated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecalibrationReason {
+    /// First calibration of the expiry
+    Initial,
+    /// The fit error drifted beyond the thresholds
+    ObjectiveDrift,
+    /// Market IVs or the quote set changed beyond the thresholds
+    InputChange,
+    /// Requested through [`RecalibrationEngine::invalidate`]
+    Forced,
+}
+
+/// State of one expiry in a published snapshot
+#[derive(Debug, Clone)]
+pub struct SliceStatus {
+    pub expiration: i64,
+    /// Calibrated parameters currently in use, with `t` at the current quotes' mean time
+    /// to maturity
+    pub params: SVIParams,
+    /// Fit error of `params` on the quotes they were calibrated to
+    pub objective: f64,
+    /// Snapshot version in which the slice was last recalibrated
+    pub calibrated_version: u64,
+    /// Reason for the last recalibration
+    pub reason: RecalibrationReason,
+    /// Number of quotes used by the last calibration
+    pub points: usize,
+}
+
+/// Surface published after a recalibration
+#[derive(Debug, Clone)]
+pub struct SurfaceSnapshot {
+    /// Monotonic snapshot number, starting at 1
+    pub version: u64,
+    /// Surface built from every calibrated expiry
+    pub model: SVIModel,
+    /// Status per calibrated expiry, sorted by maturity
+    pub slices: Vec<SliceStatus>,
+    /// Expiries recalibrated for this snapshot
+    pub recalibrated: Vec<i64>,
+    /// Expiries whose recalibration failed in this step; their previous solution is kept
+    pub failures: Vec<SliceFailure>,
+}
+
+/// Fit drift of one expiry against its stored solution
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SliceDrift {
+    pub expiration: i64,
+    /// Fit error of the stored solution on the current quotes
+    pub objective: f64,
+    /// Fit error at calibration time
+    pub objective_at_fit: f64,
+    /// RMS change of market IVs of quotes present both now and at calibration time
+    pub iv_change: f64,
+    /// Fraction of quotes added or removed since calibration
+    pub quote_set_change: f64,
+}
+
+/// Callback receiving every published snapshot
+pub type SnapshotSubscriber = Box<dyn Fn(&Arc<SurfaceSnapshot>) + Send + Sync>;
+
+/// Quote identity within an expiry: option type and strike
+type QuoteKey = (String, u64);
+
+fn quote_key(row: &MarketDataRow) -> QuoteKey {
+    (row.option_type.clone(), row.strike_price.to_bits())
+}
+
+/// Last solution of an expiry and the inputs it was fitted to
+#[derive(Debug, Clone)]
+struct SliceSolution {
+    params: SVIParams,
+    objective: f64,
+    fitted_ivs: HashMap<QuoteKey, f64>,
+    version: u64,
+    reason: RecalibrationReason,
+}
+
+#[derive(Debug, Default)]
+struct SliceState {
+    quotes: BTreeMap<QuoteKey, MarketDataRow>,
+    solution: Option<SliceSolution>,
+    forced: bool,
+}
+
+impl SliceState {
+    fn rows(&self) -> Vec<MarketDataRow> {
+        self.quotes.values().cloned().collect()
+    }
+
+    /// Mean time to maturity of the current quotes
+    fn t(&self) -> Option<f64> {
+        let n = self.quotes.len();
+        (n > 0).then(|| self.quotes.values().map(|r| r.years_to_exp).sum::<f64>() / n as f64)
+    }
+}
+
+/// Stateful engine recalibrating an SVI surface from streaming quote updates.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use surface_lib::{CalibrationParams, MarketDataRow, RecalibrationConfig, RecalibrationEngine};
+///
+/// # fn next_quotes() -> Vec<MarketDataRow> { vec![] }
+/// let mut engine = RecalibrationEngine::new(RecalibrationConfig::default(), CalibrationParams::default());
+/// engine.subscribe(Box::new(|snapshot| println!("surface v{} published", snapshot.version)));
+/// loop {
+///     engine.ingest(next_quotes());
+///     if let Some(snapshot) = engine.step()? {
+///         println!("recalibrated {:?}", snapshot.recalibrated);
+///     }
+/// #   break;
+/// }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct RecalibrationEngine {
+    config: RecalibrationConfig,
+    calib_params: CalibrationParams,
+    slices: BTreeMap<i64, SliceState>,
+    version: u64,
+    snapshot: Option<Arc<SurfaceSnapshot>>,
+    subscribers: Vec<SnapshotSubscriber>,
+}
+
+impl RecalibrationEngine {
+    /// Create an engine. `calib_params` supplies bounds, model parameters and the run
+    /// control for every recalibration; its `reg_lambda` is replaced by
+    /// [`RecalibrationConfig::reg_lambda`].
+    pub fn new(config: RecalibrationConfig, calib_params: CalibrationParams) -> Self {
+        Self {
+            config,
+            calib_params,
+            slices: BTreeMap::new(),
+            version: 0,
+            snapshot: None,
+            subscribers: Vec::new(),
+        }
+    }
+
+    /// Register a callback invoked with every published snapshot
+    pub fn subscribe(&mut self, subscriber: SnapshotSubscriber) {
+        self.subscribers.push(subscriber);
+    }
+
+    /// Insert or replace quotes, keyed by expiration, option type and strike
+    pub fn ingest<I: IntoIterator<Item = MarketDataRow>>(&mut self, quotes: I) {
+        for row in quotes {
+            self.slices
+                .entry(row.expiration)
+                .or_default()
+                .quotes
+                .insert(quote_key(&row), row);
+        }
+    }
+
+    /// Remove a single quote; returns whether it was present
+    pub fn remove_quote(&mut self, expiration: i64, option_type: &str, strike: f64) -> bool {
+        self.slices.get_mut(&expiration).is_some_and(|s| {
+            s.quotes
+                .remove(&(option_type.to_string(), strike.to_bits()))
+                .is_some()
+        })
+    }
+
+    /// Drop an expiry with its quotes and solution (e.g. once it has expired)
+    pub fn remove_expiry(&mut self, expiration: i64) {
+        self.slices.remove(&expiration);
+    }
+
+    /// Force recalibration of one expiry, or of all expiries when `None`, at the next step
+    pub fn invalidate(&mut self, expiration: Option<i64>) {
+        for (exp, state) in self.slices.iter_mut() {
+            if expiration.is_none_or(|e| e == *exp) {
+                state.forced = true;
+            }
+        }
+    }
+
+    /// Latest published snapshot
+    pub fn snapshot(&self) -> Option<Arc<SurfaceSnapshot>> {
+        self.snapshot.clone()
+    }
+
+    /// Last solution of an expiry
+    pub fn solution(&self, expiration: i64) -> Option<&SVIParams> {
+        self.slices
+            .get(&expiration)?
+            .solution
+            .as_ref()
+            .map(|s| &s.params)
+    }
+
+    /// Fit drift of every calibrated expiry against the current quotes
+    pub fn drift(&self) -> Vec<SliceDrift> {
+        self.slices
+            .iter()
+            .filter_map(|(exp, state)| {
+                let solution = state.solution.as_ref()?;
+                Some(self.measure_drift(*exp, state, solution))
+            })
+            .collect()
+    }
+
+    /// Recalibrate the slices that crossed a threshold and publish a new snapshot.
+    ///
+    /// Returns `Ok(None)` when nothing needed recalibration. Failed slices keep their previous
+    /// solution and are listed in [`SurfaceSnapshot::failures`]. When the recalibrated slices
+    /// don't build a valid [`SVIModel`], the error is returned and no solution is updated.
+    pub fn step(&mut self) -> Result<Option<Arc<SurfaceSnapshot>>> {
+        let due: Vec<(i64, RecalibrationReason)> = self
+            .slices
+            .iter()
+            .filter(|(_, state)| state.quotes.len() >= self.config.min_points)
+            .filter_map(|(exp, state)| self.recalibration_reason(*exp, state).map(|r| (*exp, r)))
+            .collect();
+        if due.is_empty() {
+            return Ok(None);
+        }
+
+        let version = self.version + 1;
+        let results = self.recalibrate(&due);
+
+        // Candidate solutions are only written to the slices once the surface builds
+        let mut solutions = BTreeMap::new();
+        let mut failures = Vec::new();
+        for ((exp, reason), result) in due.iter().zip(results) {
+            match result {
+                Ok((params, objective)) => {
+                    let fitted_ivs = self.slices[exp]
+                        .quotes
+                        .iter()
+                        .map(|(k, r)| (k.clone(), r.market_iv))
+                        .collect();
+                    solutions.insert(
+                        *exp,
+                        SliceSolution {
+                            params,
+                            objective,
+                            fitted_ivs,
+                            version,
+                            reason: *reason,
+                        },
+                    );
+                }
+                Err(failure) => {
+                    log::warn!(
+                        expiry = failure.expiration, tte = failure.t, points = failure.points,
+                        error:% = failure.error;
+                        "SVI slice recalibration failed"
+                    );
+                    failures.push(failure);
+                }
+            }
+        }
+
+        if solutions.is_empty() {
+            for (exp, _) in &due {
+                self.slices.get_mut(exp).expect("due slice exists").forced = false;
+            }
+            return Ok(None);
+        }
+
+        let mut slices: Vec<SliceStatus> = self
+            .slices
+            .iter()
+            .filter_map(|(exp, state)| {
+                let s = solutions.get(exp).or(state.solution.as_ref())?;
+                // Slices kept from earlier steps are stamped at their quotes' current time
+                // to maturity, keeping their total variance
+                let mut params = s.params.clone();
+                params.t = state.t().unwrap_or(params.t);
+                Some(SliceStatus {
+                    expiration: *exp,
+                    params,
+                    objective: s.objective,
+                    calibrated_version: s.version,
+                    reason: s.reason,
+                    points: s.fitted_ivs.len(),
+                })
+            })
+            .collect();
+        slices.sort_by(|a, b| a.params.t.total_cmp(&b.params.t));
+
+        let model = SVIModel::new(
+            slices
+                .iter()
+                .map(|s| (s.params.t, s.params.clone()))
+                .collect(),
+            self.config.calendar_arbitrage_tolerance,
+        )?;
+
+        let recalibrated: Vec<i64> = solutions.keys().copied().collect();
+        for (exp, _) in &due {
+            let state = self.slices.get_mut(exp).expect("due slice exists");
+            state.forced = false;
+            if let Some(solution) = solutions.remove(exp) {
+                state.solution = Some(solution);
+            }
+        }
+
+        self.version = version;
+        let snapshot = Arc::new(SurfaceSnapshot {
+            version,
+            model,
+            slices,
+            recalibrated,
+            failures,
+        });
+        self.snapshot = Some(snapshot.clone());
+        for subscriber in &self.subscribers {
+            subscriber(&snapshot);
+        }
+        Ok(Some(snapshot))
+    }
+
+    fn recalibration_reason(&self, exp: i64, state: &SliceState) -> Option<RecalibrationReason> {
+        let Some(solution) = &state.solution else {
+            return Some(RecalibrationReason::Initial);
+        };
+        if state.forced {
+            return Some(RecalibrationReason::Forced);
+        }
+
+        let drift = self.measure_drift(exp, state, solution);
+        if drift.iv_change > self.config.iv_change_threshold
+            || drift.quote_set_change > self.config.quote_set_change
+        {
+            return Some(RecalibrationReason::InputChange);
+        }
+        let drifted = drift.objective
+            > drift.objective_at_fit * (1.0 + self.config.objective_drift)
+            || self
+                .config
+                .max_objective
+                .is_some_and(|m| drift.objective > m);
+        drifted.then_some(RecalibrationReason::ObjectiveDrift)
+    }
+
+    fn measure_drift(&self, exp: i64, state: &SliceState, solution: &SliceSolution) -> SliceDrift {
+        let mut sq_sum = 0.0;
+        let mut matched = 0usize;
+        for (key, row) in &state.quotes {
+            if let Some(iv) = solution.fitted_ivs.get(key) {
+                sq_sum += (row.market_iv - iv).powi(2);
+                matched += 1;
+            }
+        }
+        let added = state.quotes.len() - matched;
+        let removed = solution.fitted_ivs.len() - matched;
+        let iv_change = if matched > 0 {
+            (sq_sum / matched as f64).sqrt()
+        } else {
+            0.0
+        };
+        let quote_set_change = (added + removed) as f64 / solution.fitted_ivs.len().max(1) as f64;
+
+        let objective = evaluate_svi(
+            state.rows(),
+            solution.params.clone(),
+            self.slice_params(),
+            &self.config.optimization.fixed_params,
+        )
+        .unwrap_or(f64::INFINITY);
+
+        SliceDrift {
+            expiration: exp,
+            objective,
+            objective_at_fit: solution.objective,
+            iv_change,
+            quote_set_change,
+        }
+    }
+
+    /// Calibration parameters for one slice
+    fn slice_params(&self) -> CalibrationParams {
+        CalibrationParams {
+            param_bounds: self.calib_params.param_bounds.clone(),
+            model_params: clone_model_params(&self.calib_params.model_params),
+            reg_lambda: Some(self.config.reg_lambda),
+            control: self.calib_params.control.clone(),
+        }
+    }
+
+    /// Calibrate the due slices concurrently; results are in the order of `due`
+    fn recalibrate(
+        &self,
+        due: &[(i64, RecalibrationReason)],
+    ) -> Vec<std::result::Result<(SVIParams, f64), SliceFailure>> {
+        let threads = self
+            .config
+            .max_threads
+            .unwrap_or_else(|| {
+                std::thread::available_parallelism()
+                    .map(|n| n.get())
+                    .unwrap_or(1)
+            })
+            .clamp(1, due.len());
+        let chunk_len = due.len().div_ceil(threads);
+
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = due
+                .chunks(chunk_len)
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        chunk
+                            .iter()
+                            .map(|(exp, _)| self.calibrate_slice(*exp))
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|h| h.join().unwrap_or_else(|e| std::panic::resume_unwind(e)))
+                .collect()
+        })
+    }
+
+    fn calibrate_slice(&self, exp: i64) -> std::result::Result<(SVIParams, f64), SliceFailure> {
+        let state = &self.slices[&exp];
+        let rows = state.rows();
+        let t = state.t().unwrap_or(f64::NAN);
+        let fail = |error: String| SliceFailure {
+            expiration: exp,
+            t,
+            points: rows.len(),
+            error,
+        };
+
+        let guess = state.solution.as_ref().map(|s| {
+            let p = &s.params;
+            vec![p.a, p.b, p.rho, p.m, p.sigma]
+        });
+        let outcome = calibrate_svi_detailed(
+            rows.clone(),
+            self.config.optimization.clone(),
+            self.slice_params(),
+            guess,
+        )
+        .map_err(|e| fail(e.to_string()))?;
+        if !outcome.objective.is_finite() || outcome.objective >= INVALID_OBJECTIVE {
+            return Err(fail(format!(
+                "no valid SVI parameters found (objective {})",
+                outcome.objective
+            )));
+        }
+
+        let p = &outcome.params;
+        let params =
+            SVIParams::new(t, p[0], p[1], p[2], p[3], p[4]).map_err(|e| fail(e.to_string()))?;
+        // Store the pure fit error, without the regularisation term, for drift monitoring
+        let objective = evaluate_svi(
+            rows.clone(),
+            params.clone(),
+            self.slice_params(),
+            &self.config.optimization.fixed_params,
+        )
+        .map_err(|e| fail(e.to_string()))?;
+        Ok((params, objective))
+    }
+}
@@ -14,10 +14,15 @@
 //! - ρ: asymmetry parameter (skew, -1 < ρ < 1)
 //! - m: horizontal shift (ATM location)
 //! - σ: curvature parameter (controls smile curvature)
+//!
+//! An [`SVIModel`] strings slices together in time; [`SviInterpolation`] selects how total
+//! variance between and beyond the slices is obtained.
 
 use crate::models::traits::SurfaceModel;
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
 
 /// Parameters for the SVI (Stochastic Volatility Inspired) model for a single maturity.
 ///
@@ -117,7 +122,7 @@ impl SVIParams {
 
 /// Represents the SVI volatility model for a single maturity slice.
 /// Contains the parameters and implements the SVI calculation logic.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SVISlice {
     pub params: SVIParams,
 }
@@ -132,10 +137,7 @@ impl SVISlice {
     /// Calculates total variance w(k) using the SVI formula:
     /// w(k) = a + b * (ρ(k-m) + sqrt((k-m)² + σ²))
     pub fn total_variance_at_k(&self, k: f64) -> f64 {
-        let k_minus_m = k - self.params.m;
-        let sqrt_term = (k_minus_m * k_minus_m + self.params.sigma * self.params.sigma).sqrt();
-
-        self.params.a + self.params.b * (self.params.rho * k_minus_m + sqrt_term)
+        raw_total_variance(&self.params, k)
     }
 
     /// Calculates implied volatility σ(k) from total variance.
@@ -146,6 +148,60 @@ impl SVISlice {
         }
         (total_var / self.params.t).sqrt()
     }
+
+    /// Implied volatility annualised over calendar time `calendar_t`.
+    ///
+    /// For slices fit in variance time (see [`crate::clock`]), where `params.t` is the
+    /// variance time to expiry and `calendar_t` the ACT/365 time.
+    pub fn calendar_implied_vol(&self, k: f64, calendar_t: f64) -> f64 {
+        let total_var = self.total_variance_at_k(k);
+        if total_var <= 0.0 || calendar_t <= 0.0 {
+            return 1e-6;
+        }
+        (total_var / calendar_t).sqrt()
+    }
+}
+
+/// w(k) = a + b * (ρ(k-m) + sqrt((k-m)² + σ²))
+fn raw_total_variance(params: &SVIParams, k: f64) -> f64 {
+    let k_minus_m = k - params.m;
+    let sqrt_term = (k_minus_m * k_minus_m + params.sigma * params.sigma).sqrt();
+
+    params.a + params.b * (params.rho * k_minus_m + sqrt_term)
+}
+
+/// Gatheral's butterfly condition on the smile `w` at `k`, with finite-difference derivatives:
+/// g(k) = (1 - k*w'/(2*w))² - (w')²/4 * (1/w + 1/4) + w''/2 >= 0
+pub(crate) fn check_butterfly(k: f64, t: f64, w: impl Fn(f64) -> Result<f64>) -> Result<()> {
+    const EPSILON: f64 = 1e-5;
+    let tolerance = 1e-9; // Tolerance for g_k check
+
+    let w_0 = w(k)?;
+    let w_p = w(k - EPSILON)?;
+    let w_n = w(k + EPSILON)?;
+
+    if w_0 <= tolerance {
+        return Ok(()); // No arbitrage if variance is near zero
+    }
+
+    // Calculate first and second derivatives using finite differences
+    let w_k = (w_n - w_p) / (2.0 * EPSILON); // First derivative
+    let w_kk = (w_n - 2.0 * w_0 + w_p) / (EPSILON * EPSILON); // Second derivative
+
+    // Gatheral's g(k) condition
+    let term1 = 1.0 - k * w_k / (2.0 * w_0);
+    let g_k = term1 * term1 - (w_k * w_k / 4.0) * (1.0 / w_0 + 0.25) + w_kk / 2.0;
+
+    if g_k < -tolerance {
+        Err(anyhow!(
+            "Butterfly arbitrage detected at k={:.6}, t={:.4}. g(k) = {:.6e} < 0",
+            k,
+            t,
+            g_k
+        ))
+    } else {
+        Ok(())
+    }
 }
 
 // Define the 5-minute tolerance in years as a constant (matching Wing implementation)
@@ -207,9 +263,6 @@ impl SurfaceModel for SVISlice {
     /// Uses Gatheral's g(k) condition: g(k) = (1 - k*w'/(2*w))² - (w')²/4 * (1/w + 1/4) + w''/2 >= 0
     /// **Requires `t` to be within ~5 minutes of the slice's `params.t`.**
     fn check_butterfly_arbitrage_at_k(&self, k: f64, t: f64) -> Result<()> {
-        const EPSILON: f64 = 1e-5;
-        let tolerance = 1e-9; // Tolerance for g_k check
-
         // Check if the provided time `t` is close enough to the slice's time `self.params.t`
         if (t - self.params.t).abs() > FIVE_MINUTES_IN_YEARS {
             return Err(anyhow!(
@@ -226,32 +279,7 @@ impl SurfaceModel for SVISlice {
 
         // Use slice's exact time for consistency
         let slice_t = self.params.t;
-        let w = self.total_variance(k, slice_t)?;
-        let w_p = self.total_variance(k - EPSILON, slice_t)?;
-        let w_n = self.total_variance(k + EPSILON, slice_t)?;
-
-        if w <= tolerance {
-            return Ok(()); // No arbitrage if variance is near zero
-        }
-
-        // Calculate first and second derivatives using finite differences
-        let w_k = (w_n - w_p) / (2.0 * EPSILON); // First derivative
-        let w_kk = (w_n - 2.0 * w + w_p) / (EPSILON * EPSILON); // Second derivative
-
-        // Gatheral's g(k) condition
-        let term1 = 1.0 - k * w_k / (2.0 * w);
-        let g_k = term1 * term1 - (w_k * w_k / 4.0) * (1.0 / w + 0.25) + w_kk / 2.0;
-
-        if g_k < -tolerance {
-            Err(anyhow!(
-                "Butterfly arbitrage detected at k={:.6}, t={:.4}. g(k) = {:.6e} < 0",
-                k,
-                t,
-                g_k
-            ))
-        } else {
-            Ok(())
-        }
+        check_butterfly(k, t, |k| self.total_variance(k, slice_t))
     }
 }
 
@@ -308,9 +336,10 @@ pub fn interpolate_svi_params(slices: &[(f64, SVIParams)], t: f64) -> SVIParams
     )
     .unwrap_or_else(|err| {
         // Fallback if interpolation results in invalid params
-        eprintln!(
-            "Warning: SVI interpolation failed for t={}: {}. Falling back to nearest slice.",
-            t_clamped, err
+        log::warn!(
+            tte = t_clamped, t0, t1, a = a_interp, b = b_interp, rho = rho_interp,
+            m = m_interp, sigma = sigma_interp, error:% = err;
+            "SVI parameter interpolation failed, falling back to nearest slice"
         );
         if (t_clamped - t0) < (t1 - t_clamped) {
             params0.clone()
@@ -320,13 +349,103 @@ pub fn interpolate_svi_params(slices: &[(f64, SVIParams)], t: f64) -> SVIParams
     })
 }
 
+/// How an [`SVIModel`] obtains total variance between and beyond its slices
+///
+/// With `θ_i = w_i(0)` the ATM total variance of slice `i` at time `t_i`:
+///
+/// | Mode | Between slices | Before the first | After the last |
+/// |---|---|---|---|
+/// | `Parameters` | raw a, b, ρ, m, σ blended linearly | first slice | last slice |
+/// | `TotalVariance` | `w(k)` linear in `t` at fixed `k` | `w_1(k)·t/t_1` | last segment's slope at each `k`, floored at zero |
+/// | `AtmVariance` | `θ` linear in `t`, smile shape blended at fixed `k/√θ` | `θ_1·t/t_1` | last segment's `θ` slope, floored at zero |
+///
+/// `TotalVariance` works at constant forward log-moneyness and is free of calendar
+/// arbitrage whenever adjacent slices are. `AtmVariance` works in ATM-total-variance
+/// space: the smile is `θ(t)·φ(k/√θ(t))`, where `φ` blends the slices' normalised shapes
+/// `w_i(z·√θ_i)/θ_i`, so the ATM level follows `θ` exactly and skew scales as `1/√θ`
+/// outside the slices. Its ATM variance is monotone whenever the slices' is.
+/// `Parameters`, the historical behaviour, neither preserves calendar monotonicity nor
+/// the smile shape and is kept for compatibility.
+///
+/// ```rust
+/// use surface_lib::{SVIModel, SVIParams, SviInterpolation};
+/// use surface_lib::models::traits::SurfaceModel;
+///
+/// let slices = vec![
+///     (0.1, SVIParams::new(0.1, 0.004, 0.04, -0.3, 0.0, 0.1)?),
+///     (0.5, SVIParams::new(0.5, 0.02, 0.08, -0.3, 0.0, 0.1)?),
+/// ];
+/// let model = SVIModel::new(slices, 1e-6)?.with_interpolation(SviInterpolation::TotalVariance);
+/// let (w1, w2) = (model.total_variance(0.2, 0.1)?, model.total_variance(0.2, 0.5)?);
+/// assert!((model.total_variance(0.2, 0.3)? - (w1 + w2) / 2.0).abs() < 1e-12);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SviInterpolation {
+    /// Linear blend of the raw SVI parameters, clamped to the slice times
+    #[default]
+    Parameters,
+    /// Total variance linear in time at constant forward log-moneyness
+    TotalVariance,
+    /// ATM total variance linear in time, smiles blended at constant `k/√θ`
+    AtmVariance,
+}
+
+impl SviInterpolation {
+    /// All modes, in documentation order
+    pub const ALL: [SviInterpolation; 3] = [
+        SviInterpolation::Parameters,
+        SviInterpolation::TotalVariance,
+        SviInterpolation::AtmVariance,
+    ];
+
+    /// Name used in configs and on the command line
+    pub fn name(self) -> &'static str {
+        match self {
+            SviInterpolation::Parameters => "parameters",
+            SviInterpolation::TotalVariance => "total_variance",
+            SviInterpolation::AtmVariance => "atm_variance",
+        }
+    }
+}
+
+impl fmt::Display for SviInterpolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for SviInterpolation {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        SviInterpolation::ALL
+            .into_iter()
+            .find(|m| m.name() == s)
+            .ok_or_else(|| {
+                anyhow!(
+                    "unknown SVI interpolation `{}` (expected one of: {})",
+                    s,
+                    SviInterpolation::ALL.map(SviInterpolation::name).join(", ")
+                )
+            })
+    }
+}
+
 /// Represents the full SVI volatility surface across multiple maturities.
-#[derive(Debug, Clone)]
+///
+/// Deserialization goes through [`SVIModel::new`], so a loaded surface is sorted and
+/// validated exactly like a constructed one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "SVIModelData")]
 pub struct SVIModel {
     // Slices sorted by time t
     slices: Vec<(f64, SVIParams)>,
     // Configurable tolerance for calendar arbitrage checks
     calendar_arbitrage_tolerance: f64,
+    // How total variance is interpolated between slices
+    interpolation: SviInterpolation,
 }
 
 impl SVIModel {
@@ -353,6 +472,7 @@ impl SVIModel {
         let model = Self {
             slices,
             calendar_arbitrage_tolerance,
+            interpolation: SviInterpolation::default(),
         };
 
         // Perform initial validation of the surface
@@ -364,6 +484,102 @@ impl SVIModel {
     fn interpolate_params(&self, t: f64) -> SVIParams {
         interpolate_svi_params(&self.slices, t)
     }
+
+    /// Tolerance used for calendar-arbitrage checks
+    pub fn calendar_arbitrage_tolerance(&self) -> f64 {
+        self.calendar_arbitrage_tolerance
+    }
+
+    /// Set how total variance is interpolated between slices
+    pub fn with_interpolation(mut self, interpolation: SviInterpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
+    /// How total variance is interpolated between slices
+    pub fn interpolation(&self) -> SviInterpolation {
+        self.interpolation
+    }
+
+    /// Total variance at `k` and `t` under the model's [`SviInterpolation`], unchecked
+    fn interpolated_variance(&self, k: f64, t: f64) -> Result<f64> {
+        let slices = &self.slices;
+        let idx = slices.partition_point(|(slice_t, _)| *slice_t < t);
+        // Slices bracketing `t`: the same one twice before the first and, for a single
+        // slice, after the last
+        let (lo, hi) = match idx {
+            0 => (0, 0),
+            i if i == slices.len() => (i.saturating_sub(2), i - 1),
+            i => (i - 1, i),
+        };
+        let (t0, p0) = &slices[lo];
+        let (t1, p1) = &slices[hi];
+        let w0 = |k: f64| raw_total_variance(p0, k);
+        let w1 = |k: f64| raw_total_variance(p1, k);
+
+        // Total variance in time at fixed moneyness from the two slices' values
+        let blend = |v0: f64, v1: f64| {
+            if lo == hi {
+                v0 * t / t0
+            } else if t > *t1 {
+                v1 + (t - t1) * ((v1 - v0) / (t1 - t0)).max(0.0)
+            } else {
+                let weight = (t - t0) / (t1 - t0);
+                (1.0 - weight) * v0 + weight * v1
+            }
+        };
+
+        match self.interpolation {
+            SviInterpolation::Parameters => {
+                let mut params = self.interpolate_params(t);
+                params.t = t;
+                Ok(raw_total_variance(&params, k))
+            }
+            SviInterpolation::TotalVariance => Ok(blend(w0(k), w1(k))),
+            SviInterpolation::AtmVariance => {
+                let (theta0, theta1) = (w0(0.0), w1(0.0));
+                if theta0 <= 0.0 || theta1 <= 0.0 {
+                    return Err(anyhow!(
+                        "ATM total variance must be positive for θ-space interpolation (t={}: {}, t={}: {})",
+                        t0, theta0, t1, theta1
+                    ));
+                }
+                let theta = blend(theta0, theta1);
+                let z = k / theta.sqrt();
+                let shape0 = w0(z * theta0.sqrt()) / theta0;
+                let shape1 = w1(z * theta1.sqrt()) / theta1;
+                // Outside the slices the nearest slice's shape is kept
+                let weight = if lo == hi || t <= *t0 {
+                    0.0
+                } else if t >= *t1 {
+                    1.0
+                } else {
+                    (t - t0) / (t1 - t0)
+                };
+                Ok(theta * ((1.0 - weight) * shape0 + weight * shape1))
+            }
+        }
+    }
+}
+
+/// Unvalidated serialized form of [`SVIModel`]
+#[derive(Deserialize)]
+struct SVIModelData {
+    slices: Vec<(f64, SVIParams)>,
+    calendar_arbitrage_tolerance: f64,
+    #[serde(default)]
+    interpolation: SviInterpolation,
+}
+
+impl TryFrom<SVIModelData> for SVIModel {
+    type Error = anyhow::Error;
+
+    fn try_from(data: SVIModelData) -> Result<Self> {
+        Ok(
+            SVIModel::new(data.slices, data.calendar_arbitrage_tolerance)?
+                .with_interpolation(data.interpolation),
+        )
+    }
 }
 
 // Implement SurfaceModel for the SVIModel (Surface)
@@ -405,9 +621,11 @@ impl SurfaceModel for SVIModel {
                     let w2 = slice2.total_variance_at_k(k);
 
                     if w2 < w1 - self.calendar_arbitrage_tolerance {
-                        eprintln!("Warning: Calendar arbitrage detected between t1={:.4} and t2={:.4} at k={:.3}. w1={:.6}, w2={:.6}", 
-                                t1, t2, k, w1, w2);
-                        // Note: We print a warning but don't return an error to allow flexibility
+                        log::warn!(
+                            t1 = *t1, t2 = *t2, k, w1, w2, violation = w1 - w2;
+                            "calendar arbitrage detected between adjacent SVI slices"
+                        );
+                        // Note: We log a warning but don't return an error to allow flexibility
                     }
                 }
             }
@@ -416,8 +634,28 @@ impl SurfaceModel for SVIModel {
         Ok(())
     }
 
-    /// Calculates total variance by interpolating parameters for time `t`.
+    /// Calculates total variance at time `t` following the model's [`SviInterpolation`].
     fn total_variance(&self, k: f64, t: f64) -> Result<f64> {
+        if self.interpolation != SviInterpolation::Parameters {
+            if !k.is_finite() || !t.is_finite() || t <= 0.0 {
+                return Err(anyhow!(
+                    "Total variance needs finite k and positive t (k={}, t={})",
+                    k,
+                    t
+                ));
+            }
+            let total_var = self.interpolated_variance(k, t)?;
+            if !total_var.is_finite() || total_var < 0.0 {
+                return Err(anyhow!(
+                    "Calculated total variance is invalid: {} for k={}, t={}",
+                    total_var,
+                    k,
+                    t
+                ));
+            }
+            return Ok(total_var);
+        }
+
         let mut interpolated_params = self.interpolate_params(t);
         // Set the time `t` on the interpolated parameters to the requested time
         interpolated_params.t = t;
@@ -453,8 +691,11 @@ impl SurfaceModel for SVIModel {
         }
     }
 
-    /// Checks butterfly arbitrage at a specific k and t by creating a temporary slice.
+    /// Checks butterfly arbitrage at a specific k and t on the interpolated smile.
     fn check_butterfly_arbitrage_at_k(&self, k: f64, t: f64) -> Result<()> {
+        if self.interpolation != SviInterpolation::Parameters {
+            return check_butterfly(k, t, |k| self.total_variance(k, t));
+        }
         let mut interpolated_params = self.interpolate_params(t);
         interpolated_params.t = t;
 
@@ -532,4 +773,77 @@ mod tests {
         // With negative rho, we expect some skew (put vol > call vol for same |k|)
         assert!(iv_otm_put > iv_otm_call);
     }
+
+    fn three_slice_model() -> SVIModel {
+        let slices = [(0.1, 0.004, 0.04), (0.25, 0.012, 0.06), (0.5, 0.03, 0.08)]
+            .map(|(t, a, b)| (t, SVIParams::new(t, a, b, -0.4, 0.02, 0.15).unwrap()));
+        SVIModel::new(slices.to_vec(), 1e-6).unwrap()
+    }
+
+    #[test]
+    fn test_total_variance_interpolation() {
+        let model = three_slice_model().with_interpolation(SviInterpolation::TotalVariance);
+        let w = |k: f64, t: f64| model.total_variance(k, t).unwrap();
+        let slice = |i: usize| SVISlice::new(model.slices[i].1.clone());
+
+        for k in [-0.6, -0.2, 0.0, 0.3, 0.8] {
+            // Slices are reproduced and blended linearly between them
+            assert!((w(k, 0.25) - slice(1).total_variance_at_k(k)).abs() < 1e-12);
+            let mid = 0.5 * (slice(0).total_variance_at_k(k) + slice(1).total_variance_at_k(k));
+            assert!((w(k, 0.175) - mid).abs() < 1e-12);
+            // Constant implied vol before the first slice
+            let iv = |t: f64| (w(k, t) / t).sqrt();
+            assert!((iv(0.02) - slice(0).implied_vol(k)).abs() < 1e-12);
+            // Calendar monotone from the first slice's extrapolation to beyond the last
+            let times: Vec<f64> = (1..=80).map(|i| i as f64 * 0.01).collect();
+            for pair in times.windows(2) {
+                assert!(w(k, pair[1]) >= w(k, pair[0]), "k={k}, t={:?}", pair);
+            }
+        }
+        // The last segment's slope continues after the last slice
+        let slope = (slice(2).total_variance_at_k(0.0) - slice(1).total_variance_at_k(0.0)) / 0.25;
+        assert!((w(0.0, 0.7) - slice(2).total_variance_at_k(0.0) - 0.2 * slope).abs() < 1e-12);
+        assert!(model.check_butterfly_arbitrage_at_k(0.1, 0.3).is_ok());
+        assert!(model.total_variance(0.0, 0.0).is_err());
+    }
+
+    #[test]
+    fn test_atm_variance_interpolation() {
+        let model = three_slice_model().with_interpolation(SviInterpolation::AtmVariance);
+        let w = |k: f64, t: f64| model.total_variance(k, t).unwrap();
+        let theta = |i: usize| SVISlice::new(model.slices[i].1.clone()).total_variance_at_k(0.0);
+
+        // ATM total variance is linear in time between slices and proportional before them
+        assert!((w(0.0, 0.175) - 0.5 * (theta(0) + theta(1))).abs() < 1e-12);
+        assert!((w(0.0, 0.05) - 0.5 * theta(0)).abs() < 1e-12);
+        for k in [-0.5, 0.0, 0.4] {
+            let slice = SVISlice::new(model.slices[2].1.clone());
+            assert!((w(k, 0.5) - slice.total_variance_at_k(k)).abs() < 1e-12);
+        }
+        // Before the first slice the smile keeps its shape in k/√θ
+        let first = SVISlice::new(model.slices[0].1.clone());
+        let z = 0.5;
+        let ratio = w(z * (0.5 * theta(0)).sqrt(), 0.05) / (0.5 * theta(0));
+        assert!((ratio - first.total_variance_at_k(z * theta(0).sqrt()) / theta(0)).abs() < 1e-12);
+        // ATM variance keeps growing after the last slice
+        assert!(w(0.0, 0.8) > w(0.0, 0.5));
+    }
+
+    #[test]
+    fn test_interpolation_names_and_serde() {
+        for mode in SviInterpolation::ALL {
+            assert_eq!(mode.to_string().parse::<SviInterpolation>().unwrap(), mode);
+        }
+        assert!("linear".parse::<SviInterpolation>().is_err());
+
+        let model = three_slice_model().with_interpolation(SviInterpolation::AtmVariance);
+        let json = serde_json::to_string(&model).unwrap();
+        assert!(json.contains("\"interpolation\":\"atm_variance\""));
+        let loaded: SVIModel = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.interpolation(), SviInterpolation::AtmVariance);
+        // Surfaces saved before interpolation modes existed keep the parameter blend
+        let legacy = json.replace(",\"interpolation\":\"atm_variance\"", "");
+        let loaded: SVIModel = serde_json::from_str(&legacy).unwrap();
+        assert_eq!(loaded.interpolation(), SviInterpolation::Parameters);
+    }
 }
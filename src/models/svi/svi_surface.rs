@@ -0,0 +1,388 @@
+// src/models/svi/svi_surface.rs
+
+//! Multi-expiry SVI surface calibration
+//!
+//! [`calibrate_svi_surface`] splits a full option chain by expiration, calibrates one SVI
+//! slice per expiry and assembles the successful slices into an [`SVIModel`]. Expiries are
+//! sorted by maturity and divided into contiguous chains of four slices that
+//! run on a pool of threads; inside a chain each slice is warm-started from its
+//! shorter-dated neighbour's solution. Chain boundaries depend only on the data, so the
+//! result does not depend on the number of threads.
+//! A slice that fails is reported in [`SurfaceCalibration::failures`] without affecting
+//! the others.
+
+use crate::calibration::config::OptimizationConfig;
+use crate::calibration::types::{FixedParameters, MarketDataRow};
+use crate::models::svi::svi_calibrator::SVIParamBounds;
+use crate::models::svi::svi_model::{SVIModel, SVIParams, SVISlice};
+use crate::{calibrate_svi_detailed, clone_model_params, CalibrationParams};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/// Objective values at or above this level mean no valid SVI parameters were found
+const INVALID_OBJECTIVE: f64 = 1e12;
+
+/// Expiries per warm-start chain; chains are the unit of parallel work
+const WARM_START_CHAIN: usize = 4;
+
+/// Options for [`calibrate_svi_surface`]
+#[derive(Debug, Clone)]
+pub struct SurfaceCalibrationOptions {
+    /// Tolerance passed to [`SVIModel::new`] for calendar-arbitrage checks
+    pub calendar_arbitrage_tolerance: f64,
+    /// Warm-start each slice from its neighbour's solution
+    pub warm_start: bool,
+    /// Maximum number of warm-start chains, or slices without warm starts, calibrated
+    /// concurrently (None = available parallelism). Only affects speed, not the result
+    pub max_threads: Option<usize>,
+    /// Expiries with fewer quotes are reported as failures without calibrating
+    pub min_points: usize,
+}
+
+impl Default for SurfaceCalibrationOptions {
+    fn default() -> Self {
+        Self {
+            calendar_arbitrage_tolerance: 1e-6,
+            warm_start: true,
+            max_threads: None,
+            min_points: 5,
+        }
+    }
+}
+
+/// Fit statistics for a successfully calibrated slice
+#[derive(Debug, Clone)]
+pub struct SliceDiagnostics {
+    /// Expiration timestamp of the slice
+    pub expiration: i64,
+    /// Time to expiry (years) used for the slice
+    pub t: f64,
+    /// Number of quotes in the slice
+    pub points: usize,
+    /// Calibrated SVI parameters
+    pub params: SVIParams,
+    /// Final calibration objective
+    pub objective: f64,
+    /// Root-mean-square difference between model and market implied volatility
+    pub iv_rmse: f64,
+    /// Largest absolute difference between model and market implied volatility
+    pub max_iv_error: f64,
+    /// Parameter bounds in effect at the end of the calibration
+    pub bounds: SVIParamBounds,
+    /// Objective evaluations used
+    pub evaluations: usize,
+    /// Whether the slice was warm-started from a neighbouring solution
+    pub warm_started: bool,
+    /// Whether the calibration was cut short by the deadline or cancellation
+    pub truncated: bool,
+    /// Wall-clock time spent on the slice
+    pub elapsed: Duration,
+}
+
+/// An expiry that could not be calibrated
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SliceFailure {
+    /// Expiration timestamp of the slice
+    pub expiration: i64,
+    /// Average time to expiry (years) of the slice's quotes
+    pub t: f64,
+    /// Number of quotes in the slice
+    pub points: usize,
+    /// Reason for the failure
+    pub error: String,
+}
+
+/// Result of [`calibrate_svi_surface`]
+#[derive(Debug, Clone)]
+pub struct SurfaceCalibration {
+    /// Surface built from every successfully calibrated slice
+    pub model: SVIModel,
+    /// Diagnostics per calibrated slice, sorted by maturity
+    pub slices: Vec<SliceDiagnostics>,
+    /// Expiries that failed, sorted by maturity
+    pub failures: Vec<SliceFailure>,
+}
+
+/// Quotes of a single expiry
+struct ExpirySlice {
+    expiration: i64,
+    t: f64,
+    data: Vec<MarketDataRow>,
+}
+
+/// Calibrate an SVI surface to a multi-expiry option chain.
+///
+/// Quotes are grouped by `expiration` and each group is calibrated with the same pipeline as
+/// [`calibrate_svi`](crate::calibrate_svi), using `config` and `calib_params` for every slice.
+/// The deadline and cancellation token in `calib_params.control` apply to the surface as a
+/// whole. When warm-starting, `calib_params.reg_lambda` defaults to `0.0` so that a slice is
+/// not pulled towards its neighbour's parameters.
+///
+/// # Errors
+///
+/// Fails only when no expiry could be calibrated or the calibrated slices do not form a valid
+/// [`SVIModel`]; individual slice failures are returned in [`SurfaceCalibration::failures`].
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use surface_lib::{calibrate_svi_surface, default_configs, CalibrationParams, MarketDataRow};
+/// use surface_lib::SurfaceCalibrationOptions;
+///
+/// # let chain: Vec<MarketDataRow> = vec![];
+/// let surface = calibrate_svi_surface(
+///     chain,
+///     default_configs::fast(),
+///     CalibrationParams::default(),
+///     SurfaceCalibrationOptions::default(),
+/// )?;
+/// for slice in &surface.slices {
+///     println!("t={:.4} iv_rmse={:.4}", slice.t, slice.iv_rmse);
+/// }
+/// for failure in &surface.failures {
+///     println!("expiry {} failed: {}", failure.expiration, failure.error);
+/// }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn calibrate_svi_surface(
+    data: Vec<MarketDataRow>,
+    config: OptimizationConfig,
+    calib_params: CalibrationParams,
+    options: SurfaceCalibrationOptions,
+) -> Result<SurfaceCalibration> {
+    let expiries = group_by_expiry(data);
+    if expiries.is_empty() {
+        return Err(anyhow!("No market data provided"));
+    }
+
+    // Contiguous chains of expiries, taken from a shared queue by the worker threads
+    let chains: Vec<&[ExpirySlice]> = if options.warm_start {
+        expiries.chunks(WARM_START_CHAIN).collect()
+    } else {
+        expiries.chunks(1).collect()
+    };
+    let threads = options
+        .max_threads
+        .unwrap_or_else(|| {
+            std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1)
+        })
+        .clamp(1, chains.len());
+    let next = AtomicUsize::new(0);
+
+    let mut chain_results: Vec<(
+        usize,
+        Vec<std::result::Result<SliceDiagnostics, SliceFailure>>,
+    )> = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|_| {
+                let (chains, next) = (&chains, &next);
+                let (config, calib_params, options) = (&config, &calib_params, &options);
+                scope.spawn(move || {
+                    let mut done = Vec::new();
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(chain) = chains.get(i) else {
+                            return done;
+                        };
+                        done.push((i, calibrate_chain(chain, config, calib_params, options)));
+                    }
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().unwrap_or_else(|e| std::panic::resume_unwind(e)))
+            .collect()
+    });
+    chain_results.sort_by_key(|(i, _)| *i);
+    let results = chain_results.into_iter().flat_map(|(_, results)| results);
+
+    let mut slices = Vec::new();
+    let mut failures = Vec::new();
+    for result in results {
+        match result {
+            Ok(diag) => slices.push(diag),
+            Err(failure) => {
+                log::warn!(
+                    expiry = failure.expiration, tte = failure.t, points = failure.points,
+                    error:% = failure.error;
+                    "SVI slice calibration failed"
+                );
+                failures.push(failure);
+            }
+        }
+    }
+
+    if slices.is_empty() {
+        return Err(anyhow!(
+            "SVI surface calibration failed for all {} expiries",
+            failures.len()
+        ));
+    }
+
+    let model = SVIModel::new(
+        slices.iter().map(|s| (s.t, s.params.clone())).collect(),
+        options.calendar_arbitrage_tolerance,
+    )?;
+
+    Ok(SurfaceCalibration {
+        model,
+        slices,
+        failures,
+    })
+}
+
+/// Group quotes by expiration, sorted by average time to expiry
+fn group_by_expiry(data: Vec<MarketDataRow>) -> Vec<ExpirySlice> {
+    let mut grouped: BTreeMap<i64, Vec<MarketDataRow>> = BTreeMap::new();
+    for row in data {
+        grouped.entry(row.expiration).or_default().push(row);
+    }
+
+    let mut expiries: Vec<ExpirySlice> = grouped
+        .into_iter()
+        .map(|(expiration, data)| ExpirySlice {
+            expiration,
+            t: data.iter().map(|r| r.years_to_exp).sum::<f64>() / data.len() as f64,
+            data,
+        })
+        .collect();
+    expiries.sort_by(|a, b| a.t.total_cmp(&b.t));
+    expiries
+}
+
+/// Calibrate a chain of expiries in maturity order, warm-starting from the last success
+fn calibrate_chain(
+    chain: &[ExpirySlice],
+    config: &OptimizationConfig,
+    calib_params: &CalibrationParams,
+    options: &SurfaceCalibrationOptions,
+) -> Vec<std::result::Result<SliceDiagnostics, SliceFailure>> {
+    let mut previous: Option<SVIParams> = None;
+    chain
+        .iter()
+        .map(|slice| {
+            let guess = previous
+                .as_ref()
+                .filter(|_| options.warm_start)
+                .map(|p| warm_start_guess(p, slice.t, calib_params.param_bounds.as_ref()));
+            let result = calibrate_slice(slice, config, calib_params, options, guess);
+            if let Ok(diag) = &result {
+                previous = Some(diag.params.clone());
+            }
+            result
+        })
+        .collect()
+}
+
+/// Scale a neighbour's solution to maturity `t` and clamp it into the parameter bounds.
+///
+/// Total variance grows roughly linearly in time, so the variance level `a` and slope `b`
+/// are rescaled by the maturity ratio while the shape parameters are kept.
+fn warm_start_guess(neighbour: &SVIParams, t: f64, bounds: Option<&SVIParamBounds>) -> Vec<f64> {
+    let default_bounds = SVIParamBounds::default();
+    let bounds = bounds.unwrap_or(&default_bounds);
+    let ratio = if neighbour.t > 0.0 {
+        t / neighbour.t
+    } else {
+        1.0
+    };
+    let clamp = |x: f64, (lo, hi): (f64, f64)| x.clamp(lo, hi);
+    vec![
+        clamp(neighbour.a * ratio, bounds.a),
+        clamp(neighbour.b * ratio, bounds.b),
+        clamp(neighbour.rho, bounds.rho),
+        clamp(neighbour.m, bounds.m),
+        clamp(neighbour.sigma, bounds.sigma),
+    ]
+}
+
+fn calibrate_slice(
+    slice: &ExpirySlice,
+    config: &OptimizationConfig,
+    calib_params: &CalibrationParams,
+    options: &SurfaceCalibrationOptions,
+    guess: Option<Vec<f64>>,
+) -> std::result::Result<SliceDiagnostics, SliceFailure> {
+    let fail = |error: String| SliceFailure {
+        expiration: slice.expiration,
+        t: slice.t,
+        points: slice.data.len(),
+        error,
+    };
+
+    if slice.data.len() < options.min_points {
+        return Err(fail(format!(
+            "insufficient quotes: {} < {}",
+            slice.data.len(),
+            options.min_points
+        )));
+    }
+
+    let started = Instant::now();
+    let warm_started = guess.is_some();
+    let params = CalibrationParams {
+        param_bounds: calib_params.param_bounds.clone(),
+        model_params: clone_model_params(&calib_params.model_params),
+        reg_lambda: Some(calib_params.reg_lambda.unwrap_or(0.0)),
+        control: calib_params.control.clone(),
+    };
+
+    let outcome = calibrate_svi_detailed(slice.data.clone(), config.clone(), params, guess)
+        .map_err(|e| fail(e.to_string()))?;
+    if !outcome.objective.is_finite() || outcome.objective >= INVALID_OBJECTIVE {
+        return Err(fail(format!(
+            "no valid SVI parameters found (objective {})",
+            outcome.objective
+        )));
+    }
+
+    let p = &outcome.params;
+    let svi =
+        SVIParams::new(slice.t, p[0], p[1], p[2], p[3], p[4]).map_err(|e| fail(e.to_string()))?;
+    let (iv_rmse, max_iv_error) = iv_errors(&svi, &slice.data, &config.fixed_params);
+
+    Ok(SliceDiagnostics {
+        expiration: slice.expiration,
+        t: slice.t,
+        points: slice.data.len(),
+        params: svi,
+        objective: outcome.objective,
+        iv_rmse,
+        max_iv_error,
+        bounds: SVIParamBounds::from(outcome.bounds.as_slice()),
+        evaluations: outcome.evaluations,
+        warm_started,
+        truncated: outcome.truncated,
+        elapsed: started.elapsed(),
+    })
+}
+
+/// RMS and maximum absolute implied volatility error of `params` on `data`, in the
+/// dividend-adjusted moneyness the slice was fit in
+fn iv_errors(
+    params: &SVIParams,
+    data: &[MarketDataRow],
+    fixed_params: &FixedParameters,
+) -> (f64, f64) {
+    let slice = SVISlice::new(params.clone());
+    let errors: Vec<f64> = data
+        .iter()
+        .filter(|r| r.underlying_price > 0.0 && r.strike_price > 0.0)
+        .map(|r| {
+            let k = fixed_params.log_moneyness(r.strike_price, r.underlying_price, r.years_to_exp);
+            (slice.implied_vol(k) - r.market_iv).abs()
+        })
+        .collect();
+    if errors.is_empty() {
+        return (f64::NAN, f64::NAN);
+    }
+    let rmse = (errors.iter().map(|e| e * e).sum::<f64>() / errors.len() as f64).sqrt();
+    let max = errors.iter().copied().fold(0.0, f64::max);
+    (rmse, max)
+}
@@ -0,0 +1,622 @@
+//! SVG charts of smiles, term structures, parameter histories and surfaces
+//!
+//! Available with the `plot` feature. Every function renders from library types and returns
+//! the SVG document as a string:
+//!
+//! - [`smile_svg`] / [`quote_smile_svg`]: market against model implied volatility of one
+//!   expiry, with a residual panel
+//! - [`term_structure_svg`]: ATM IV, risk reversals and butterflies of [`FixedTimeMetrics`]
+//! - [`param_history_svg`]: SVI parameters over time, e.g. from
+//!   [`param_history_from_snapshots`]
+//! - [`variance_heatmap_svg`]: total variance of an [`SVIModel`] across (k, t)
+//!
+//! ```rust,no_run
+//! use surface_lib::plot::{smile_svg, PlotOptions};
+//! # use surface_lib::{MarketDataRow, SVIParams};
+//! # let data: Vec<MarketDataRow> = vec![];
+//! # let params = SVIParams::new(0.1, 0.01, 0.1, -0.3, 0.0, 0.2)?;
+//!
+//! let svg = smile_svg(&data, &params, &PlotOptions::default().with_title("BTC 10JAN25"))?;
+//! std::fs::write("smile.svg", svg)?;
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use crate::calibration::types::MarketDataRow;
+use crate::io::OptionQuote;
+use crate::models::linear_iv::FixedTimeMetrics;
+use crate::models::svi::svi_engine::SurfaceSnapshot;
+use crate::models::svi::svi_model::{SVIModel, SVIParams, SVISlice};
+use crate::models::traits::SurfaceModel;
+use crate::models::utils::log_moneyness;
+use anyhow::{anyhow, Result};
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Points of a model curve or heatmap axis
+const CURVE_POINTS: usize = 250;
+
+/// Size and title of a chart
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlotOptions {
+    /// Width in pixels
+    pub width: u32,
+    /// Height in pixels
+    pub height: u32,
+    /// Chart caption (None = a default caption per chart)
+    pub title: Option<String>,
+}
+
+impl Default for PlotOptions {
+    fn default() -> Self {
+        Self {
+            width: 1280,
+            height: 768,
+            title: None,
+        }
+    }
+}
+
+impl PlotOptions {
+    /// Set the size in pixels
+    pub fn with_size(mut self, width: u32, height: u32) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    /// Set the caption
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    fn title_or(&self, default: impl FnOnce() -> String) -> String {
+        self.title.clone().unwrap_or_else(default)
+    }
+}
+
+/// One market point of a smile
+struct SmilePoint {
+    strike: f64,
+    underlying: f64,
+    iv: f64,
+    is_call: bool,
+    bid_ask: Option<(f64, f64)>,
+}
+
+/// Market against model implied volatility of one expiry.
+///
+/// Calls are drawn in red and puts in blue; the lower panel shows model minus market IV in
+/// volatility points. The model is evaluated at each quote's own log-moneyness
+/// `ln(strike / underlying_price)`, as in calibration.
+pub fn smile_svg(
+    data: &[MarketDataRow],
+    params: &SVIParams,
+    options: &PlotOptions,
+) -> Result<String> {
+    let points: Vec<SmilePoint> = data
+        .iter()
+        .map(|row| SmilePoint {
+            strike: row.strike_price,
+            underlying: row.underlying_price,
+            iv: row.market_iv,
+            is_call: row.option_type == "call",
+            bid_ask: None,
+        })
+        .collect();
+    render_smile(&points, params, options)
+}
+
+/// [`smile_svg`] for loaded quotes, adding bid/ask IV bars where available.
+pub fn quote_smile_svg(
+    quotes: &[OptionQuote],
+    params: &SVIParams,
+    options: &PlotOptions,
+) -> Result<String> {
+    let points: Vec<SmilePoint> = quotes
+        .iter()
+        .map(|q| SmilePoint {
+            strike: q.row.strike_price,
+            underlying: q.row.underlying_price,
+            iv: q.row.market_iv,
+            is_call: q.row.option_type == "call",
+            bid_ask: q.bid_iv.zip(q.ask_iv).filter(|(bid, ask)| ask > bid),
+        })
+        .collect();
+    render_smile(&points, params, options)
+}
+
+fn render_smile(
+    points: &[SmilePoint],
+    params: &SVIParams,
+    options: &PlotOptions,
+) -> Result<String> {
+    if points.is_empty() {
+        return Err(anyhow!("no market data to plot"));
+    }
+    let slice = SVISlice::new(params.clone());
+    let model_iv =
+        |strike: f64, underlying: f64| slice.implied_vol(log_moneyness(strike, underlying));
+
+    let (k_min, k_max) = bounds(points.iter().map(|p| p.strike));
+    let k_max = if k_max > k_min { k_max } else { k_min + 1.0 };
+    let underlying = points.iter().map(|p| p.underlying).sum::<f64>() / points.len() as f64;
+    let curve: Vec<(f64, f64)> = (0..=CURVE_POINTS)
+        .map(|i| {
+            let strike = k_min + (k_max - k_min) * i as f64 / CURVE_POINTS as f64;
+            (strike, model_iv(strike, underlying) * 100.0)
+        })
+        .collect();
+    let residuals: Vec<(f64, f64, bool)> = points
+        .iter()
+        .map(|p| {
+            (
+                p.strike,
+                (model_iv(p.strike, p.underlying) - p.iv) * 100.0,
+                p.is_call,
+            )
+        })
+        .collect();
+
+    let iv_range = padded(bounds(
+        points
+            .iter()
+            .flat_map(|p| {
+                let (bid, ask) = p.bid_ask.unwrap_or((p.iv, p.iv));
+                [p.iv, bid, ask]
+            })
+            .map(|v| v * 100.0)
+            .chain(curve.iter().map(|c| c.1)),
+    ));
+    let (lo, hi) = bounds(residuals.iter().map(|r| r.1));
+    let res_extent = lo.abs().max(hi.abs()).max(0.1) * 1.1;
+
+    let title = options.title_or(|| {
+        format!(
+            "SVI vs market IV (t={:.4}y, {:.1}d)",
+            params.t,
+            params.t * 365.0
+        )
+    });
+    render(options, |root| {
+        let root = root.titled(&title, ("sans-serif", 28))?;
+        let (upper, lower) = root.split_vertically(root.dim_in_pixel().1 * 7 / 10);
+
+        let mut chart = ChartBuilder::on(&upper)
+            .margin(15)
+            .x_label_area_size(30)
+            .y_label_area_size(60)
+            .build_cartesian_2d(k_min..k_max, iv_range.0.max(0.0)..iv_range.1)?;
+        chart.configure_mesh().y_desc("Implied Vol (%)").draw()?;
+        for p in points {
+            if let Some((bid, ask)) = p.bid_ask {
+                chart.draw_series(std::iter::once(PathElement::new(
+                    vec![(p.strike, bid * 100.0), (p.strike, ask * 100.0)],
+                    BLUE.mix(0.4).stroke_width(1),
+                )))?;
+            }
+        }
+        chart
+            .draw_series(
+                points
+                    .iter()
+                    .filter(|p| p.is_call)
+                    .map(|p| Circle::new((p.strike, p.iv * 100.0), 3, RED.filled())),
+            )?
+            .label("calls")
+            .legend(|(x, y)| Circle::new((x, y), 3, RED.filled()));
+        chart
+            .draw_series(
+                points
+                    .iter()
+                    .filter(|p| !p.is_call)
+                    .map(|p| Circle::new((p.strike, p.iv * 100.0), 3, BLUE.filled())),
+            )?
+            .label("puts")
+            .legend(|(x, y)| Circle::new((x, y), 3, BLUE.filled()));
+        chart
+            .draw_series(LineSeries::new(curve.clone(), BLACK.stroke_width(2)))?
+            .label("SVI")
+            .legend(|(x, y)| PathElement::new(vec![(x - 10, y), (x + 10, y)], BLACK));
+        chart
+            .configure_series_labels()
+            .border_style(BLACK)
+            .background_style(WHITE.mix(0.8))
+            .draw()?;
+
+        let mut res_chart = ChartBuilder::on(&lower)
+            .margin(15)
+            .x_label_area_size(40)
+            .y_label_area_size(60)
+            .build_cartesian_2d(k_min..k_max, -res_extent..res_extent)?;
+        res_chart
+            .configure_mesh()
+            .x_desc("Strike")
+            .y_desc("Model - market (vol pts)")
+            .draw()?;
+        res_chart.draw_series(std::iter::once(PathElement::new(
+            vec![(k_min, 0.0), (k_max, 0.0)],
+            BLACK.mix(0.5),
+        )))?;
+        res_chart.draw_series(residuals.iter().map(|&(strike, r, is_call)| {
+            let color = if is_call { RED } else { BLUE };
+            PathElement::new(vec![(strike, 0.0), (strike, r)], color.stroke_width(2))
+        }))?;
+        Ok(())
+    })
+}
+
+/// ATM IV, risk reversals and butterflies of fixed-tenor metrics against days to expiry.
+///
+/// Risk reversal and butterfly panels draw one line per delta level.
+pub fn term_structure_svg(metrics: &[FixedTimeMetrics], options: &PlotOptions) -> Result<String> {
+    if metrics.is_empty() {
+        return Err(anyhow!("no fixed-tenor metrics to plot"));
+    }
+    let mut metrics = metrics.to_vec();
+    metrics.sort_by(|a, b| a.tte_years.total_cmp(&b.tte_years));
+
+    let days = bounds(metrics.iter().map(|m| m.tte_years * 365.0));
+    let days = if days.1 > days.0 {
+        days
+    } else {
+        (days.0 - 1.0, days.1 + 1.0)
+    };
+    let atm: Vec<(f64, f64)> = metrics
+        .iter()
+        .map(|m| (m.tte_years * 365.0, m.atm_iv * 100.0))
+        .collect();
+    // Keyed by delta level in basis points of delta, for ordering
+    let mut by_delta: BTreeMap<i64, DeltaSeries> = BTreeMap::new();
+    for m in &metrics {
+        for dm in &m.delta_metrics {
+            let entry = by_delta
+                .entry((dm.delta_level * 10_000.0).round() as i64)
+                .or_insert_with(|| DeltaSeries {
+                    level: dm.delta_level,
+                    series: [Vec::new(), Vec::new()],
+                });
+            entry.series[0].push((m.tte_years * 365.0, dm.risk_reversal * 100.0));
+            entry.series[1].push((m.tte_years * 365.0, dm.butterfly * 100.0));
+        }
+    }
+
+    let title = options.title_or(|| "Volatility term structure".to_string());
+    render(options, |root| {
+        let root = root.titled(&title, ("sans-serif", 28))?;
+        let panels = root.split_evenly((3, 1));
+
+        let atm_range = padded(bounds(atm.iter().map(|p| p.1)));
+        let mut chart = ChartBuilder::on(&panels[0])
+            .margin(10)
+            .x_label_area_size(25)
+            .y_label_area_size(60)
+            .build_cartesian_2d(days.0..days.1, atm_range.0.max(0.0)..atm_range.1)?;
+        chart.configure_mesh().y_desc("ATM IV (%)").draw()?;
+        chart.draw_series(LineSeries::new(atm.clone(), BLACK.stroke_width(2)))?;
+        chart.draw_series(atm.iter().map(|&p| Circle::new(p, 3, BLACK.filled())))?;
+
+        for (pick, (panel, label)) in panels[1..]
+            .iter()
+            .zip(["Risk reversal (%)", "Butterfly (%)"])
+            .enumerate()
+        {
+            let range = padded(bounds(
+                by_delta
+                    .values()
+                    .flat_map(|e| e.series[pick].iter())
+                    .map(|p| p.1),
+            ));
+            let last = pick == 1;
+            let mut chart = ChartBuilder::on(panel)
+                .margin(10)
+                .x_label_area_size(if last { 40 } else { 25 })
+                .y_label_area_size(60)
+                .build_cartesian_2d(days.0..days.1, range.0..range.1)?;
+            let mut mesh = chart.configure_mesh();
+            mesh.y_desc(label);
+            if last {
+                mesh.x_desc("Days to expiry");
+            }
+            mesh.draw()?;
+            for (i, entry) in by_delta.values().enumerate() {
+                let color = Palette99::pick(i).to_rgba();
+                let points = entry.series[pick].clone();
+                chart
+                    .draw_series(LineSeries::new(points.clone(), color.stroke_width(2)))?
+                    .label(format!("{:.0}δ", entry.level * 100.0))
+                    .legend(move |(x, y)| PathElement::new(vec![(x - 10, y), (x + 10, y)], color));
+                chart.draw_series(
+                    points
+                        .into_iter()
+                        .map(|p| Circle::new(p, 3, color.filled())),
+                )?;
+            }
+            if !by_delta.is_empty() {
+                chart
+                    .configure_series_labels()
+                    .border_style(BLACK)
+                    .background_style(WHITE.mix(0.8))
+                    .draw()?;
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Risk reversal and butterfly series of one delta level
+struct DeltaSeries {
+    level: f64,
+    /// `[risk reversals, butterflies]` as (days, %) points
+    series: [Vec<(f64, f64)>; 2],
+}
+
+/// The five SVI parameters of one expiry over time.
+///
+/// `history` holds `(x, params)` pairs, where `x` is the observation time in any unit
+/// (Unix seconds, snapshot version, ...).
+pub fn param_history_svg(history: &[(f64, SVIParams)], options: &PlotOptions) -> Result<String> {
+    if history.is_empty() {
+        return Err(anyhow!("no parameter history to plot"));
+    }
+    let mut history = history.to_vec();
+    history.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let xs = bounds(history.iter().map(|h| h.0));
+    let xs = if xs.1 > xs.0 {
+        xs
+    } else {
+        (xs.0 - 1.0, xs.1 + 1.0)
+    };
+    type Field = (&'static str, fn(&SVIParams) -> f64);
+    let fields: [Field; 5] = [
+        ("a", |p| p.a),
+        ("b", |p| p.b),
+        ("rho", |p| p.rho),
+        ("m", |p| p.m),
+        ("sigma", |p| p.sigma),
+    ];
+
+    let title = options.title_or(|| "SVI parameter history".to_string());
+    render(options, |root| {
+        let root = root.titled(&title, ("sans-serif", 28))?;
+        let panels = root.split_evenly((fields.len(), 1));
+        for (i, (panel, (name, field))) in panels.iter().zip(fields).enumerate() {
+            let series: Vec<(f64, f64)> = history.iter().map(|(x, p)| (*x, field(p))).collect();
+            let range = padded(bounds(series.iter().map(|p| p.1)));
+            let last = i + 1 == fields.len();
+            let mut chart = ChartBuilder::on(panel)
+                .margin(8)
+                .x_label_area_size(if last { 40 } else { 20 })
+                .y_label_area_size(70)
+                .build_cartesian_2d(xs.0..xs.1, range.0..range.1)?;
+            let mut mesh = chart.configure_mesh();
+            mesh.y_desc(name);
+            if last {
+                mesh.x_desc("Observation");
+            }
+            mesh.draw()?;
+            let color = Palette99::pick(i).to_rgba();
+            chart.draw_series(LineSeries::new(series.clone(), color.stroke_width(2)))?;
+            chart.draw_series(
+                series
+                    .into_iter()
+                    .map(|p| Circle::new(p, 2, color.filled())),
+            )?;
+        }
+        Ok(())
+    })
+}
+
+/// Parameter history of one expiry from recalibration snapshots, keyed by snapshot version
+pub fn param_history_from_snapshots(
+    snapshots: &[Arc<SurfaceSnapshot>],
+    expiration: i64,
+) -> Vec<(f64, SVIParams)> {
+    snapshots
+        .iter()
+        .filter_map(|s| {
+            s.slices
+                .iter()
+                .find(|slice| slice.expiration == expiration)
+                .map(|slice| (s.version as f64, slice.params.clone()))
+        })
+        .collect()
+}
+
+/// Total variance w(k, t) of a surface on a grid of log-moneyness and maturities.
+///
+/// Maturities span the model's first to last slice (±50% around a single slice); the
+/// colour runs from blue (lowest w) to red (highest w).
+pub fn variance_heatmap_svg(
+    model: &SVIModel,
+    k_range: (f64, f64),
+    options: &PlotOptions,
+) -> Result<String> {
+    if k_range.0 >= k_range.1 || k_range.0.is_nan() || k_range.1.is_nan() {
+        return Err(anyhow!(
+            "k range ({}, {}) must be increasing",
+            k_range.0,
+            k_range.1
+        ));
+    }
+    let ts = bounds(model.parameters().iter().map(|(t, _)| *t));
+    let ts = if ts.1 > ts.0 {
+        ts
+    } else {
+        (ts.0 * 0.5, ts.1 * 1.5)
+    };
+
+    const K_CELLS: usize = 120;
+    const T_CELLS: usize = 60;
+    let dk = (k_range.1 - k_range.0) / K_CELLS as f64;
+    let dt = (ts.1 - ts.0) / T_CELLS as f64;
+    let mut cells = Vec::with_capacity(K_CELLS * T_CELLS);
+    for i in 0..K_CELLS {
+        for j in 0..T_CELLS {
+            let k = k_range.0 + (i as f64 + 0.5) * dk;
+            let t = ts.0 + (j as f64 + 0.5) * dt;
+            cells.push((i, j, model.total_variance(k, t)?));
+        }
+    }
+    let (w_min, w_max) = bounds(cells.iter().map(|c| c.2));
+    let color = |w: f64| {
+        let x = if w_max > w_min {
+            (w - w_min) / (w_max - w_min)
+        } else {
+            0.5
+        };
+        HSLColor(2.0 / 3.0 * (1.0 - x), 0.85, 0.5)
+    };
+
+    let title = options.title_or(|| "SVI total variance w(k, t)".to_string());
+    render(options, |root| {
+        let root = root.titled(&title, ("sans-serif", 28))?;
+        let (main, legend) = root.split_horizontally(root.dim_in_pixel().0 - 140);
+
+        let mut chart = ChartBuilder::on(&main)
+            .margin(15)
+            .x_label_area_size(40)
+            .y_label_area_size(70)
+            .build_cartesian_2d(k_range.0..k_range.1, ts.0..ts.1)?;
+        chart
+            .configure_mesh()
+            .disable_mesh()
+            .x_desc("Log-moneyness k")
+            .y_desc("Maturity t (years)")
+            .draw()?;
+        chart.draw_series(cells.iter().map(|&(i, j, w)| {
+            let k0 = k_range.0 + i as f64 * dk;
+            let t0 = ts.0 + j as f64 * dt;
+            Rectangle::new([(k0, t0), (k0 + dk, t0 + dt)], color(w).filled())
+        }))?;
+        // Calibrated maturities
+        chart.draw_series(model.parameters().iter().map(|(t, _)| {
+            PathElement::new(vec![(k_range.0, *t), (k_range.1, *t)], BLACK.mix(0.6))
+        }))?;
+
+        let steps = 50;
+        let mut bar = ChartBuilder::on(&legend)
+            .margin_top(15)
+            .margin_bottom(55)
+            .margin_right(10)
+            .y_label_area_size(70)
+            .build_cartesian_2d(0.0..1.0, w_min..w_max.max(w_min + 1e-12))?;
+        bar.configure_mesh()
+            .disable_mesh()
+            .disable_x_axis()
+            .y_desc("w")
+            .draw()?;
+        let dw = (w_max - w_min) / steps as f64;
+        bar.draw_series((0..steps).map(|s| {
+            let w0 = w_min + s as f64 * dw;
+            Rectangle::new([(0.0, w0), (1.0, w0 + dw)], color(w0 + dw / 2.0).filled())
+        }))?;
+        Ok(())
+    })
+}
+
+/// Render into an SVG string
+fn render(
+    options: &PlotOptions,
+    draw: impl FnOnce(
+        &DrawingArea<SVGBackend, Shift>,
+    ) -> std::result::Result<(), Box<dyn std::error::Error>>,
+) -> Result<String> {
+    let mut svg = String::new();
+    {
+        let root =
+            SVGBackend::with_string(&mut svg, (options.width, options.height)).into_drawing_area();
+        root.fill(&WHITE)
+            .map_err(|e| anyhow!("failed to draw chart: {}", e))?;
+        draw(&root).map_err(|e| anyhow!("failed to draw chart: {}", e))?;
+        root.present()
+            .map_err(|e| anyhow!("failed to draw chart: {}", e))?;
+    }
+    Ok(svg)
+}
+
+/// Smallest and largest finite value
+fn bounds(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    let (lo, hi) = values
+        .filter(|v| v.is_finite())
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| {
+            (lo.min(v), hi.max(v))
+        });
+    if lo.is_finite() {
+        (lo, hi)
+    } else {
+        (0.0, 1.0)
+    }
+}
+
+/// Range widened by 5% on each side (and to a non-empty interval)
+fn padded((lo, hi): (f64, f64)) -> (f64, f64) {
+    let pad = ((hi - lo) * 0.05).max(1e-6 * lo.abs().max(1.0));
+    (lo - pad, hi + pad)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::linear_iv::DeltaMetrics;
+
+    fn params(t: f64) -> SVIParams {
+        SVIParams::new(t, 0.01 * t / 0.25, 0.1, -0.3, 0.0, 0.2).unwrap()
+    }
+
+    #[test]
+    fn renders_every_chart() {
+        let data: Vec<MarketDataRow> = (0..9)
+            .map(|i| {
+                let strike = 80.0 + 5.0 * i as f64;
+                MarketDataRow {
+                    option_type: if strike >= 100.0 { "call" } else { "put" }.to_string(),
+                    strike_price: strike,
+                    underlying_price: 100.0,
+                    years_to_exp: 0.25,
+                    market_iv: 0.3 + 0.001 * (strike - 100.0).powi(2) / 10.0,
+                    vega: 1.0,
+                    expiration: 0,
+                }
+            })
+            .collect();
+        let options = PlotOptions::default().with_size(640, 480);
+        let svg = smile_svg(&data, &params(0.25), &options.clone().with_title("smile")).unwrap();
+        assert!(svg.starts_with("<svg") && svg.contains("smile"));
+
+        let metrics: Vec<FixedTimeMetrics> = [7, 30, 90]
+            .iter()
+            .map(|&days| FixedTimeMetrics {
+                tenor: crate::clock::Tenor::Days(days),
+                tte_days: days,
+                tte_years: days as f64 / 365.0,
+                forward: 100.0,
+                atm_iv: 0.5 + days as f64 / 1000.0,
+                event_variance: 0.0,
+                delta_smile: Vec::new(),
+                moneyness_smile: Vec::new(),
+                delta_metrics: vec![DeltaMetrics {
+                    delta_level: 0.25,
+                    risk_reversal: -0.02,
+                    butterfly: 0.01,
+                }],
+            })
+            .collect();
+        let svg = term_structure_svg(&metrics, &options).unwrap();
+        assert!(svg.contains("25δ"));
+
+        let history: Vec<(f64, SVIParams)> = (0..5).map(|i| (i as f64, params(0.25))).collect();
+        assert!(param_history_svg(&history, &options)
+            .unwrap()
+            .contains("sigma"));
+
+        let model = SVIModel::new(vec![(0.1, params(0.1)), (0.5, params(0.5))], 1e-6).unwrap();
+        let svg = variance_heatmap_svg(&model, (-1.0, 1.0), &options).unwrap();
+        assert!(svg.contains("<rect"));
+
+        assert!(smile_svg(&[], &params(0.25), &options).is_err());
+        assert!(variance_heatmap_svg(&model, (1.0, -1.0), &options).is_err());
+    }
+}
@@ -0,0 +1,490 @@
+//! Option portfolios: valuation and risk aggregation
+//!
+//! A [`Portfolio`] holds signed quantities of option contracts on one underlying, given
+//! as [`MarketDataRow`]s (only the contract fields and `underlying_price` are used).
+//! [`Portfolio::risk`] prices every position on any [`SurfaceModel`] with
+//! [`price_option`] and aggregates:
+//!
+//! - `pv`: present value
+//! - `delta` and `gamma`: first and second derivatives in the spot, by central
+//!   differences of a relative `spot_bump`; the surface follows the spot according to
+//!   [`RiskConfig::dynamics`], as in a [`Scenario`]
+//! - `vega`: value change for one vol point (0.01) of parallel implied-vol shift, by
+//!   central differences of `vol_bump`
+//! - `theta`: value change over one calendar day on an unchanged surface; a position
+//!   expiring within the day rolls to its intrinsic value
+//!
+//! Vega is also bucketed by tenor and by delta. Each position's vega is split linearly
+//! between the two neighbouring pillars of `tenor_days` (usually the `fixed_days` of the
+//! [`TemporalConfig`](crate::models::linear_iv::TemporalConfig) behind the fixed-tenor
+//! metrics) and of `delta_pillars`, so the buckets add up to the total. Positions beyond
+//! the first or last pillar go entirely to it. Delta pillars are forward call deltas
+//! `N(d1)`: 0.9 is where a 10-delta put sits.
+
+use crate::calibration::types::{FixedParameters, MarketDataRow};
+use crate::models::linear_iv::{bs_delta_with_convention, DeltaConvention};
+use crate::models::traits::SurfaceModel;
+use crate::models::utils::price_option;
+use crate::scenario::{Scenario, SpotDynamics};
+use anyhow::{anyhow, Context, Result};
+
+const DAYS_PER_YEAR: f64 = 365.0;
+
+/// A signed quantity of one option contract
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Position {
+    pub contract: MarketDataRow,
+    /// Number of contracts; negative when short
+    pub quantity: f64,
+}
+
+/// Option positions on one underlying
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Portfolio {
+    positions: Vec<Position>,
+}
+
+/// Bumps and bucket pillars of [`Portfolio::risk`]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct RiskConfig {
+    /// How the surface follows the spot in delta and gamma
+    pub dynamics: SpotDynamics,
+    /// Relative spot bump for delta and gamma
+    pub spot_bump: f64,
+    /// Absolute implied-vol bump for vega
+    pub vol_bump: f64,
+    /// Tenor pillars of the vega buckets, in calendar days
+    pub tenor_days: Vec<i32>,
+    /// Forward call delta pillars of the vega buckets, in (0, 1)
+    pub delta_pillars: Vec<f64>,
+}
+
+impl Default for RiskConfig {
+    fn default() -> Self {
+        Self {
+            dynamics: SpotDynamics::default(),
+            spot_bump: 0.01,
+            vol_bump: 0.01,
+            tenor_days: vec![1, 3, 7, 14, 30],
+            delta_pillars: vec![0.1, 0.25, 0.5, 0.75, 0.9],
+        }
+    }
+}
+
+impl RiskConfig {
+    /// Set how the surface follows the spot in delta and gamma
+    pub fn with_dynamics(mut self, dynamics: SpotDynamics) -> Self {
+        self.dynamics = dynamics;
+        self
+    }
+
+    /// Set the tenor pillars, e.g. `&temporal_config.fixed_days`
+    pub fn with_tenor_days(mut self, days: &[i32]) -> Self {
+        self.tenor_days = days.to_vec();
+        self
+    }
+
+    /// Set the forward call delta pillars
+    pub fn with_delta_pillars(mut self, pillars: &[f64]) -> Self {
+        self.delta_pillars = pillars.to_vec();
+        self
+    }
+
+    /// Check that bumps are positive and pillars strictly increasing
+    pub fn validate(&self) -> Result<()> {
+        for (name, bump) in [("spot_bump", self.spot_bump), ("vol_bump", self.vol_bump)] {
+            if !bump.is_finite() || bump <= 0.0 || bump >= 1.0 {
+                return Err(anyhow!("{} must be in (0, 1) (got {})", name, bump));
+            }
+        }
+        if self.tenor_days.iter().any(|&d| d <= 0)
+            || self.tenor_days.windows(2).any(|w| w[1] <= w[0])
+        {
+            return Err(anyhow!(
+                "tenor_days must be positive and strictly increasing (got {:?})",
+                self.tenor_days
+            ));
+        }
+        if self.delta_pillars.iter().any(|d| !(*d > 0.0 && *d < 1.0))
+            || self.delta_pillars.windows(2).any(|w| w[1] <= w[0])
+        {
+            return Err(anyhow!(
+                "delta_pillars must be in (0, 1) and strictly increasing (got {:?})",
+                self.delta_pillars
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Valuation and risk of one position
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PositionRisk {
+    pub option_type: String,
+    pub strike_price: f64,
+    pub expiration: i64,
+    pub years_to_exp: f64,
+    pub quantity: f64,
+    /// Model price of one contract
+    pub price: f64,
+    /// Model implied volatility
+    pub iv: f64,
+    /// Forward call delta `N(d1)` used for the delta buckets
+    pub call_delta: f64,
+    pub pv: f64,
+    pub delta: f64,
+    pub gamma: f64,
+    pub vega: f64,
+    pub theta: f64,
+}
+
+/// Vega of one bucket
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VegaBucket {
+    /// Tenor in days or forward call delta
+    pub pillar: f64,
+    pub vega: f64,
+}
+
+/// Aggregated valuation and risk of a [`Portfolio`]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RiskReport {
+    pub pv: f64,
+    pub delta: f64,
+    pub gamma: f64,
+    pub vega: f64,
+    pub theta: f64,
+    pub positions: Vec<PositionRisk>,
+    /// Vega per `RiskConfig::tenor_days` pillar
+    pub vega_by_tenor: Vec<VegaBucket>,
+    /// Vega per `RiskConfig::delta_pillars` pillar
+    pub vega_by_delta: Vec<VegaBucket>,
+}
+
+impl Portfolio {
+    /// Empty portfolio
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `quantity` of `contract`
+    pub fn with_position(mut self, contract: MarketDataRow, quantity: f64) -> Self {
+        self.push(contract, quantity);
+        self
+    }
+
+    /// Add `quantity` of `contract`
+    pub fn push(&mut self, contract: MarketDataRow, quantity: f64) {
+        self.positions.push(Position { contract, quantity });
+    }
+
+    pub fn positions(&self) -> &[Position] {
+        &self.positions
+    }
+
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Present value on `model`
+    pub fn value<M: SurfaceModel>(&self, model: &M, fixed_params: &FixedParameters) -> Result<f64> {
+        self.positions
+            .iter()
+            .map(|p| {
+                let row = &p.contract;
+                let price = price_option(
+                    &row.option_type,
+                    row.strike_price,
+                    row.underlying_price,
+                    row.years_to_exp,
+                    fixed_params,
+                    model,
+                )
+                .with_context(|| format!("pricing {}", describe(row)))?;
+                Ok(p.quantity * price.price)
+            })
+            .sum()
+    }
+
+    /// Value, Greeks and bucketed vega on `model`; see the [module documentation](self)
+    pub fn risk<M: SurfaceModel>(
+        &self,
+        model: &M,
+        fixed_params: &FixedParameters,
+        config: &RiskConfig,
+    ) -> Result<RiskReport> {
+        config.validate()?;
+        let positions = self
+            .positions
+            .iter()
+            .map(|p| {
+                position_risk(p, model, fixed_params, config)
+                    .with_context(|| format!("risk of {}", describe(&p.contract)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let tenor_pillars: Vec<f64> = config.tenor_days.iter().map(|&d| d as f64).collect();
+        let mut vega_by_tenor: Vec<VegaBucket> = tenor_pillars
+            .iter()
+            .map(|&pillar| VegaBucket { pillar, vega: 0.0 })
+            .collect();
+        let mut vega_by_delta: Vec<VegaBucket> = config
+            .delta_pillars
+            .iter()
+            .map(|&pillar| VegaBucket { pillar, vega: 0.0 })
+            .collect();
+        for p in &positions {
+            for (i, weight) in pillar_weights(&tenor_pillars, p.years_to_exp * DAYS_PER_YEAR) {
+                vega_by_tenor[i].vega += weight * p.vega;
+            }
+            for (i, weight) in pillar_weights(&config.delta_pillars, p.call_delta) {
+                vega_by_delta[i].vega += weight * p.vega;
+            }
+        }
+
+        Ok(RiskReport {
+            pv: positions.iter().map(|p| p.pv).sum(),
+            delta: positions.iter().map(|p| p.delta).sum(),
+            gamma: positions.iter().map(|p| p.gamma).sum(),
+            vega: positions.iter().map(|p| p.vega).sum(),
+            theta: positions.iter().map(|p| p.theta).sum(),
+            positions,
+            vega_by_tenor,
+            vega_by_delta,
+        })
+    }
+}
+
+fn describe(row: &MarketDataRow) -> String {
+    format!(
+        "{} {} expiring {} (t={})",
+        row.option_type, row.strike_price, row.expiration, row.years_to_exp
+    )
+}
+
+fn position_risk<M: SurfaceModel>(
+    position: &Position,
+    model: &M,
+    fixed_params: &FixedParameters,
+    config: &RiskConfig,
+) -> Result<PositionRisk> {
+    let row = &position.contract;
+    let (spot, strike, t) = (row.underlying_price, row.strike_price, row.years_to_exp);
+    // Contract priced on a shocked surface at the scenario's spot
+    let shocked = |scenario: &Scenario| -> Result<f64> {
+        let surface = scenario.apply(model, spot, fixed_params);
+        Ok(price_option(
+            &row.option_type,
+            strike,
+            surface.shocked_spot(),
+            t,
+            fixed_params,
+            &surface,
+        )?
+        .price)
+    };
+
+    let base = price_option(&row.option_type, strike, spot, t, fixed_params, model)?;
+
+    let h = config.spot_bump;
+    let up = shocked(&Scenario::new("spot up").with_spot_move(h, config.dynamics))?;
+    let down = shocked(&Scenario::new("spot down").with_spot_move(-h, config.dynamics))?;
+    let delta = (up - down) / (2.0 * spot * h);
+    let gamma = (up - 2.0 * base.price + down) / (spot * h).powi(2);
+
+    let v = config.vol_bump;
+    let vol_up = shocked(&Scenario::new("vol up").with_vol_shift(v))?;
+    let vol_down = shocked(&Scenario::new("vol down").with_vol_shift(-v))?;
+    let vega = (vol_up - vol_down) / (2.0 * v) * 0.01;
+
+    let day = 1.0 / DAYS_PER_YEAR;
+    let rolled = if t > day {
+        price_option(&row.option_type, strike, spot, t - day, fixed_params, model)?.price
+    } else {
+        match row.option_type.to_lowercase().as_str() {
+            "call" => (spot - strike).max(0.0),
+            _ => (strike - spot).max(0.0),
+        }
+    };
+
+    let forward = fixed_params.forward(spot, t);
+    let call_delta = bs_delta_with_convention(
+        (strike / forward).ln(),
+        base.model_iv,
+        t,
+        true,
+        0.0,
+        DeltaConvention::Forward,
+    );
+
+    let q = position.quantity;
+    Ok(PositionRisk {
+        option_type: row.option_type.clone(),
+        strike_price: strike,
+        expiration: row.expiration,
+        years_to_exp: t,
+        quantity: q,
+        price: base.price,
+        iv: base.model_iv,
+        call_delta,
+        pv: q * base.price,
+        delta: q * delta,
+        gamma: q * gamma,
+        vega: q * vega,
+        theta: q * (rolled - base.price),
+    })
+}
+
+/// Indices and weights splitting `x` linearly between the neighbouring `pillars`
+fn pillar_weights(pillars: &[f64], x: f64) -> Vec<(usize, f64)> {
+    let Some(last) = pillars.len().checked_sub(1) else {
+        return Vec::new();
+    };
+    let i = pillars.partition_point(|&p| p <= x);
+    if i == 0 {
+        vec![(0, 1.0)]
+    } else if i > last {
+        vec![(last, 1.0)]
+    } else {
+        let weight = (x - pillars[i - 1]) / (pillars[i] - pillars[i - 1]);
+        vec![(i - 1, 1.0 - weight), (i, weight)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use statrs::distribution::{Continuous, ContinuousCDF, Normal};
+
+    /// Flat implied volatility
+    struct FlatVol(f64);
+
+    impl SurfaceModel for FlatVol {
+        type Parameters = f64;
+
+        fn parameters(&self) -> &f64 {
+            &self.0
+        }
+
+        fn validate_params(&self) -> Result<()> {
+            Ok(())
+        }
+
+        fn total_variance(&self, _k: f64, t: f64) -> Result<f64> {
+            Ok(self.0 * self.0 * t)
+        }
+
+        fn check_calendar_arbitrage(&self, _k: f64, _t1: f64, _t2: f64) -> Result<()> {
+            Ok(())
+        }
+
+        fn check_butterfly_arbitrage_at_k(&self, _k: f64, _t: f64) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    fn contract(option_type: &str, strike: f64, days: f64) -> MarketDataRow {
+        MarketDataRow {
+            option_type: option_type.into(),
+            strike_price: strike,
+            underlying_price: 100.0,
+            years_to_exp: days / DAYS_PER_YEAR,
+            market_iv: 0.0,
+            vega: 0.0,
+            expiration: (days * 86_400.0) as i64,
+        }
+    }
+
+    #[test]
+    fn test_greeks_match_black_scholes() {
+        let (sigma, r, q) = (0.3, 0.03, 0.01);
+        let model = FlatVol(sigma);
+        let fixed = FixedParameters::flat(r, q);
+        let portfolio = Portfolio::new().with_position(contract("call", 105.0, 60.0), 2.0);
+        let report = portfolio
+            .risk(&model, &fixed, &RiskConfig::default())
+            .unwrap();
+
+        let (s, k, t): (f64, f64, f64) = (100.0, 105.0, 60.0 / DAYS_PER_YEAR);
+        let n = Normal::new(0.0, 1.0).unwrap();
+        let d1 = ((s / k).ln() + (r - q + 0.5 * sigma * sigma) * t) / (sigma * t.sqrt());
+        let carry = (-q * t).exp();
+        let delta = 2.0 * carry * n.cdf(d1);
+        let gamma = 2.0 * carry * n.pdf(d1) / (s * sigma * t.sqrt());
+        let vega = 2.0 * s * carry * n.pdf(d1) * t.sqrt() * 0.01;
+
+        assert!(
+            (report.delta - delta).abs() < 1e-3 * delta,
+            "delta {}",
+            report.delta
+        );
+        assert!(
+            (report.gamma - gamma).abs() < 1e-3 * gamma,
+            "gamma {}",
+            report.gamma
+        );
+        assert!(
+            (report.vega - vega).abs() < 1e-3 * vega,
+            "vega {}",
+            report.vega
+        );
+        assert!(report.theta < 0.0);
+        assert!((report.pv - portfolio.value(&model, &fixed).unwrap()).abs() < 1e-12);
+        assert!((report.positions[0].call_delta - n.cdf(d1)).abs() < 1e-12);
+
+        // A long call and short put at one strike is a forward: no gamma or vega
+        let synthetic = Portfolio::new()
+            .with_position(contract("call", 100.0, 30.0), 1.0)
+            .with_position(contract("put", 100.0, 30.0), -1.0);
+        let report = synthetic
+            .risk(&model, &fixed, &RiskConfig::default())
+            .unwrap();
+        assert!((report.delta - (-q * 30.0 / DAYS_PER_YEAR).exp()).abs() < 1e-6);
+        assert!(report.gamma.abs() < 1e-6);
+        assert!(report.vega.abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_vega_buckets() {
+        let model = FlatVol(0.5);
+        let fixed = FixedParameters::flat(0.0, 0.0);
+        let portfolio = Portfolio::new()
+            .with_position(contract("call", 100.0, 10.0), 1.0)
+            .with_position(contract("put", 80.0, 45.0), -3.0)
+            .with_position(contract("call", 150.0, 0.5), 4.0);
+        let config = RiskConfig::default().with_tenor_days(&[7, 14, 30]);
+        let report = portfolio.risk(&model, &fixed, &config).unwrap();
+        let vegas: Vec<f64> = report.positions.iter().map(|p| p.vega).collect();
+
+        let tenor: Vec<f64> = report.vega_by_tenor.iter().map(|b| b.vega).collect();
+        assert!((tenor[0] - (vegas[0] * 4.0 / 7.0 + vegas[2])).abs() < 1e-12);
+        assert!((tenor[1] - vegas[0] * 3.0 / 7.0).abs() < 1e-12);
+        assert!((tenor[2] - vegas[1]).abs() < 1e-12);
+
+        let by_delta: f64 = report.vega_by_delta.iter().map(|b| b.vega).sum();
+        assert!((by_delta - report.vega).abs() < 1e-12);
+        // The short 80 put is deep in the high call-delta wing
+        assert!(report.positions[1].call_delta > 0.9);
+        assert!((report.vega_by_delta[4].vega - vegas[1]).abs() < 1e-9);
+
+        // The half-day call expires worthless within the day
+        assert!((report.positions[2].theta + report.positions[2].pv).abs() < 1e-12);
+
+        let bad = RiskConfig::default().with_delta_pillars(&[0.5, 0.25]);
+        assert!(portfolio.risk(&model, &fixed, &bad).is_err());
+        let bad = RiskConfig::default().with_tenor_days(&[0, 7]);
+        assert!(portfolio.risk(&model, &fixed, &bad).is_err());
+    }
+}
@@ -0,0 +1,498 @@
+//! Realised volatility estimators
+//!
+//! Estimates the volatility an underlying actually delivered, to judge whether implied
+//! volatilities are rich or cheap. Prices come as OHLC [`Bar`]s, built directly or by
+//! sampling a tick series with [`bars_from_ticks`]:
+//!
+//! | Estimator | Per-bar variance |
+//! |---|---|
+//! | `CloseToClose` | `ln(C_i / C_{i-1})²` |
+//! | `Parkinson` | `ln(H / L)² / (4 ln 2)` |
+//! | `GarmanKlass` | `½ ln(H / L)² − (2 ln 2 − 1) ln(C / O)²` |
+//! | `RogersSatchell` | `ln(H / C) ln(H / O) + ln(L / C) ln(L / O)` |
+//! | `YangZhang` | overnight variance `+ k·` open-to-close variance `+ (1 − k)·` Rogers–Satchell |
+//!
+//! Close-to-close on intraday bars is the realised variance of intraday returns
+//! ([`realized_variance`]). Parkinson, Garman–Klass and Rogers–Satchell only see the
+//! moves within each bar, so on daily bars they miss overnight gaps; Yang–Zhang adds
+//! them back.
+//!
+//! Variances are summed over the bars of a window and annualised over its calendar
+//! length in ACT/365 years, the day count of `years_to_exp`, so a realised vol can be
+//! compared directly with an implied one. [`realized_windows`] measures the trailing
+//! windows of a [`TemporalConfig`]'s fixed days and tenors, matching the
+//! `FixedTimeMetrics` built with it.
+
+use crate::clock::Tenor;
+use crate::io::SECONDS_PER_YEAR;
+use crate::models::linear_iv::TemporalConfig;
+use anyhow::{anyhow, Result};
+use std::fmt;
+use std::str::FromStr;
+
+/// Open, high, low and close prices of one period
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bar {
+    /// End of the period (Unix seconds)
+    pub time: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+}
+
+impl Bar {
+    /// Bar with positive prices and `low <= open, close <= high`
+    pub fn new(time: i64, open: f64, high: f64, low: f64, close: f64) -> Result<Self> {
+        let bar = Self {
+            time,
+            open,
+            high,
+            low,
+            close,
+        };
+        bar.validate()?;
+        Ok(bar)
+    }
+
+    /// Bar of a single price observation
+    pub fn flat(time: i64, price: f64) -> Self {
+        Self {
+            time,
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+        }
+    }
+
+    /// Check that prices are positive and finite with `low <= open, close <= high`
+    pub fn validate(&self) -> Result<()> {
+        let prices = [self.open, self.high, self.low, self.close];
+        if prices.iter().any(|p| !p.is_finite() || *p <= 0.0) {
+            return Err(anyhow!(
+                "Bar at {}: prices must be positive and finite",
+                self.time
+            ));
+        }
+        if self.low > self.open.min(self.close) || self.high < self.open.max(self.close) {
+            return Err(anyhow!(
+                "Bar at {}: low {} and high {} must bracket open {} and close {}",
+                self.time,
+                self.low,
+                self.high,
+                self.open,
+                self.close
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Realised volatility estimator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum RealizedEstimator {
+    /// Squared close-to-close log returns
+    #[default]
+    CloseToClose,
+    /// High-low range (Parkinson, 1980)
+    Parkinson,
+    /// Range and open-to-close return (Garman and Klass, 1980)
+    GarmanKlass,
+    /// Drift-independent range estimator (Rogers and Satchell, 1991)
+    RogersSatchell,
+    /// Overnight, open-to-close and Rogers–Satchell variances combined (Yang and Zhang, 2000)
+    YangZhang,
+}
+
+impl RealizedEstimator {
+    /// All estimators, in documentation order
+    pub const ALL: [RealizedEstimator; 5] = [
+        RealizedEstimator::CloseToClose,
+        RealizedEstimator::Parkinson,
+        RealizedEstimator::GarmanKlass,
+        RealizedEstimator::RogersSatchell,
+        RealizedEstimator::YangZhang,
+    ];
+
+    /// Name used in configs and on the command line
+    pub fn name(self) -> &'static str {
+        match self {
+            RealizedEstimator::CloseToClose => "close_to_close",
+            RealizedEstimator::Parkinson => "parkinson",
+            RealizedEstimator::GarmanKlass => "garman_klass",
+            RealizedEstimator::RogersSatchell => "rogers_satchell",
+            RealizedEstimator::YangZhang => "yang_zhang",
+        }
+    }
+
+    /// Total variance of `bars`, each measured from the previous bar's close; the first
+    /// bar only provides that close
+    fn total_variance(self, bars: &[Bar]) -> Result<f64> {
+        let n = bars.len().saturating_sub(1);
+        let min_bars = if self == RealizedEstimator::YangZhang {
+            2
+        } else {
+            1
+        };
+        if n < min_bars {
+            return Err(anyhow!(
+                "{} needs at least {} bars after the first, got {}",
+                self,
+                min_bars,
+                n
+            ));
+        }
+        let pairs = bars.windows(2).map(|w| (&w[0], &w[1]));
+        let ln = |a: f64, b: f64| (a / b).ln();
+        let rogers_satchell = |b: &Bar| {
+            ln(b.high, b.close) * ln(b.high, b.open) + ln(b.low, b.close) * ln(b.low, b.open)
+        };
+
+        Ok(match self {
+            RealizedEstimator::CloseToClose => {
+                pairs.map(|(p, b)| ln(b.close, p.close).powi(2)).sum()
+            }
+            RealizedEstimator::Parkinson => {
+                pairs.map(|(_, b)| ln(b.high, b.low).powi(2)).sum::<f64>() / (4.0 * 2f64.ln())
+            }
+            RealizedEstimator::GarmanKlass => pairs
+                .map(|(_, b)| {
+                    0.5 * ln(b.high, b.low).powi(2)
+                        - (2.0 * 2f64.ln() - 1.0) * ln(b.close, b.open).powi(2)
+                })
+                .sum(),
+            RealizedEstimator::RogersSatchell => pairs.map(|(_, b)| rogers_satchell(b)).sum(),
+            RealizedEstimator::YangZhang => {
+                let overnight: Vec<f64> = pairs.clone().map(|(p, b)| ln(b.open, p.close)).collect();
+                let open_close: Vec<f64> =
+                    pairs.clone().map(|(_, b)| ln(b.close, b.open)).collect();
+                let rs = pairs.map(|(_, b)| rogers_satchell(b)).sum::<f64>() / n as f64;
+                let nf = n as f64;
+                let k = 0.34 / (1.34 + (nf + 1.0) / (nf - 1.0));
+                nf * (sample_variance(&overnight)
+                    + k * sample_variance(&open_close)
+                    + (1.0 - k) * rs)
+            }
+        })
+    }
+}
+
+impl fmt::Display for RealizedEstimator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for RealizedEstimator {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        RealizedEstimator::ALL
+            .into_iter()
+            .find(|e| e.name() == s)
+            .ok_or_else(|| {
+                anyhow!(
+                    "unknown realised volatility estimator `{}` (expected one of: {})",
+                    s,
+                    RealizedEstimator::ALL
+                        .map(RealizedEstimator::name)
+                        .join(", ")
+                )
+            })
+    }
+}
+
+/// Realised volatility of one trailing window
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RealizedWindow {
+    /// Window length, a `fixed_days` entry or one of `TemporalConfig::tenors`
+    pub tenor: Tenor,
+    /// Start of the window (Unix seconds)
+    pub start: i64,
+    /// End of the window (Unix seconds)
+    pub end: i64,
+    /// Bars measured, excluding the one providing the starting close
+    pub bars: usize,
+    /// Annualised realised volatility
+    pub vol: f64,
+}
+
+/// Annualised realised volatility of time-sorted `bars`
+///
+/// The first bar only provides the starting close; the variance of the others is
+/// annualised over the ACT/365 time from the first bar to the last.
+///
+/// ```rust
+/// use surface_lib::realized::{realized_vol, Bar, RealizedEstimator};
+///
+/// // Closes alternating between 100 and 101 once a day
+/// let bars: Vec<Bar> = (0..=20)
+///     .map(|d| Bar::flat(d * 86_400, if d % 2 == 0 { 100.0 } else { 101.0 }))
+///     .collect();
+/// let vol = realized_vol(&bars, RealizedEstimator::CloseToClose)?;
+/// assert!((vol - (101.0f64 / 100.0).ln() * 365f64.sqrt()).abs() < 1e-12);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn realized_vol(bars: &[Bar], estimator: RealizedEstimator) -> Result<f64> {
+    let (Some(first), Some(last)) = (bars.first(), bars.last()) else {
+        return Err(anyhow!("No bars provided"));
+    };
+    if bars.windows(2).any(|w| w[1].time <= w[0].time) {
+        return Err(anyhow!("Bars must be in strictly increasing time order"));
+    }
+    for bar in bars {
+        bar.validate()?;
+    }
+    let years = (last.time - first.time) as f64 / SECONDS_PER_YEAR;
+    if years <= 0.0 {
+        return Err(anyhow!("Bars must span a positive time"));
+    }
+    let variance = estimator.total_variance(bars)? / years;
+    Ok(variance.max(0.0).sqrt())
+}
+
+/// Annualised realised variance of intraday returns: `ticks` sampled every
+/// `interval` seconds, then summed squared returns
+///
+/// `ticks` are `(Unix seconds, price)` pairs in any order.
+pub fn realized_variance(ticks: &[(i64, f64)], interval: i64) -> Result<f64> {
+    let vol = realized_vol(
+        &bars_from_ticks(ticks, interval)?,
+        RealizedEstimator::CloseToClose,
+    )?;
+    Ok(vol * vol)
+}
+
+/// OHLC bars of `ticks` over consecutive `interval`-second periods aligned to the Unix
+/// epoch, stamped at the end of each period
+///
+/// The first bar is the period of the first tick. Periods without ticks repeat the
+/// previous close, so returns are sampled on a regular grid (previous-tick sampling).
+pub fn bars_from_ticks(ticks: &[(i64, f64)], interval: i64) -> Result<Vec<Bar>> {
+    if interval <= 0 {
+        return Err(anyhow!(
+            "Sampling interval must be positive (got {})",
+            interval
+        ));
+    }
+    if let Some((time, price)) = ticks.iter().find(|(_, p)| !p.is_finite() || *p <= 0.0) {
+        return Err(anyhow!(
+            "Tick at {}: price must be positive and finite (got {})",
+            time,
+            price
+        ));
+    }
+    let mut ticks = ticks.to_vec();
+    ticks.sort_by_key(|(t, _)| *t);
+
+    let mut bars: Vec<Bar> = Vec::new();
+    for (time, price) in ticks {
+        let end = (time.div_euclid(interval) + 1) * interval;
+        if let Some(bar) = bars.last_mut().filter(|b| b.time == end) {
+            bar.high = bar.high.max(price);
+            bar.low = bar.low.min(price);
+            bar.close = price;
+            continue;
+        }
+        // Fill periods without ticks with the previous close
+        while let Some(last) = bars.last().copied().filter(|b| b.time + interval < end) {
+            bars.push(Bar::flat(last.time + interval, last.close));
+        }
+        bars.push(Bar::flat(end, price));
+    }
+    Ok(bars)
+}
+
+/// Realised volatility over the trailing window of each of `config.targets()` ending at
+/// `end`
+///
+/// A window of length `d` uses the bars stamped in `(end − d, end]` plus the last bar
+/// at or before `end − d` for the starting close. Dated tenors, windows reaching back
+/// before the first bar and windows with too few bars are skipped.
+pub fn realized_windows(
+    bars: &[Bar],
+    estimator: RealizedEstimator,
+    config: &TemporalConfig,
+    end: i64,
+) -> Vec<RealizedWindow> {
+    config
+        .targets()
+        .filter(|tenor| tenor.is_relative())
+        .filter_map(|tenor| {
+            let start = end - (tenor.expiration(end) - end);
+            let window = trailing_window(bars, start, end);
+            if window.first()?.time > start {
+                return None;
+            }
+            let vol = realized_vol(window, estimator).ok()?;
+            Some(RealizedWindow {
+                tenor,
+                start,
+                end,
+                bars: window.len() - 1,
+                vol,
+            })
+        })
+        .collect()
+}
+
+/// Time-sorted bars stamped in `(start, end]`, preceded by the last bar at or before
+/// `start` when there is one
+pub(crate) fn trailing_window(bars: &[Bar], start: i64, end: i64) -> &[Bar] {
+    let first = bars.partition_point(|b| b.time <= start).saturating_sub(1);
+    let last = bars.partition_point(|b| b.time <= end);
+    &bars[first.min(last)..last]
+}
+
+/// Sample variance (n − 1 denominator) of at least two values
+fn sample_variance(values: &[f64]) -> f64 {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    values.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / (n - 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    const DAY: i64 = 86_400;
+
+    /// Five-minute ticks of a driftless GBM with volatility `sigma`, around the clock
+    fn simulated_ticks(sigma: f64, days: i64, seed: u64) -> Vec<(i64, f64)> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let step = 300;
+        let dt = step as f64 / SECONDS_PER_YEAR;
+        let mut price = 100.0;
+        (0..days * DAY / step)
+            .map(|i| {
+                let u1: f64 = 1.0 - rng.gen::<f64>();
+                let u2: f64 = rng.gen::<f64>();
+                let z = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+                price *= (sigma * dt.sqrt() * z - 0.5 * sigma * sigma * dt).exp();
+                (i * step + 1, price)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_estimators_recover_simulated_vol() {
+        let sigma = 0.3;
+        let ticks = simulated_ticks(sigma, 250, 7);
+        let daily = bars_from_ticks(&ticks, DAY).unwrap();
+        assert_eq!(daily.len(), 250);
+
+        for estimator in RealizedEstimator::ALL {
+            let vol = realized_vol(&daily, estimator).unwrap();
+            assert!(
+                (vol - sigma).abs() < 0.1 * sigma,
+                "{} estimated {:.4}",
+                estimator,
+                vol
+            );
+        }
+
+        // Intraday realised variance is far tighter than any daily estimator
+        let rv = realized_variance(&ticks, 300).unwrap();
+        assert!(
+            (rv.sqrt() - sigma).abs() < 0.02 * sigma,
+            "rv vol {}",
+            rv.sqrt()
+        );
+    }
+
+    #[test]
+    fn test_range_estimators_on_known_bars() {
+        // Every bar opens at 100, ranges 99-102 and closes at 101
+        let bars: Vec<Bar> = (0..=10)
+            .map(|d| Bar::new(d * DAY, 100.0, 102.0, 99.0, 101.0).unwrap())
+            .collect();
+        let years = 10.0 / 365.0;
+        let hl = (102.0f64 / 99.0).ln();
+        let co = (101.0f64 / 100.0).ln();
+        let expect = |per_bar: f64| (10.0 * per_bar / years).sqrt();
+
+        let parkinson = realized_vol(&bars, RealizedEstimator::Parkinson).unwrap();
+        assert!((parkinson - expect(hl * hl / (4.0 * 2f64.ln()))).abs() < 1e-12);
+
+        let gk = realized_vol(&bars, RealizedEstimator::GarmanKlass).unwrap();
+        let gk_bar = 0.5 * hl * hl - (2.0 * 2f64.ln() - 1.0) * co * co;
+        assert!((gk - expect(gk_bar)).abs() < 1e-12);
+
+        let ln = |a: f64, b: f64| (a / b).ln();
+        let rs_bar = ln(102.0, 101.0) * ln(102.0, 100.0) + ln(99.0, 101.0) * ln(99.0, 100.0);
+        let rs = realized_vol(&bars, RealizedEstimator::RogersSatchell).unwrap();
+        assert!((rs - expect(rs_bar)).abs() < 1e-12);
+
+        // Identical bars: no dispersion in overnight or open-to-close returns
+        let k = 0.34 / (1.34 + 11.0 / 9.0);
+        let yz = realized_vol(&bars, RealizedEstimator::YangZhang).unwrap();
+        assert!((yz - expect((1.0 - k) * rs_bar)).abs() < 1e-12);
+
+        assert!(realized_vol(&bars[..2], RealizedEstimator::YangZhang).is_err());
+        assert!(realized_vol(&bars[..1], RealizedEstimator::CloseToClose).is_err());
+        assert!(Bar::new(0, 100.0, 99.0, 98.0, 100.0).is_err());
+        assert!(Bar::new(0, 100.0, 101.0, -1.0, 100.0).is_err());
+    }
+
+    #[test]
+    fn test_ticks_and_windows() {
+        // Three ticks in the first hour, none in the second, one in the third
+        let ticks = [(3_500, 101.0), (100, 100.0), (7_300, 103.0), (3_000, 99.0)];
+        let bars = bars_from_ticks(&ticks, 3_600).unwrap();
+        let times: Vec<i64> = bars.iter().map(|b| b.time).collect();
+        assert_eq!(times, vec![3_600, 7_200, 10_800]);
+        assert_eq!(bars[0], Bar::new(3_600, 100.0, 101.0, 99.0, 101.0).unwrap());
+        assert_eq!(
+            bars[1],
+            Bar::new(7_200, 101.0, 101.0, 101.0, 101.0).unwrap()
+        );
+        assert_eq!(bars[2], Bar::flat(10_800, 103.0));
+        assert!(bars_from_ticks(&ticks, 0).is_err());
+
+        // Daily closes alternating 100 / 101 for 40 days
+        let bars: Vec<Bar> = (0..=40)
+            .map(|d| Bar::flat(d * DAY, if d % 2 == 0 { 100.0 } else { 101.0 }))
+            .collect();
+        let config = TemporalConfig {
+            fixed_days: vec![7, 30, 60],
+            tenors: vec![Tenor::Hours(12), Tenor::At(0)],
+            ..TemporalConfig::default()
+        };
+        let windows = realized_windows(&bars, RealizedEstimator::CloseToClose, &config, 40 * DAY);
+        let tenors: Vec<Tenor> = windows.iter().map(|w| w.tenor).collect();
+        // Dated tenors and the 60 days the data doesn't cover are skipped; 12 hours
+        // starts from the previous daily close
+        assert_eq!(
+            tenors,
+            vec![Tenor::Days(7), Tenor::Days(30), Tenor::Hours(12)]
+        );
+        let daily = (101.0f64 / 100.0).ln() * 365f64.sqrt();
+        assert_eq!(windows[0].bars, 7);
+        assert_eq!(windows[0].start, 33 * DAY);
+        assert!((windows[0].vol - daily).abs() < 1e-12);
+        assert_eq!(windows[1].bars, 30);
+        assert_eq!(windows[2].bars, 1);
+        // A window starting exactly on the first bar is covered
+        let config = TemporalConfig {
+            fixed_days: vec![40, 41],
+            tenors: vec![],
+            ..TemporalConfig::default()
+        };
+        let windows = realized_windows(&bars, RealizedEstimator::CloseToClose, &config, 40 * DAY);
+        assert_eq!(windows.len(), 1);
+        assert_eq!((windows[0].start, windows[0].bars), (0, 40));
+
+        assert_eq!(
+            "yang_zhang".parse::<RealizedEstimator>().unwrap(),
+            RealizedEstimator::YangZhang
+        );
+        assert!("hodges_tompkins".parse::<RealizedEstimator>().is_err());
+    }
+}
@@ -0,0 +1,622 @@
+//! Scenario and stress analysis of volatility surfaces
+//!
+//! A [`Scenario`] combines a relative spot move with implied-volatility shocks:
+//!
+//! - `vol_shift`: parallel shift, in vol points (0.02 = +2 vols)
+//! - `skew_tilt`: vol change per unit of log-moneyness, `tilt·k`, steepening (negative)
+//!   or flattening (positive) the skew
+//! - `term_twist`: vol change per unit of `ln(t / twist_pivot)`, so the pivot maturity
+//!   is unchanged and a positive twist raises longer maturities and lowers shorter ones
+//!
+//! [`Scenario::apply`] turns any [`SurfaceModel`] into a [`ShockedSurface`], itself a
+//! `SurfaceModel` in the coordinates [`price_option`] uses after the spot move. How the
+//! base surface follows the spot is the scenario's [`SpotDynamics`]:
+//!
+//! | Dynamics | Held fixed | Base surface queried at |
+//! |---|---|---|
+//! | `StickyStrike` | vol of each strike | the same strike |
+//! | `StickyMoneyness` | vol at each `K / S` | the strike scaled by `S / S'` |
+//! | `StickyDelta` | vol at each forward delta | the same log-moneyness `k` |
+//!
+//! Rates and dividend yields carry spot and forward together, so sticky-moneyness and
+//! sticky-delta only differ when discrete cash dividends make the forward move less than
+//! proportionally with the spot. Vol shocks apply on top, at the shocked log-moneyness;
+//! shocked vols are floored at [`MIN_SHOCKED_VOL`].
+//!
+//! [`run_scenarios`] reprices a set of contracts under each scenario and reports P&L
+//! against the base surface, one unit long per contract.
+
+use crate::calibration::types::{FixedParameters, MarketDataRow};
+use crate::models::svi::svi_model::check_butterfly;
+use crate::models::traits::SurfaceModel;
+use crate::models::utils::price_option;
+use anyhow::{anyhow, Context, Result};
+use std::fmt;
+use std::str::FromStr;
+
+/// Lowest implied volatility a shock can produce
+pub const MIN_SHOCKED_VOL: f64 = 1e-4;
+
+/// How the surface moves with the spot
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum SpotDynamics {
+    /// Each strike keeps its implied volatility
+    #[default]
+    StickyStrike,
+    /// The smile moves with the spot: vols are fixed per `K / S`
+    StickyMoneyness,
+    /// Vols are fixed per forward delta, i.e. per forward log-moneyness
+    StickyDelta,
+}
+
+impl SpotDynamics {
+    /// All dynamics, in documentation order
+    pub const ALL: [SpotDynamics; 3] = [
+        SpotDynamics::StickyStrike,
+        SpotDynamics::StickyMoneyness,
+        SpotDynamics::StickyDelta,
+    ];
+
+    /// Name used in configs and on the command line
+    pub fn name(self) -> &'static str {
+        match self {
+            SpotDynamics::StickyStrike => "sticky_strike",
+            SpotDynamics::StickyMoneyness => "sticky_moneyness",
+            SpotDynamics::StickyDelta => "sticky_delta",
+        }
+    }
+}
+
+impl fmt::Display for SpotDynamics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for SpotDynamics {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        SpotDynamics::ALL
+            .into_iter()
+            .find(|d| d.name() == s)
+            .ok_or_else(|| {
+                anyhow!(
+                    "unknown spot dynamics `{}` (expected one of: {})",
+                    s,
+                    SpotDynamics::ALL.map(SpotDynamics::name).join(", ")
+                )
+            })
+    }
+}
+
+/// A named set of spot and volatility shocks
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct Scenario {
+    pub name: String,
+    /// Relative spot move (-0.1 = spot down 10%)
+    pub spot_shift: f64,
+    /// How the surface follows the spot move
+    pub dynamics: SpotDynamics,
+    /// Parallel implied-vol shift
+    pub vol_shift: f64,
+    /// Implied-vol change per unit of log-moneyness
+    pub skew_tilt: f64,
+    /// Implied-vol change per unit of `ln(t / twist_pivot)`
+    pub term_twist: f64,
+    /// Maturity (years) left unchanged by the twist
+    pub twist_pivot: f64,
+}
+
+impl Default for Scenario {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            spot_shift: 0.0,
+            dynamics: SpotDynamics::default(),
+            vol_shift: 0.0,
+            skew_tilt: 0.0,
+            term_twist: 0.0,
+            twist_pivot: 0.25,
+        }
+    }
+}
+
+impl Scenario {
+    /// Scenario without any shock
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Move the spot by `shift` (relative) under `dynamics`
+    pub fn with_spot_move(mut self, shift: f64, dynamics: SpotDynamics) -> Self {
+        self.spot_shift = shift;
+        self.dynamics = dynamics;
+        self
+    }
+
+    /// Shift all implied vols by `shift`
+    pub fn with_vol_shift(mut self, shift: f64) -> Self {
+        self.vol_shift = shift;
+        self
+    }
+
+    /// Add `tilt·k` to the implied vol at log-moneyness `k`
+    pub fn with_skew_tilt(mut self, tilt: f64) -> Self {
+        self.skew_tilt = tilt;
+        self
+    }
+
+    /// Add `twist·ln(t / pivot)` to the implied vols at maturity `t` (years)
+    pub fn with_term_twist(mut self, twist: f64, pivot: f64) -> Self {
+        self.term_twist = twist;
+        self.twist_pivot = pivot;
+        self
+    }
+
+    /// Check that shocks are finite, the spot stays positive and the pivot is positive
+    pub fn validate(&self) -> Result<()> {
+        let shocks = [
+            self.spot_shift,
+            self.vol_shift,
+            self.skew_tilt,
+            self.term_twist,
+        ];
+        if shocks.iter().any(|s| !s.is_finite()) {
+            return Err(anyhow!("Scenario `{}`: shocks must be finite", self.name));
+        }
+        if self.spot_shift <= -1.0 {
+            return Err(anyhow!(
+                "Scenario `{}`: spot_shift must be > -1 (got {})",
+                self.name,
+                self.spot_shift
+            ));
+        }
+        if !self.twist_pivot.is_finite() || self.twist_pivot <= 0.0 {
+            return Err(anyhow!(
+                "Scenario `{}`: twist_pivot must be positive (got {})",
+                self.name,
+                self.twist_pivot
+            ));
+        }
+        Ok(())
+    }
+
+    /// Shocked spot of `spot`
+    pub fn shocked_spot(&self, spot: f64) -> f64 {
+        spot * (1.0 + self.spot_shift)
+    }
+
+    /// `base` shocked by this scenario, for contracts on an underlying at `spot`
+    ///
+    /// The result is queried in the coordinates of the shocked spot: `price_option` on it
+    /// must be given [`Scenario::shocked_spot`] and the same `fixed_params`.
+    pub fn apply<'a, M: SurfaceModel>(
+        &'a self,
+        base: &'a M,
+        spot: f64,
+        fixed_params: &'a FixedParameters,
+    ) -> ShockedSurface<'a, M> {
+        ShockedSurface {
+            base,
+            scenario: self,
+            spot,
+            fixed_params,
+        }
+    }
+
+    /// Implied-vol shock at log-moneyness `k` and maturity `t`
+    fn vol_shock(&self, k: f64, t: f64) -> f64 {
+        self.vol_shift + self.skew_tilt * k + self.term_twist * (t / self.twist_pivot).ln()
+    }
+}
+
+/// A surface shocked by a [`Scenario`]
+///
+/// Log-moneyness and total variance are those of the shocked spot; see the
+/// [module documentation](self).
+#[derive(Debug, Clone, Copy)]
+pub struct ShockedSurface<'a, M: SurfaceModel> {
+    base: &'a M,
+    scenario: &'a Scenario,
+    spot: f64,
+    fixed_params: &'a FixedParameters,
+}
+
+impl<M: SurfaceModel> ShockedSurface<'_, M> {
+    /// The unshocked surface
+    pub fn base(&self) -> &M {
+        self.base
+    }
+
+    /// Spot before the move
+    pub fn base_spot(&self) -> f64 {
+        self.spot
+    }
+
+    /// Spot after the move
+    pub fn shocked_spot(&self) -> f64 {
+        self.scenario.shocked_spot(self.spot)
+    }
+
+    /// Log-moneyness on the base surface of the contract at shocked log-moneyness `k`
+    fn base_log_moneyness(&self, k: f64, t: f64) -> Result<f64> {
+        let spot_ratio = 1.0 + self.scenario.spot_shift;
+        let adjusted = |spot: f64| self.fixed_params.dividend_adjusted_spot(spot, t);
+        let (before, after) = (adjusted(self.spot), adjusted(self.shocked_spot()));
+        if before <= 0.0 || after <= 0.0 {
+            return Err(anyhow!(
+                "Non-positive dividend-adjusted spot at t={}: dividends exceed the carried spot",
+                t
+            ));
+        }
+        let strike_shift = (after / before).ln();
+        Ok(match self.scenario.dynamics {
+            SpotDynamics::StickyStrike => k + strike_shift,
+            SpotDynamics::StickyMoneyness => k + strike_shift - spot_ratio.ln(),
+            SpotDynamics::StickyDelta => k,
+        })
+    }
+}
+
+impl<M: SurfaceModel> SurfaceModel for ShockedSurface<'_, M> {
+    type Parameters = Scenario;
+
+    fn parameters(&self) -> &Scenario {
+        self.scenario
+    }
+
+    fn validate_params(&self) -> Result<()> {
+        self.scenario.validate()?;
+        self.base.validate_params()
+    }
+
+    /// Total variance of the base surface at the mapped log-moneyness, with the vol
+    /// shocks added in implied-vol space
+    fn total_variance(&self, k: f64, t: f64) -> Result<f64> {
+        if !k.is_finite() || !t.is_finite() || t <= 0.0 {
+            return Err(anyhow!(
+                "Invalid point for shocked surface: k={}, t={}",
+                k,
+                t
+            ));
+        }
+        let base_var = self
+            .base
+            .total_variance(self.base_log_moneyness(k, t)?, t)?;
+        let vol = (base_var.max(0.0) / t).sqrt() + self.scenario.vol_shock(k, t);
+        Ok(vol.max(MIN_SHOCKED_VOL).powi(2) * t)
+    }
+
+    fn check_calendar_arbitrage(&self, k: f64, t1: f64, t2: f64) -> Result<()> {
+        if t1 >= t2 {
+            return Err(anyhow!(
+                "Calendar check requires t1 < t2, got t1={}, t2={}",
+                t1,
+                t2
+            ));
+        }
+        let w1 = self.total_variance(k, t1)?;
+        let w2 = self.total_variance(k, t2)?;
+        if w2 < w1 {
+            Err(anyhow!(
+                "Calendar arbitrage detected at k={:.6}: w(t1={:.4})={:.6} > w(t2={:.4})={:.6}",
+                k,
+                t1,
+                w1,
+                t2,
+                w2
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn check_butterfly_arbitrage_at_k(&self, k: f64, t: f64) -> Result<()> {
+        check_butterfly(k, t, |k| self.total_variance(k, t))
+    }
+}
+
+/// One contract priced on the base and the shocked surface
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScenarioPricing {
+    pub option_type: String,
+    pub strike_price: f64,
+    pub expiration: i64,
+    pub years_to_exp: f64,
+    pub base_price: f64,
+    pub base_iv: f64,
+    /// Underlying price after the spot move
+    pub shocked_spot: f64,
+    pub shocked_price: f64,
+    pub shocked_iv: f64,
+    /// `shocked_price − base_price`
+    pub pnl: f64,
+}
+
+/// Repricing of all contracts under one scenario
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScenarioResult {
+    pub scenario: Scenario,
+    pub contracts: Vec<ScenarioPricing>,
+    /// Sum of the contracts' P&L
+    pub pnl: f64,
+}
+
+/// Reprice `contracts` on `model` under each scenario
+///
+/// Contracts are priced at their own `underlying_price` and `years_to_exp` with
+/// [`price_option`], once on `model` and once on each [`ShockedSurface`]. Any contract
+/// that cannot be priced fails the run.
+///
+/// ```rust
+/// use surface_lib::scenario::{run_scenarios, Scenario, SpotDynamics};
+/// use surface_lib::{FixedParameters, MarketDataRow, SVIModel, SVIParams};
+///
+/// let params = SVIParams::new(0.25, 0.01, 0.1, -0.3, 0.0, 0.2)?;
+/// let model = SVIModel::new(vec![(0.25, params)], 1e-6)?;
+/// let put = MarketDataRow {
+///     option_type: "put".into(),
+///     strike_price: 90.0,
+///     underlying_price: 100.0,
+///     years_to_exp: 0.25,
+///     market_iv: 0.0,
+///     vega: 0.0,
+///     expiration: 0,
+/// };
+/// let crash = Scenario::new("crash")
+///     .with_spot_move(-0.2, SpotDynamics::StickyStrike)
+///     .with_vol_shift(0.1);
+/// let results = run_scenarios(&model, &[put], &FixedParameters::default(), &[crash])?;
+/// assert!(results[0].pnl > 0.0);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn run_scenarios<M: SurfaceModel>(
+    model: &M,
+    contracts: &[MarketDataRow],
+    fixed_params: &FixedParameters,
+    scenarios: &[Scenario],
+) -> Result<Vec<ScenarioResult>> {
+    let describe = |row: &MarketDataRow| {
+        format!(
+            "{} {} expiring {} (t={})",
+            row.option_type, row.strike_price, row.expiration, row.years_to_exp
+        )
+    };
+    let base = contracts
+        .iter()
+        .map(|row| {
+            price_option(
+                &row.option_type,
+                row.strike_price,
+                row.underlying_price,
+                row.years_to_exp,
+                fixed_params,
+                model,
+            )
+            .with_context(|| format!("pricing {} on the base surface", describe(row)))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    scenarios
+        .iter()
+        .map(|scenario| {
+            scenario.validate()?;
+            let contracts = contracts
+                .iter()
+                .zip(&base)
+                .map(|(row, base)| {
+                    let surface = scenario.apply(model, row.underlying_price, fixed_params);
+                    let shocked = price_option(
+                        &row.option_type,
+                        row.strike_price,
+                        surface.shocked_spot(),
+                        row.years_to_exp,
+                        fixed_params,
+                        &surface,
+                    )
+                    .with_context(|| {
+                        format!(
+                            "pricing {} under scenario `{}`",
+                            describe(row),
+                            scenario.name
+                        )
+                    })?;
+                    Ok(ScenarioPricing {
+                        option_type: row.option_type.clone(),
+                        strike_price: row.strike_price,
+                        expiration: row.expiration,
+                        years_to_exp: row.years_to_exp,
+                        base_price: base.price,
+                        base_iv: base.model_iv,
+                        shocked_spot: surface.shocked_spot(),
+                        shocked_price: shocked.price,
+                        shocked_iv: shocked.model_iv,
+                        pnl: shocked.price - base.price,
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Ok(ScenarioResult {
+                scenario: scenario.clone(),
+                pnl: contracts.iter().map(|c| c.pnl).sum(),
+                contracts,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curves::{Dividend, DividendSchedule};
+    use crate::models::svi::svi_model::{SVIModel, SVIParams};
+
+    fn model() -> SVIModel {
+        let short = SVIParams::new(0.1, 0.004, 0.05, -0.4, 0.0, 0.15).unwrap();
+        let long = SVIParams::new(1.0, 0.04, 0.12, -0.4, 0.0, 0.25).unwrap();
+        SVIModel::new(vec![(0.1, short), (1.0, long)], 1e-6).unwrap()
+    }
+
+    fn row(option_type: &str, strike: f64, t: f64) -> MarketDataRow {
+        MarketDataRow {
+            option_type: option_type.into(),
+            strike_price: strike,
+            underlying_price: 100.0,
+            years_to_exp: t,
+            market_iv: 0.0,
+            vega: 0.0,
+            expiration: (t * 365.0 * 86_400.0) as i64,
+        }
+    }
+
+    fn iv(
+        model: &impl SurfaceModel,
+        fixed: &FixedParameters,
+        strike: f64,
+        spot: f64,
+        t: f64,
+    ) -> f64 {
+        price_option("call", strike, spot, t, fixed, model)
+            .unwrap()
+            .model_iv
+    }
+
+    #[test]
+    fn test_vol_shocks() {
+        let model = model();
+        let fixed = FixedParameters::default();
+        let contracts = [
+            row("call", 100.0, 0.5),
+            row("put", 90.0, 0.1),
+            row("call", 110.0, 1.0),
+        ];
+
+        let results = run_scenarios(
+            &model,
+            &contracts,
+            &fixed,
+            &[
+                Scenario::new("flat"),
+                Scenario::new("up").with_vol_shift(0.02),
+                Scenario::new("tilt").with_skew_tilt(-0.1),
+                Scenario::new("twist").with_term_twist(0.01, 0.5),
+            ],
+        )
+        .unwrap();
+
+        assert!(results[0].contracts.iter().all(|c| c.pnl.abs() < 1e-12));
+        assert_eq!(results[0].pnl, 0.0);
+
+        for c in &results[1].contracts {
+            assert!((c.shocked_iv - c.base_iv - 0.02).abs() < 1e-12);
+            assert!(c.pnl > 0.0);
+        }
+        assert!(
+            (results[1].pnl - results[1].contracts.iter().map(|c| c.pnl).sum::<f64>()).abs()
+                < 1e-12
+        );
+
+        // Downside strikes gain vol, upside strikes lose it
+        let tilt = &results[2].contracts;
+        assert!(tilt[1].shocked_iv > tilt[1].base_iv);
+        assert!(tilt[2].shocked_iv < tilt[2].base_iv);
+
+        // The pivot maturity is unchanged, shorter falls, longer rises
+        let twist = &results[3].contracts;
+        assert!((twist[0].shocked_iv - twist[0].base_iv).abs() < 1e-12);
+        assert!((twist[1].shocked_iv - twist[1].base_iv - 0.01 * 0.2f64.ln()).abs() < 1e-12);
+        assert!((twist[2].shocked_iv - twist[2].base_iv - 0.01 * 2f64.ln()).abs() < 1e-12);
+
+        // Shocks below zero vol are floored
+        let floor = Scenario::new("floor").with_vol_shift(-5.0);
+        let surface = floor.apply(&model, 100.0, &fixed);
+        assert!((iv(&surface, &fixed, 100.0, 100.0, 0.5) - MIN_SHOCKED_VOL).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_spot_dynamics() {
+        let model = model();
+        let fixed = FixedParameters::flat(0.03, 0.01);
+        let t = 0.5;
+
+        let scenario = |dynamics| Scenario::new("down").with_spot_move(-0.1, dynamics);
+        let sticky_strike = scenario(SpotDynamics::StickyStrike);
+        let surface = sticky_strike.apply(&model, 100.0, &fixed);
+        for strike in [80.0, 90.0, 100.0] {
+            let base = iv(&model, &fixed, strike, 100.0, t);
+            let shocked = iv(&surface, &fixed, strike, 90.0, t);
+            assert!((shocked - base).abs() < 1e-12, "strike {}", strike);
+        }
+
+        // Without cash dividends the smile moves with the spot under both sticky-moneyness
+        // and sticky-delta
+        for dynamics in [SpotDynamics::StickyMoneyness, SpotDynamics::StickyDelta] {
+            let s = scenario(dynamics);
+            let surface = s.apply(&model, 100.0, &fixed);
+            for strike in [80.0, 90.0, 100.0] {
+                let base = iv(&model, &fixed, strike, 100.0, t);
+                let shocked = iv(&surface, &fixed, strike * 0.9, 90.0, t);
+                assert!(
+                    (shocked - base).abs() < 1e-12,
+                    "{} strike {}",
+                    dynamics,
+                    strike
+                );
+            }
+        }
+
+        // With a cash dividend the forward falls by more than the spot, so the two differ
+        let dividends = DividendSchedule::new(vec![Dividend::cash(0.2, 2.0)]).unwrap();
+        let with_div = FixedParameters::flat(0.03, 0.01).with_dividends(dividends);
+        let moneyness = scenario(SpotDynamics::StickyMoneyness);
+        let delta = scenario(SpotDynamics::StickyDelta);
+        let a = iv(
+            &moneyness.apply(&model, 100.0, &with_div),
+            &with_div,
+            90.0,
+            90.0,
+            t,
+        );
+        let b = iv(
+            &delta.apply(&model, 100.0, &with_div),
+            &with_div,
+            90.0,
+            90.0,
+            t,
+        );
+        assert!((a - b).abs() > 1e-6);
+
+        // A spot drop under sticky-strike makes puts more valuable
+        let results = run_scenarios(
+            &model,
+            &[row("put", 95.0, t), row("call", 105.0, t)],
+            &fixed,
+            std::slice::from_ref(&sticky_strike),
+        )
+        .unwrap();
+        assert!(results[0].contracts[0].pnl > 0.0);
+        assert!(results[0].contracts[1].pnl < 0.0);
+        assert_eq!(results[0].contracts[0].shocked_spot, 90.0);
+
+        assert!(surface.check_butterfly_arbitrage_at_k(0.0, t).is_ok());
+        assert!(surface.check_calendar_arbitrage(0.0, 0.2, 0.8).is_ok());
+        assert!(Scenario::new("bad")
+            .with_spot_move(-1.0, SpotDynamics::StickyStrike)
+            .validate()
+            .is_err());
+        assert_eq!(
+            "sticky_delta".parse::<SpotDynamics>().unwrap(),
+            SpotDynamics::StickyDelta
+        );
+        assert!("sticky_vol".parse::<SpotDynamics>().is_err());
+    }
+}
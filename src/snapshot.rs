@@ -0,0 +1,473 @@
+//! Versioned persistence of calibrated surfaces
+//!
+//! A [`SurfaceRecord`] captures everything needed to ship, reload and reproduce a calibrated
+//! surface: the model type and slices, valuation time, per-slice forwards, the optimization
+//! and calibration settings, fit diagnostics and an [`AuditRecord`] with hashes of the input
+//! data and configuration.
+//!
+//! Records are encoded either as JSON or as a compact binary (`SVIS` magic, little-endian
+//! schema version, then a bincode payload). Schema history:
+//!
+//! | Version | Format | Contents |
+//! |---------|--------|----------|
+//! | 1 | JSON only | Bare array of [`SVIParams`], as produced by serializing `Vec<SVIParams>` |
+//! | 2 | JSON, binary | Full [`SurfaceRecord`] |
+//!
+//! Loading upgrades older versions to the current schema; missing fields become `None`.
+
+use crate::calibration::config::OptimizationConfig;
+use crate::calibration::types::MarketDataRow;
+use crate::model_params::SviModelParams;
+use crate::models::svi::svi_calibrator::SVIParamBounds;
+use crate::models::svi::svi_model::{SVIModel, SVIParams};
+use crate::models::svi::svi_surface::{SliceFailure, SurfaceCalibration};
+use crate::models::traits::SurfaceModel;
+use crate::CalibrationParams;
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+
+/// Schema version written by this crate
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Leading bytes of the binary encoding
+const BINARY_MAGIC: &[u8; 4] = b"SVIS";
+
+/// Calendar-arbitrage tolerance for records that do not come from an [`SVIModel`]
+const DEFAULT_CALENDAR_TOLERANCE: f64 = 1e-6;
+
+/// Surface model family of a record
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelType {
+    Svi,
+}
+
+/// Fit statistics of a calibrated slice
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SliceFit {
+    pub points: usize,
+    pub objective: f64,
+    pub iv_rmse: f64,
+    pub max_iv_error: f64,
+    pub evaluations: usize,
+    pub warm_started: bool,
+    pub truncated: bool,
+}
+
+/// One maturity of a stored surface
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SliceRecord {
+    /// Expiration timestamp, when known
+    pub expiration: Option<i64>,
+    /// Time to expiry (years)
+    pub t: f64,
+    /// Forward (average underlying price of the slice's quotes), when known
+    pub forward: Option<f64>,
+    pub params: SVIParams,
+    /// Calibration diagnostics, when the slice was calibrated by this crate
+    pub diagnostics: Option<SliceFit>,
+}
+
+/// Calibration settings that are not part of [`OptimizationConfig`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CalibrationSettings {
+    pub param_bounds: Option<SVIParamBounds>,
+    pub model_params: Option<SviModelParams>,
+    pub reg_lambda: Option<f64>,
+}
+
+impl From<&CalibrationParams> for CalibrationSettings {
+    fn from(params: &CalibrationParams) -> Self {
+        Self {
+            param_bounds: params.param_bounds.clone(),
+            model_params: params
+                .model_params
+                .as_ref()
+                .and_then(|mp| mp.as_any().downcast_ref::<SviModelParams>().cloned()),
+            reg_lambda: params.reg_lambda,
+        }
+    }
+}
+
+/// Provenance of a stored surface
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    /// Hex SHA-256 of the input quotes (order-independent)
+    pub input_hash: Option<String>,
+    /// Number of input quotes
+    pub input_points: Option<usize>,
+    /// Hex SHA-256 of the optimization config and calibration settings
+    pub config_hash: Option<String>,
+    /// Version of `surface-lib` that produced the record
+    pub crate_version: String,
+    /// CMA-ES seed used for the calibration
+    pub seed: Option<u64>,
+}
+
+/// Versioned, self-describing representation of a calibrated surface
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SurfaceRecord {
+    pub schema_version: u32,
+    pub model_type: ModelType,
+    /// Valuation time as Unix seconds
+    pub valuation_time: Option<i64>,
+    pub calendar_arbitrage_tolerance: f64,
+    /// Slices sorted by maturity
+    pub slices: Vec<SliceRecord>,
+    /// Expiries that could not be calibrated
+    pub failures: Vec<SliceFailure>,
+    pub config: Option<OptimizationConfig>,
+    pub calibration: Option<CalibrationSettings>,
+    pub audit: AuditRecord,
+}
+
+impl SurfaceRecord {
+    /// Record of a bare model, without inputs, configuration or diagnostics
+    pub fn from_model(model: &SVIModel) -> Self {
+        let params = model.parameters().iter().map(|(_, p)| p.clone()).collect();
+        let mut record = Self::from_params(params);
+        record.calendar_arbitrage_tolerance = model.calendar_arbitrage_tolerance();
+        record
+    }
+
+    /// Record of a [`calibrate_svi_surface`](crate::calibrate_svi_surface) result together
+    /// with the inputs and settings that produced it
+    pub fn from_calibration(
+        calibration: &SurfaceCalibration,
+        data: &[MarketDataRow],
+        config: &OptimizationConfig,
+        calib_params: &CalibrationParams,
+    ) -> Self {
+        let mut forwards: BTreeMap<i64, (f64, usize)> = BTreeMap::new();
+        for row in data {
+            let entry = forwards.entry(row.expiration).or_default();
+            entry.0 += row.underlying_price;
+            entry.1 += 1;
+        }
+
+        let mut record = Self::from_model(&calibration.model);
+        record.slices = calibration
+            .slices
+            .iter()
+            .map(|s| SliceRecord {
+                expiration: Some(s.expiration),
+                t: s.t,
+                forward: forwards.get(&s.expiration).map(|(sum, n)| sum / *n as f64),
+                params: s.params.clone(),
+                diagnostics: Some(SliceFit {
+                    points: s.points,
+                    objective: s.objective,
+                    iv_rmse: s.iv_rmse,
+                    max_iv_error: s.max_iv_error,
+                    evaluations: s.evaluations,
+                    warm_started: s.warm_started,
+                    truncated: s.truncated,
+                }),
+            })
+            .collect();
+        record.failures = calibration.failures.clone();
+
+        let settings = CalibrationSettings::from(calib_params);
+        record.audit.input_hash = Some(hash_inputs(data));
+        record.audit.input_points = Some(data.len());
+        record.audit.config_hash = Some(hash_config(config, &settings));
+        record.audit.seed = config.cmaes.seed;
+        record.config = Some(config.clone());
+        record.calibration = Some(settings);
+        record
+    }
+
+    /// Set the valuation time (Unix seconds)
+    pub fn with_valuation_time(mut self, valuation_time: i64) -> Self {
+        self.valuation_time = Some(valuation_time);
+        self
+    }
+
+    /// Rebuild the surface model
+    pub fn model(&self) -> Result<SVIModel> {
+        SVIModel::new(
+            self.slices
+                .iter()
+                .map(|s| (s.t, s.params.clone()))
+                .collect(),
+            self.calendar_arbitrage_tolerance,
+        )
+    }
+
+    /// Whether `data`, `config` and `calib_params` hash to the values in the audit record,
+    /// i.e. whether rerunning the calibration on them reproduces this surface
+    pub fn matches_inputs(
+        &self,
+        data: &[MarketDataRow],
+        config: &OptimizationConfig,
+        calib_params: &CalibrationParams,
+    ) -> bool {
+        let settings = CalibrationSettings::from(calib_params);
+        self.audit.input_hash.as_deref() == Some(hash_inputs(data).as_str())
+            && self.audit.config_hash.as_deref() == Some(hash_config(config, &settings).as_str())
+    }
+
+    /// Encode as compact JSON
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    /// Encode as indented JSON
+    pub fn to_json_pretty(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Decode JSON of any supported schema version
+    pub fn from_json(json: &str) -> Result<Self> {
+        let value: serde_json::Value =
+            serde_json::from_str(json).context("invalid surface JSON")?;
+
+        // Version 1: bare array of SVI parameters
+        if value.is_array() {
+            let params: Vec<SVIParams> =
+                serde_json::from_value(value).context("invalid schema v1 surface")?;
+            return Ok(Self::from_legacy_params(params));
+        }
+
+        let version = value
+            .get("schema_version")
+            .and_then(|v| v.as_u64())
+            .ok_or_else(|| anyhow!("surface JSON has no schema_version"))?;
+        match version {
+            2 => Ok(serde_json::from_value(value).context("invalid schema v2 surface")?),
+            v => Err(anyhow!(
+                "unsupported surface schema version {} (this crate reads up to {})",
+                v,
+                SCHEMA_VERSION
+            )),
+        }
+    }
+
+    /// Encode in the compact binary format
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut bytes = Vec::with_capacity(256);
+        bytes.extend_from_slice(BINARY_MAGIC);
+        bytes.extend_from_slice(&SCHEMA_VERSION.to_le_bytes());
+        bincode::serialize_into(&mut bytes, self)?;
+        Ok(bytes)
+    }
+
+    /// Decode the compact binary format
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < 8 || &bytes[..4] != BINARY_MAGIC {
+            return Err(anyhow!("not a binary surface record"));
+        }
+        let version = u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
+        match version {
+            2 => Ok(bincode::deserialize(&bytes[8..]).context("invalid schema v2 surface")?),
+            v => Err(anyhow!(
+                "unsupported binary surface schema version {} (this crate reads 2 to {})",
+                v,
+                SCHEMA_VERSION
+            )),
+        }
+    }
+
+    fn from_legacy_params(mut params: Vec<SVIParams>) -> Self {
+        params.sort_by(|a, b| a.t.total_cmp(&b.t));
+        let mut record = Self::from_params(params);
+        record.audit.crate_version = "unknown".to_string();
+        record
+    }
+
+    fn from_params(params: Vec<SVIParams>) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            model_type: ModelType::Svi,
+            valuation_time: None,
+            calendar_arbitrage_tolerance: DEFAULT_CALENDAR_TOLERANCE,
+            slices: params
+                .into_iter()
+                .map(|p| SliceRecord {
+                    expiration: None,
+                    t: p.t,
+                    forward: None,
+                    params: p,
+                    diagnostics: None,
+                })
+                .collect(),
+            failures: Vec::new(),
+            config: None,
+            calibration: None,
+            audit: AuditRecord {
+                input_hash: None,
+                input_points: None,
+                config_hash: None,
+                crate_version: env!("CARGO_PKG_VERSION").to_string(),
+                seed: None,
+            },
+        }
+    }
+}
+
+/// Order-independent SHA-256 of market quotes
+pub fn hash_inputs(data: &[MarketDataRow]) -> String {
+    let mut rows: Vec<&MarketDataRow> = data.iter().collect();
+    rows.sort_by(|a, b| {
+        a.expiration
+            .cmp(&b.expiration)
+            .then_with(|| a.option_type.cmp(&b.option_type))
+            .then_with(|| a.strike_price.total_cmp(&b.strike_price))
+            .then_with(|| a.years_to_exp.total_cmp(&b.years_to_exp))
+            .then_with(|| a.market_iv.total_cmp(&b.market_iv))
+    });
+    sha256_hex(&serde_json::to_vec(&rows).expect("market data serializes"))
+}
+
+/// SHA-256 of an optimization config and calibration settings
+pub fn hash_config(config: &OptimizationConfig, settings: &CalibrationSettings) -> String {
+    sha256_hex(&serde_json::to_vec(&(config, settings)).expect("config serializes"))
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_model() -> SVIModel {
+        SVIModel::new(
+            vec![
+                (0.1, SVIParams::new(0.1, 0.01, 0.1, -0.3, 0.0, 0.2).unwrap()),
+                (
+                    0.5,
+                    SVIParams::new(0.5, 0.04, 0.15, -0.4, 0.05, 0.3).unwrap(),
+                ),
+            ],
+            1e-6,
+        )
+        .unwrap()
+    }
+
+    fn sample_rows() -> Vec<MarketDataRow> {
+        (0..4)
+            .map(|i| MarketDataRow {
+                option_type: if i % 2 == 0 { "call" } else { "put" }.to_string(),
+                strike_price: 90.0 + 5.0 * i as f64,
+                underlying_price: 100.0,
+                years_to_exp: 0.1,
+                market_iv: 0.5,
+                vega: 1.0,
+                expiration: 1_700_000_000,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_json_and_binary_roundtrip() {
+        let record = SurfaceRecord::from_model(&sample_model()).with_valuation_time(1_699_000_000);
+
+        let json = record.to_json().unwrap();
+        assert_eq!(SurfaceRecord::from_json(&json).unwrap(), record);
+
+        let bytes = record.to_bytes().unwrap();
+        assert!(bytes.len() < json.len());
+        assert_eq!(SurfaceRecord::from_bytes(&bytes).unwrap(), record);
+
+        let model = record.model().unwrap();
+        assert_eq!(model.parameters(), sample_model().parameters());
+    }
+
+    #[test]
+    fn test_model_serde_validates() {
+        let json = serde_json::to_string(&sample_model()).unwrap();
+        let model: SVIModel = serde_json::from_str(&json).unwrap();
+        assert_eq!(model.parameters().len(), 2);
+
+        // Duplicate maturities are rejected on load, as in SVIModel::new
+        let bad = json.replace("0.5,", "0.1,");
+        assert!(serde_json::from_str::<SVIModel>(&bad).is_err());
+    }
+
+    #[test]
+    fn test_load_schema_v1() {
+        let params: Vec<SVIParams> = sample_model()
+            .parameters()
+            .iter()
+            .rev()
+            .map(|(_, p)| p.clone())
+            .collect();
+        let legacy = serde_json::to_string(&params).unwrap();
+
+        let record = SurfaceRecord::from_json(&legacy).unwrap();
+        assert_eq!(record.schema_version, SCHEMA_VERSION);
+        assert_eq!(record.slices.len(), 2);
+        assert!(record.slices[0].t < record.slices[1].t);
+        assert!(record.audit.input_hash.is_none());
+        record.model().unwrap();
+    }
+
+    #[test]
+    fn test_fixed_params_roundtrip() {
+        let mut record = SurfaceRecord::from_model(&sample_model());
+        record.config = Some(OptimizationConfig::fast());
+
+        // Flat rates stay bare numbers in JSON and an empty dividend schedule is omitted
+        let json = record.to_json().unwrap();
+        assert!(json.contains(r#""fixed_params":{"r":0.02,"q":0.0}"#));
+        assert!(!json.contains("dividends"));
+
+        // Curves and dividends round-trip in both encodings
+        let config = record.config.as_mut().unwrap();
+        config.fixed_params.r = crate::curves::RateCurve::new(
+            vec![(0.25, 0.04), (1.0, 0.045)],
+            crate::curves::CurveInterpolation::PiecewiseFlat,
+        )
+        .unwrap();
+        config.fixed_params.dividends =
+            crate::curves::DividendSchedule::new(vec![crate::curves::Dividend::cash(0.2, 1.5)])
+                .unwrap();
+        assert_eq!(
+            SurfaceRecord::from_bytes(&record.to_bytes().unwrap()).unwrap(),
+            record
+        );
+        let json = record.to_json().unwrap();
+        assert!(json.contains(r#""dividends":[{"ex_time":0.2,"cash":1.5,"proportional":0.0}]"#));
+        assert_eq!(SurfaceRecord::from_json(&json).unwrap(), record);
+    }
+
+    #[test]
+    fn test_rejects_unknown_versions() {
+        let mut record = SurfaceRecord::from_model(&sample_model());
+        record.schema_version = SCHEMA_VERSION + 1;
+        assert!(SurfaceRecord::from_json(&record.to_json().unwrap()).is_err());
+
+        let mut bytes = record.to_bytes().unwrap();
+        bytes[4] = 99;
+        assert!(SurfaceRecord::from_bytes(&bytes).is_err());
+        assert!(SurfaceRecord::from_bytes(b"nope").is_err());
+    }
+
+    #[test]
+    fn test_audit_hashes() {
+        let rows = sample_rows();
+        let mut reversed = rows.clone();
+        reversed.reverse();
+        assert_eq!(hash_inputs(&rows), hash_inputs(&reversed));
+
+        let mut changed = rows.clone();
+        changed[0].market_iv += 1e-9;
+        assert_ne!(hash_inputs(&rows), hash_inputs(&changed));
+
+        let settings = CalibrationSettings::from(&CalibrationParams::default());
+        let config = OptimizationConfig::fast();
+        let mut seeded = config.clone();
+        seeded.cmaes.seed = Some(7);
+        assert_ne!(
+            hash_config(&config, &settings),
+            hash_config(&seeded, &settings)
+        );
+    }
+}
@@ -0,0 +1,131 @@
+#![cfg(feature = "cli")]
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+use surface_lib::snapshot::SurfaceRecord;
+
+const DATA: &str = "tests/data/options_snapshots_20250101.csv";
+
+fn surface(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_surface"))
+        .args(args)
+        .output()
+        .expect("failed to run surface")
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("surface-cli-{}-{}", std::process::id(), name))
+}
+
+/// Calibrate two expiries, then price, check and plot with the stored surface
+#[test]
+fn test_calibrate_price_check_plot() {
+    let surface_json = temp_path("surface.json");
+    let out = surface(&[
+        "--preset",
+        "minimal",
+        "--seed",
+        "7",
+        "calibrate",
+        "--data",
+        DATA,
+        "--expiry",
+        "10JAN25,1737100800",
+        "--output",
+        surface_json.to_str().unwrap(),
+    ]);
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+
+    let record =
+        SurfaceRecord::from_json(&std::fs::read_to_string(&surface_json).unwrap()).unwrap();
+    let expirations: Vec<_> = record.slices.iter().map(|s| s.expiration).collect();
+    assert_eq!(expirations, vec![Some(1736496000), Some(1737100800)]);
+    assert_eq!(record.config.as_ref().unwrap().cmaes.seed, Some(7));
+
+    let contracts = temp_path("contracts.csv");
+    std::fs::write(
+        &contracts,
+        "option_type,strike_price,underlying_price,years_to_exp\n\
+         call,100000,94000,0.03\n\
+         put,90000,94000,0.04\n",
+    )
+    .unwrap();
+    let out = surface(&[
+        "price",
+        "--surface",
+        surface_json.to_str().unwrap(),
+        "--contracts",
+        contracts.to_str().unwrap(),
+    ]);
+    assert!(out.status.success());
+    let prices = String::from_utf8(out.stdout).unwrap();
+    let lines: Vec<&str> = prices.lines().collect();
+    assert_eq!(
+        lines[0],
+        "option_type,strike_price,underlying_price,years_to_exp,model_price,model_iv"
+    );
+    assert_eq!(lines.len(), 3);
+    for line in &lines[1..] {
+        let fields: Vec<f64> = line
+            .split(',')
+            .skip(1)
+            .map(|f| f.parse().unwrap())
+            .collect();
+        assert!(fields[3] > 0.0 && fields[4] > 0.1 && fields[4] < 2.0);
+    }
+
+    let out = surface(&["check", "--surface", surface_json.to_str().unwrap()]);
+    let report: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(report["slices"], 2);
+    assert_eq!(
+        out.status.success(),
+        report["arbitrage_free"].as_bool().unwrap()
+    );
+
+    let svg = temp_path("smile.svg");
+    let out = surface(&[
+        "plot",
+        "--data",
+        DATA,
+        "--surface",
+        surface_json.to_str().unwrap(),
+        "--expiry",
+        "10JAN25",
+        "--output",
+        svg.to_str().unwrap(),
+    ]);
+    assert!(out.status.success());
+    assert!(std::fs::read_to_string(&svg).unwrap().starts_with("<svg"));
+
+    for path in [surface_json, contracts, svg] {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+#[test]
+fn test_metrics_and_errors() {
+    let out = surface(&["metrics", "--data", DATA, "--days", "7,30"]);
+    assert!(out.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert!(report["expiries"].as_array().unwrap().len() >= 5);
+    let tenors: Vec<_> = report["fixed_tenors"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|m| m["tte_days"].as_i64().unwrap())
+        .collect();
+    assert_eq!(tenors, vec![7, 30]);
+
+    let out = surface(&["calibrate", "--data", DATA, "--expiry", "11JAN25"]);
+    assert_eq!(out.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("no quotes for expiry `11JAN25`"));
+    assert!(stderr.contains("10JAN25 (1736496000)"));
+
+    let out = surface(&["calibrate"]);
+    assert!(String::from_utf8_lossy(&out.stderr).contains("pass --data or a --job"));
+}
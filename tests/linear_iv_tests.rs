@@ -669,3 +669,470 @@ fn test_temporal_delta_metrics() {
         );
     }
 }
+
+/// Per-maturity forwards: each expiry's smile is centred on its own futures price, so
+/// a single forward misplaces ATM for the other expiry while per-group forwards recover it.
+/// Fixed-day forwards are interpolated geometrically between pillars.
+#[test]
+fn test_temporal_forward_curve() {
+    let smile = |forward: f64, days: f64, atm: f64| -> Vec<MarketDataRow> {
+        [-0.1, -0.05, 0.0, 0.05, 0.1]
+            .iter()
+            .map(|&x: &f64| {
+                let option_type = if x < 0.0 { "put" } else { "call" };
+                let iv = atm + 2.0 * x * x - 0.2 * x;
+                create_market_data(option_type, forward * x.exp(), forward, days / 365.0, iv)
+            })
+            .collect()
+    };
+    let mut data = smile(100.0, 7.0, 0.30);
+    data.extend(smile(104.0, 21.0, 0.32));
+
+    let temp_config = TemporalConfig {
+        fixed_days: vec![7, 14, 21],
+        ..Default::default()
+    };
+    let strike_config = LinearIvConfig::default();
+
+    let metrics = build_fixed_time_metrics_from_market_data(&data, &temp_config, &strike_config)
+        .expect("per-group forwards should work");
+    assert_eq!(metrics.len(), 3);
+    assert!((metrics[0].atm_iv - 0.30).abs() < 1e-6);
+    assert!((metrics[2].atm_iv - 0.32).abs() < 1e-6);
+    assert!((metrics[0].forward - 100.0).abs() < 1e-6);
+    assert!((metrics[1].forward - (100.0f64 * 104.0).sqrt()).abs() < 1e-6);
+    assert!((metrics[2].forward - 104.0).abs() < 1e-6);
+
+    // A single forward shifts the 21d smile off its ATM point
+    let flat = build_fixed_time_metrics(&data, 100.0, &temp_config, &strike_config).unwrap();
+    assert!((flat[2].atm_iv - 0.32).abs() > 1e-3);
+    assert!(flat.iter().all(|m| m.forward == 100.0));
+
+    // An explicit curve gives the same result as the inferred one
+    let curve = ForwardCurve::new(vec![(21.0 / 365.0, 104.0), (7.0 / 365.0, 100.0)]).unwrap();
+    let explicit =
+        build_fixed_time_metrics_with_forwards(&data, &curve, &temp_config, &strike_config)
+            .unwrap();
+    assert!((explicit[2].atm_iv - metrics[2].atm_iv).abs() < 1e-12);
+
+    // Carry is extended beyond the last pillar
+    let carry = (104.0f64 / 100.0).ln() / (14.0 / 365.0);
+    assert!((curve.forward(35.0 / 365.0) - 104.0 * (carry * 14.0 / 365.0).exp()).abs() < 1e-9);
+    assert_eq!(ForwardCurve::flat(100.0).unwrap().forward(2.0), 100.0);
+    assert!(ForwardCurve::new(vec![(0.1, -1.0)]).is_err());
+    assert!(ForwardCurve::new(vec![(0.1, 100.0), (0.1, 101.0)]).is_err());
+}
+
+/// Solves fixed deltas under every convention on a smile whose variance is linear in x and
+/// positive over the whole search range, so each solved IV maps back to a unique
+/// log-moneyness.
+/// The premium-adjusted call delta peaks below 1; the OTM root must be found and
+/// targets above the peak rejected.
+#[test]
+fn test_delta_conventions() {
+    let tte = 0.25;
+    let variance = |x: f64| 0.04 * (1.0 + 0.25 * x);
+    let sorted_points: Vec<(f64, f64)> = (-10..=10)
+        .map(|i| {
+            let x = i as f64 / 10.0;
+            (x, variance(x))
+        })
+        .collect();
+    let x_of_iv = |iv: f64| (iv * iv * tte / 0.04 - 1.0) / 0.25;
+    let q = 0.05;
+
+    for convention in DeltaConvention::ALL {
+        for target in [-0.25, -0.1, 0.1, 0.25] {
+            let iv = compute_fixed_delta_iv_with_config(
+                target,
+                &sorted_points,
+                tte,
+                1e-10,
+                true,
+                q,
+                convention,
+            )
+            .unwrap_or_else(|e| panic!("{} {}: {}", convention, target, e));
+            let x = x_of_iv(iv);
+            let delta = bs_delta_with_convention(x, iv, tte, target > 0.0, q, convention);
+            assert!(
+                (delta - target).abs() < 1e-6,
+                "{} {}: delta {} at x={}",
+                convention,
+                target,
+                delta,
+                x
+            );
+            // OTM side of the smile
+            assert_eq!(x > 0.0, target > 0.0, "{} {}: x={}", convention, target, x);
+        }
+    }
+
+    // Spot is the default convention and matches bs_delta
+    let spot = compute_fixed_delta_iv_with_config(
+        0.25,
+        &sorted_points,
+        tte,
+        1e-10,
+        true,
+        q,
+        DeltaConvention::Spot,
+    )
+    .unwrap();
+    assert_eq!(
+        LinearIvConfig::default().delta_convention,
+        DeltaConvention::Spot
+    );
+    assert_eq!(
+        bs_delta(0.1, 0.4, tte, true, q),
+        bs_delta_with_convention(0.1, 0.4, tte, true, q, DeltaConvention::Spot)
+    );
+    // Forward deltas are larger than spot deltas with q > 0, so the 25δ call sits further OTM
+    let forward = compute_fixed_delta_iv_with_config(
+        0.25,
+        &sorted_points,
+        tte,
+        1e-10,
+        true,
+        q,
+        DeltaConvention::Forward,
+    )
+    .unwrap();
+    assert!(x_of_iv(forward) > x_of_iv(spot));
+
+    // Spot and forward deltas are inverted directly over the whole search range
+    for (convention, iv) in [
+        (DeltaConvention::Spot, spot),
+        (DeltaConvention::Forward, forward),
+    ] {
+        let objective = |x: f64| {
+            let sigma = (variance(x) / tte).sqrt();
+            bs_delta_with_convention(x, sigma, tte, true, q, convention) - 0.25
+        };
+        let x = roots::find_root_brent(-2.0, 2.0, &objective, &mut 1e-10).unwrap();
+        assert_eq!(iv, (variance(x) / tte).sqrt(), "{}", convention);
+    }
+
+    // The premium-adjusted call delta peaks around 0.68 on this smile
+    let err = compute_fixed_delta_iv_with_config(
+        0.9,
+        &sorted_points,
+        tte,
+        1e-10,
+        true,
+        0.0,
+        DeltaConvention::PremiumAdjustedForward,
+    )
+    .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("maximum premium_adjusted_forward call delta"));
+
+    assert_eq!(
+        "premium_adjusted_spot".parse::<DeltaConvention>().unwrap(),
+        DeltaConvention::PremiumAdjustedSpot
+    );
+    assert!("pa".parse::<DeltaConvention>().is_err());
+}
+
+/// Message and key-value fields of a captured log record
+type LogRecord = (String, Vec<(String, String)>);
+
+thread_local! {
+    /// Warnings captured on this thread while inside [`capture_warnings`]
+    static CAPTURED: std::cell::RefCell<Option<Vec<LogRecord>>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+/// Routes warnings emitted through the `log` facade, with their key-values, to the
+/// capture scope open on the emitting thread; records outside a scope are dropped.
+struct ScopedLogger;
+
+impl log::Log for ScopedLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::Level::Warn
+    }
+
+    fn log(&self, record: &log::Record) {
+        struct Collect(Vec<(String, String)>);
+        impl<'kvs> log::kv::VisitSource<'kvs> for Collect {
+            fn visit_pair(
+                &mut self,
+                key: log::kv::Key<'kvs>,
+                value: log::kv::Value<'kvs>,
+            ) -> Result<(), log::kv::Error> {
+                self.0.push((key.to_string(), value.to_string()));
+                Ok(())
+            }
+        }
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        CAPTURED.with(|captured| {
+            if let Some(records) = captured.borrow_mut().as_mut() {
+                let mut kvs = Collect(Vec::new());
+                record.key_values().visit(&mut kvs).unwrap();
+                records.push((record.args().to_string(), kvs.0));
+            }
+        });
+    }
+
+    fn flush(&self) {}
+}
+
+/// Run `f` and return the warnings it logged on the current thread.
+///
+/// The scoped logger is installed once per test binary and only records inside a capture
+/// scope, so tests running in parallel never see each other's warnings.
+fn capture_warnings(f: impl FnOnce()) -> Vec<LogRecord> {
+    static INSTALL: std::sync::Once = std::sync::Once::new();
+    INSTALL.call_once(|| {
+        if log::set_logger(&ScopedLogger).is_ok() {
+            log::set_max_level(log::LevelFilter::Warn);
+        }
+    });
+    CAPTURED.with(|captured| *captured.borrow_mut() = Some(Vec::new()));
+    f();
+    CAPTURED.with(|captured| captured.borrow_mut().take().unwrap_or_default())
+}
+
+/// Tests that coverage warnings go through the `log` facade with structured fields.
+#[test]
+fn test_coverage_warning_is_logged() {
+    let call_only_points = vec![
+        create_market_data("call", 100.0, 100.0, 0.25, 0.20),
+        create_market_data("call", 105.0, 100.0, 0.25, 0.22),
+        create_market_data("call", 110.0, 100.0, 0.25, 0.25),
+    ];
+    let records = capture_warnings(|| {
+        let _ = build_linear_iv(&call_only_points, 100.0, 0.25, &LinearIvConfig::default());
+    });
+
+    let (_, fields) = records
+        .iter()
+        .find(|(msg, _)| msg.contains("no put options"))
+        .expect("missing coverage warning");
+    assert!(fields.contains(&("tte".to_string(), "0.25".to_string())));
+    assert!(fields.contains(&("points".to_string(), "3".to_string())));
+}
+
+/// Business-time clock: variance accrues on trading days only, so a term structure with a
+/// constant business-time variance rate interpolates exactly across the weekend.
+#[test]
+fn test_temporal_variance_clock() {
+    use surface_lib::{Tenor, VarianceClock};
+
+    let thursday = 1_736_409_600; // 2025-01-09 08:00 UTC
+    let clock = VarianceClock::weekends(0.0);
+    let rate = 0.25; // variance per year of business time
+    let expiries = [1, 4, 8].map(|days| thursday + days * 86_400); // Friday, Monday, Friday
+    let data: Vec<MarketDataRow> = expiries
+        .iter()
+        .flat_map(|&expiration| {
+            let tte = (expiration - thursday) as f64 / (365.0 * 86_400.0);
+            let iv = (rate * clock.variance_time(thursday, expiration) / tte).sqrt();
+            [90.0, 95.0, 100.0, 105.0, 110.0].map(|strike| MarketDataRow {
+                expiration,
+                ..create_market_data("call", strike, 100.0, tte, iv)
+            })
+        })
+        .collect();
+
+    let config = TemporalConfig {
+        fixed_days: vec![2, 6],
+        tenors: vec![Tenor::Hours(12)],
+        clock: clock.clone(),
+        allow_short_extrapolate: true,
+        ..Default::default()
+    };
+    let metrics = build_fixed_time_metrics(&data, 100.0, &config, &LinearIvConfig::default())
+        .expect("business-time interpolation should work");
+
+    let tenors: Vec<Tenor> = metrics.iter().map(|m| m.tenor).collect();
+    assert_eq!(tenors, [Tenor::Hours(12), Tenor::Days(2), Tenor::Days(6)]);
+    assert_eq!(metrics[0].tte_days, 0);
+    // Maturities are grouped at 8-decimal TTEs, a relative error of ~1e-6 at one day
+    for m in &metrics {
+        let expiration = m.tenor.expiration(thursday);
+        let expected = (rate * clock.variance_time(thursday, expiration) / m.tte_years).sqrt();
+        assert!(
+            (m.atm_iv - expected).abs() < 1e-5,
+            "{}: {} vs {}",
+            m.tenor,
+            m.atm_iv,
+            expected
+        );
+    }
+
+    // In calendar time Saturday is a third of the way through Friday-Monday variance, while
+    // in business time all of Friday's variance has already accrued
+    let calendar = TemporalConfig {
+        clock: VarianceClock::calendar(),
+        ..config
+    };
+    let metrics = build_fixed_time_metrics(&data, 100.0, &calendar, &LinearIvConfig::default())
+        .expect("calendar interpolation should work");
+    let saturday = metrics.iter().find(|m| m.tenor == Tenor::Days(2)).unwrap();
+    let business =
+        (rate * clock.variance_time(thursday, thursday + 2 * 86_400) / saturday.tte_years).sqrt();
+    assert!(saturday.atm_iv < business - 0.03);
+}
+
+#[test]
+fn test_temporal_event_variance() {
+    use surface_lib::{implied_event_moves, ForwardCurve, Tenor, VolEvent};
+
+    let valuation = 1_736_409_600; // 2025-01-09 08:00 UTC
+    let base_variance = 0.25; // diffusive variance per year
+    let event_variance = 0.0025; // a 5% move
+    let event_time = valuation + 10 * 86_400;
+    let total_variance = |expiration: i64, tte: f64| {
+        base_variance * tte
+            + if expiration > event_time {
+                event_variance
+            } else {
+                0.0
+            }
+    };
+    let data: Vec<MarketDataRow> = [3, 7, 14, 21]
+        .iter()
+        .flat_map(|&days| {
+            let expiration = valuation + days * 86_400;
+            let tte = days as f64 / 365.0;
+            let iv = (total_variance(expiration, tte) / tte).sqrt();
+            [90.0, 95.0, 100.0, 105.0, 110.0].map(|strike| MarketDataRow {
+                expiration,
+                ..create_market_data("call", strike, 100.0, tte, iv)
+            })
+        })
+        .collect();
+
+    let known = TemporalConfig {
+        fixed_days: vec![5, 9, 12, 18],
+        valuation_time: Some(valuation),
+        events: vec![VolEvent::new("earnings", Tenor::Days(10)).with_move(0.05)],
+        ..Default::default()
+    };
+    let metrics = build_fixed_time_metrics(&data, 100.0, &known, &LinearIvConfig::default())
+        .expect("event interpolation should work");
+    assert_eq!(metrics.len(), 4);
+    for m in &metrics {
+        let expiration = m.tenor.expiration(valuation);
+        let expected = (total_variance(expiration, m.tte_years) / m.tte_years).sqrt();
+        assert!(
+            (m.atm_iv - expected).abs() < 1e-5,
+            "{}: {} vs {}",
+            m.tenor,
+            m.atm_iv,
+            expected
+        );
+        let included = if expiration > event_time {
+            event_variance
+        } else {
+            0.0
+        };
+        assert!((m.event_variance - included).abs() < 1e-12);
+    }
+
+    // Interpolating through the event spreads its variance over the days before it
+    let plain = TemporalConfig {
+        events: Vec::new(),
+        ..known.clone()
+    };
+    let metrics = build_fixed_time_metrics(&data, 100.0, &plain, &LinearIvConfig::default())
+        .expect("plain interpolation should work");
+    let day9 = metrics.iter().find(|m| m.tenor == Tenor::Days(9)).unwrap();
+    assert!(day9.atm_iv > 0.52);
+
+    // Estimated from the maturities either side of the event
+    let estimated = TemporalConfig {
+        events: vec![VolEvent::new("earnings", Tenor::At(event_time))],
+        ..known
+    };
+    let forwards = ForwardCurve::flat(100.0).unwrap();
+    let moves = implied_event_moves(&data, &forwards, &estimated, &LinearIvConfig::default())
+        .expect("event variance should be estimated");
+    assert_eq!(moves.len(), 1);
+    assert_eq!(moves[0].time, event_time);
+    assert!(moves[0].estimated);
+    assert!(
+        (moves[0].implied_move - 0.05).abs() < 1e-3,
+        "{:?}",
+        moves[0]
+    );
+
+    // The event variance cannot exceed the variance of the expiries after it
+    let too_large = TemporalConfig {
+        events: vec![VolEvent::new("earnings", Tenor::Days(10)).with_variance(0.1)],
+        ..estimated
+    };
+    let err =
+        build_fixed_time_metrics(&data, 100.0, &too_large, &LinearIvConfig::default()).unwrap_err();
+    assert!(err.to_string().contains("earnings"), "{err}");
+}
+
+#[test]
+fn test_temporal_constant_maturity_smile() {
+    // The same skewed smile in forward moneyness at 7 and 30 days
+    let smile = |x: f64| 0.5 - 0.2 * x;
+    let data: Vec<MarketDataRow> = [7.0, 30.0]
+        .iter()
+        .flat_map(|&days| {
+            (-6..=6).map(move |i| {
+                let x = i as f64 * 0.05;
+                let option_type = if x < 0.0 { "put" } else { "call" };
+                create_market_data(option_type, 100.0 * x.exp(), 100.0, days / 365.0, smile(x))
+            })
+        })
+        .collect();
+
+    for convention in DeltaConvention::ALL {
+        let config = TemporalConfig {
+            fixed_days: vec![14],
+            moneyness_grid: vec![-0.2, 0.0, 0.1],
+            ..Default::default()
+        };
+        let strike_config = LinearIvConfig {
+            delta_convention: convention,
+            ..Default::default()
+        };
+        let metrics = build_fixed_time_metrics(&data, 100.0, &config, &strike_config)
+            .expect("constant-maturity smile should build");
+        let m = &metrics[0];
+        let tte = m.tte_years;
+
+        assert_eq!(m.moneyness_smile.len(), 3);
+        for point in &m.moneyness_smile {
+            assert!(
+                (point.iv - smile(point.log_moneyness)).abs() < 1e-6,
+                "{point:?}"
+            );
+            assert!((point.strike - 100.0 * point.log_moneyness.exp()).abs() < 1e-9);
+            let is_call = point.log_moneyness >= 0.0;
+            assert_eq!(point.delta > 0.0, is_call);
+        }
+        assert!((m.moneyness_smile[1].iv - m.atm_iv).abs() < 1e-12);
+
+        // Every configured delta, with the strike that has that delta at the smile's IV
+        let deltas: Vec<f64> = m.delta_smile.iter().map(|p| p.delta).collect();
+        assert_eq!(deltas, strike_config.deltas, "{convention}");
+        for point in &m.delta_smile {
+            let x = point.log_moneyness;
+            let delta =
+                bs_delta_with_convention(x, point.iv, tte, point.delta > 0.0, 0.0, convention);
+            assert!(
+                (delta - point.delta).abs() < 1e-9,
+                "{convention}: {point:?}"
+            );
+            assert_eq!(
+                x > 0.0,
+                point.delta > 0.0,
+                "{convention}: OTM strike expected"
+            );
+            assert!(
+                (point.iv - smile(x)).abs() < 0.01,
+                "{convention}: {point:?}"
+            );
+        }
+    }
+}
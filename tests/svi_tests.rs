@@ -1,5 +1,6 @@
 mod test_utils;
 
+use surface_lib::models::traits::SurfaceModel;
 use surface_lib::{calibrate_svi, CalibrationParams};
 use test_utils::{
     create_test_config, create_verbose_test_config, filter_by_expiration,
@@ -393,7 +394,7 @@ fn test_svi_pricing() {
     };
 
     // Use fixed parameters from the calibration
-    let fixed_params = surface_lib::calibration::types::FixedParameters { r: 0.02, q: 0.0 };
+    let fixed_params = surface_lib::calibration::types::FixedParameters::flat(0.02, 0.0);
 
     // Price options using the calibrated parameters
     let pricing_results = surface_lib::price_with_svi(svi_params, jan10_data, fixed_params);
@@ -442,3 +443,624 @@ fn test_svi_pricing() {
         pricing_results.len()
     );
 }
+
+#[test]
+fn test_optimizer_backends() {
+    use surface_lib::{evaluate_svi, FixedParameters, OptimizerKind, SVIParams};
+
+    let data = load_test_data("tests/data/options_snapshots_20250101.csv").unwrap();
+    let slice = filter_by_expiration(data, "10JAN25");
+    assert!(!slice.is_empty());
+    let t = slice[0].years_to_exp;
+
+    for kind in [
+        OptimizerKind::CmaesLbfgsb,
+        OptimizerKind::NelderMead,
+        OptimizerKind::DifferentialEvolution,
+        OptimizerKind::LevenbergMarquardt,
+    ] {
+        let mut config = create_test_config();
+        config.cmaes.verbosity = 0;
+        config.optimizer = kind;
+
+        let (obj, params, bounds) =
+            calibrate_svi(slice.clone(), config, CalibrationParams::default(), None)
+                .expect("calibration failed");
+        println!("{:?}: objective={:.6e} params={:?}", kind, obj, params);
+
+        assert!(
+            obj.is_finite() && obj < 1.0e12,
+            "{:?} found no valid fit",
+            kind
+        );
+        let ranges = [bounds.a, bounds.b, bounds.rho, bounds.m, bounds.sigma];
+        for (p, (lo, hi)) in params.iter().zip(ranges) {
+            assert!(*p >= lo && *p <= hi, "{:?} left the parameter box", kind);
+        }
+
+        // Reported objective must match an independent evaluation
+        let svi = SVIParams::new(t, params[0], params[1], params[2], params[3], params[4])
+            .expect("invalid SVI parameters");
+        let check = evaluate_svi(
+            slice.clone(),
+            svi,
+            CalibrationParams::default(),
+            &FixedParameters::default(),
+        )
+        .unwrap();
+        assert!((check - obj).abs() < 1e-9 * obj.max(1.0));
+    }
+}
+
+#[test]
+fn test_warm_start_gradient_refinement() {
+    let data = load_test_data("tests/data/options_snapshots_20250101.csv").unwrap();
+    let slice = filter_by_expiration(data, "10JAN25");
+
+    let mut config = create_test_config();
+    config.cmaes.verbosity = 0;
+    let (obj1, p1, _) = calibrate_svi(
+        slice.clone(),
+        config.clone(),
+        CalibrationParams::default(),
+        None,
+    )
+    .expect("cold calibration failed");
+
+    // Warm start straight into the (analytic-gradient) L-BFGS-B refinement
+    config.cmaes.mini_cmaes_on_refinement = false;
+    let perturbed: Vec<f64> = p1.iter().map(|p| p * 1.02).collect();
+    let start = std::time::Instant::now();
+    let (obj2, _p2, _) = calibrate_svi(
+        slice,
+        config,
+        CalibrationParams {
+            reg_lambda: Some(0.0),
+            ..CalibrationParams::default()
+        },
+        Some(perturbed),
+    )
+    .expect("warm calibration failed");
+    println!(
+        "cold objective={:.6e}, warm objective={:.6e} in {:?}",
+        obj1,
+        obj2,
+        start.elapsed()
+    );
+
+    assert!(obj2.is_finite());
+    assert!(obj2 <= obj1 * 1.05, "warm start lost accuracy");
+}
+
+#[test]
+fn test_calibration_deadline_and_cancellation() {
+    use std::time::{Duration, Instant};
+    use surface_lib::{
+        calibrate_svi_detailed, default_configs, CalibrationControl, CancellationToken,
+    };
+
+    let data = load_test_data("tests/data/options_snapshots_20250101.csv").unwrap();
+    let slice = filter_by_expiration(data, "10JAN25");
+
+    // Without a deadline the run completes normally
+    let mut config = create_test_config();
+    config.cmaes.verbosity = 0;
+    let full = calibrate_svi_detailed(
+        slice.clone(),
+        config.clone(),
+        CalibrationParams::default(),
+        None,
+    )
+    .unwrap();
+    assert!(!full.truncated);
+
+    // A deadline or token that never fires leaves the run unchanged
+    for control in [
+        CalibrationControl::new().with_timeout(Duration::from_secs(600)),
+        CalibrationControl::new().with_cancellation(CancellationToken::new()),
+    ] {
+        let armed = calibrate_svi_detailed(
+            slice.clone(),
+            config.clone(),
+            CalibrationParams {
+                control,
+                ..CalibrationParams::default()
+            },
+            None,
+        )
+        .unwrap();
+        assert!(!armed.truncated);
+        assert_eq!(armed.params, full.params);
+        assert_eq!(armed.objective, full.objective);
+    }
+
+    // A tight deadline on the heaviest preset returns promptly with a usable best-so-far
+    let mut research = default_configs::research();
+    research.cmaes.verbosity = 0;
+    let start = Instant::now();
+    let outcome = calibrate_svi_detailed(
+        slice.clone(),
+        research,
+        CalibrationParams {
+            control: CalibrationControl::new().with_timeout(Duration::from_millis(50)),
+            ..CalibrationParams::default()
+        },
+        None,
+    )
+    .unwrap();
+    let elapsed = start.elapsed();
+    println!(
+        "deadline run: objective={:.6e} truncated={} in {:?}",
+        outcome.objective, outcome.truncated, elapsed
+    );
+    assert!(outcome.truncated);
+    assert!(
+        elapsed < Duration::from_secs(2),
+        "deadline was not honoured"
+    );
+    assert_eq!(outcome.params.len(), 5);
+    assert!(outcome.objective.is_finite());
+
+    // A token cancelled up front stops after the first evaluation
+    let token = CancellationToken::new();
+    token.cancel();
+    let mut config = create_test_config();
+    config.cmaes.verbosity = 0;
+    let cancelled = calibrate_svi_detailed(
+        slice,
+        config,
+        CalibrationParams {
+            control: CalibrationControl::new().with_cancellation(token),
+            ..CalibrationParams::default()
+        },
+        None,
+    )
+    .unwrap();
+    assert!(cancelled.truncated);
+    assert_eq!(cancelled.params.len(), 5);
+    assert!(cancelled.objective.is_finite());
+}
+
+#[test]
+fn test_calibration_observer_events() {
+    use std::sync::{Arc, Mutex};
+    use surface_lib::{
+        calibrate_svi_detailed, CalibrationControl, CalibrationEvent, CalibrationStage,
+    };
+
+    let data = load_test_data("tests/data/options_snapshots_20250101.csv").unwrap();
+    let slice = filter_by_expiration(data, "10JAN25");
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let sink = events.clone();
+    let mut config = create_test_config();
+    config.cmaes.verbosity = 0;
+    config.adaptive_bounds.enabled = true;
+    let outcome = calibrate_svi_detailed(
+        slice,
+        config,
+        CalibrationParams {
+            control: CalibrationControl::new().with_observer(Arc::new(
+                move |event: &CalibrationEvent| sink.lock().unwrap().push(event.clone()),
+            )),
+            ..CalibrationParams::default()
+        },
+        None,
+    )
+    .unwrap();
+
+    let events = events.lock().unwrap();
+    let started: Vec<CalibrationStage> = events
+        .iter()
+        .filter_map(|e| match e {
+            CalibrationEvent::StageStarted { stage } => Some(*stage),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        started[..3],
+        [
+            CalibrationStage::AdaptiveIteration { iteration: 1 },
+            CalibrationStage::Cmaes,
+            CalibrationStage::Lbfgsb,
+        ]
+    );
+    let finished = events
+        .iter()
+        .filter(|e| matches!(e, CalibrationEvent::StageFinished { .. }))
+        .count();
+    assert_eq!(finished, started.len(), "every stage must be closed");
+
+    // Improvements within a pass are strictly decreasing
+    let mut last = f64::INFINITY;
+    for event in events.iter() {
+        match event {
+            CalibrationEvent::ObjectiveImproved { objective, .. } => {
+                assert!(*objective < last);
+                last = *objective;
+            }
+            CalibrationEvent::StageStarted {
+                stage: CalibrationStage::AdaptiveIteration { .. },
+            } => last = f64::INFINITY,
+            _ => {}
+        }
+    }
+
+    // Exactly one summary, last, matching the returned outcome
+    match events.last() {
+        Some(CalibrationEvent::Finished {
+            objective,
+            params,
+            evaluations,
+            truncated,
+            ..
+        }) => {
+            assert_eq!(*objective, outcome.objective);
+            assert_eq!(*params, outcome.params);
+            assert_eq!(*evaluations, outcome.evaluations);
+            assert!(!truncated);
+        }
+        other => panic!("expected final summary, got {:?}", other),
+    }
+    assert_eq!(
+        events
+            .iter()
+            .filter(|e| matches!(e, CalibrationEvent::Finished { .. }))
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn test_svi_surface_calibration() {
+    use surface_lib::{calibrate_svi_surface, MarketDataRow, SurfaceCalibrationOptions};
+
+    let mut data = load_test_data("tests/data/options_snapshots_20250101.csv").unwrap();
+    let expiries = get_available_expirations(&data);
+    assert!(expiries.len() >= 2);
+
+    // An expiry with too few quotes must fail on its own
+    let bad = MarketDataRow {
+        expiration: 4_102_444_800,
+        years_to_exp: 10.0,
+        ..data[0].clone()
+    };
+    data.push(bad.clone());
+    data.push(MarketDataRow {
+        strike_price: bad.strike_price * 1.1,
+        ..bad
+    });
+
+    let mut config = create_test_config();
+    config.cmaes.verbosity = 0;
+    config.cmaes.seed = Some(7);
+    let calibrate = |threads| {
+        calibrate_svi_surface(
+            data.clone(),
+            config.clone(),
+            CalibrationParams::default(),
+            SurfaceCalibrationOptions {
+                max_threads: Some(threads),
+                ..SurfaceCalibrationOptions::default()
+            },
+        )
+        .expect("surface calibration failed")
+    };
+    let surface = calibrate(2);
+
+    // Warm-start chains don't depend on the thread count
+    let serial = calibrate(1);
+    assert_eq!(serial.slices.len(), surface.slices.len());
+    for (a, b) in serial.slices.iter().zip(&surface.slices) {
+        assert_eq!(a.params, b.params);
+        assert_eq!(a.warm_started, b.warm_started);
+    }
+
+    for s in &surface.slices {
+        println!(
+            "exp={} t={:.4} n={} obj={:.3e} iv_rmse={:.4} warm={}",
+            s.expiration, s.t, s.points, s.objective, s.iv_rmse, s.warm_started
+        );
+    }
+
+    assert_eq!(surface.failures.len(), 1);
+    assert_eq!(surface.failures[0].expiration, 4_102_444_800);
+    assert_eq!(
+        surface.slices.len() + surface.failures.len(),
+        expiries.len() + 1
+    );
+    assert!(surface.slices.windows(2).all(|w| w[0].t < w[1].t));
+    assert!(surface.slices.iter().any(|s| s.warm_started));
+    for s in &surface.slices {
+        assert!(s.iv_rmse.is_finite() && s.max_iv_error >= s.iv_rmse);
+        assert!(s.objective < 1e-2, "poor fit at t={}", s.t);
+    }
+    assert_eq!(
+        surface.model.parameters().len(),
+        surface.slices.len(),
+        "model must contain every calibrated slice"
+    );
+}
+
+#[test]
+fn test_recalibration_engine() {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+    use surface_lib::{RecalibrationConfig, RecalibrationEngine, RecalibrationReason};
+
+    let data = load_test_data("tests/data/options_snapshots_20250101.csv").unwrap();
+    let jan10 = filter_by_expiration(data.clone(), "10JAN25");
+    let jan17 = filter_by_expiration(data, "17JAN25");
+    assert!(!jan10.is_empty() && !jan17.is_empty());
+    let (exp10, exp17) = (jan10[0].expiration, jan17[0].expiration);
+
+    // Recalibrate on input changes only, not on the fit error drift from time decay
+    let mut config = RecalibrationConfig {
+        max_threads: Some(2),
+        objective_drift: 1e6,
+        ..RecalibrationConfig::default()
+    };
+    config.optimization.cmaes.verbosity = 0;
+    let mut engine = RecalibrationEngine::new(config, CalibrationParams::default());
+
+    let published = Arc::new(AtomicU64::new(0));
+    let sink = published.clone();
+    engine.subscribe(Box::new(move |snapshot| {
+        sink.store(snapshot.version, Ordering::SeqCst)
+    }));
+
+    // First step calibrates every expiry
+    engine.ingest(jan10.clone());
+    engine.ingest(jan17.clone());
+    let first = engine.step().unwrap().expect("initial snapshot");
+    assert_eq!(first.version, 1);
+    assert_eq!(first.slices.len(), 2);
+    assert!(first
+        .slices
+        .iter()
+        .all(|s| s.reason == RecalibrationReason::Initial));
+    assert_eq!(published.load(Ordering::SeqCst), 1);
+
+    // Re-sending identical quotes does not trigger any work
+    engine.ingest(jan10.clone());
+    assert!(engine.step().unwrap().is_none());
+    assert!(engine.drift().iter().all(|d| d.iv_change == 0.0));
+
+    // Shifting one expiry's IVs recalibrates only that slice, warm-started
+    let old10 = engine.solution(exp10).unwrap().clone();
+    let old17 = engine.solution(exp17).unwrap().clone();
+    engine.ingest(jan10.iter().map(|r| surface_lib::MarketDataRow {
+        market_iv: r.market_iv + 0.03,
+        ..r.clone()
+    }));
+    let second = engine
+        .step()
+        .unwrap()
+        .expect("recalibration after IV shift");
+    assert_eq!(second.version, 2);
+    assert_eq!(second.recalibrated, vec![exp10]);
+    assert_eq!(published.load(Ordering::SeqCst), 2);
+
+    let status10 = second
+        .slices
+        .iter()
+        .find(|s| s.expiration == exp10)
+        .unwrap();
+    assert_eq!(status10.reason, RecalibrationReason::InputChange);
+    assert_eq!(status10.calibrated_version, 2);
+    let atm_var = |p: &surface_lib::SVIParams| {
+        surface_lib::models::svi::svi_model::SVISlice::new(p.clone()).total_variance_at_k(0.0)
+    };
+    assert!(
+        atm_var(engine.solution(exp10).unwrap()) > atm_var(&old10),
+        "ATM variance must rise"
+    );
+    assert_eq!(engine.solution(exp17).unwrap().a, old17.a);
+
+    // Forced recalibration
+    engine.invalidate(Some(exp17));
+    let third = engine.step().unwrap().unwrap();
+    assert_eq!(third.recalibrated, vec![exp17]);
+    assert_eq!(engine.snapshot().unwrap().version, 3);
+
+    // A day later, the slice that isn't recalibrated is published at its new maturity
+    let day = 1.0 / 365.0;
+    engine.ingest(jan17.iter().map(|r| surface_lib::MarketDataRow {
+        years_to_exp: r.years_to_exp - day,
+        ..r.clone()
+    }));
+    engine.invalidate(Some(exp10));
+    let fourth = engine.step().unwrap().unwrap();
+    assert_eq!(fourth.recalibrated, vec![exp10]);
+    let t17 = fourth
+        .slices
+        .iter()
+        .find(|s| s.expiration == exp17)
+        .unwrap()
+        .params
+        .t;
+    assert!((t17 - (engine.solution(exp17).unwrap().t - day)).abs() < 1e-12);
+
+    // A surface that fails to build leaves every solution untouched
+    let clash = exp10 + 1;
+    engine.ingest(jan10.iter().map(|r| surface_lib::MarketDataRow {
+        expiration: clash,
+        ..r.clone()
+    }));
+    assert!(engine.step().is_err());
+    assert!(engine.solution(clash).is_none());
+    assert_eq!(engine.snapshot().unwrap().version, 4);
+    engine.remove_expiry(clash);
+    assert!(engine.step().unwrap().is_none());
+}
+
+#[test]
+fn test_surface_record_roundtrip() {
+    use surface_lib::snapshot::SurfaceRecord;
+    use surface_lib::{calibrate_svi_surface, SurfaceCalibrationOptions};
+
+    let data = load_test_data("tests/data/options_snapshots_20250101.csv").unwrap();
+    let mut chain = filter_by_expiration(data.clone(), "10JAN25");
+    chain.extend(filter_by_expiration(data, "17JAN25"));
+
+    let mut config = create_test_config();
+    config.cmaes.verbosity = 0;
+    config.cmaes.seed = Some(42);
+    let surface = calibrate_svi_surface(
+        chain.clone(),
+        config.clone(),
+        CalibrationParams::default(),
+        SurfaceCalibrationOptions::default(),
+    )
+    .unwrap();
+
+    let record =
+        SurfaceRecord::from_calibration(&surface, &chain, &config, &CalibrationParams::default())
+            .with_valuation_time(1_735_689_600);
+    assert_eq!(record.slices.len(), 2);
+    assert!(record.slices.iter().all(|s| s.forward.is_some()));
+    assert_eq!(record.audit.seed, Some(42));
+    assert!(record.matches_inputs(&chain, &config, &CalibrationParams::default()));
+    assert!(!record.matches_inputs(&chain[1..], &config, &CalibrationParams::default()));
+
+    let json = record.to_json_pretty().unwrap();
+    let from_json = SurfaceRecord::from_json(&json).unwrap();
+    let from_bytes = SurfaceRecord::from_bytes(&record.to_bytes().unwrap()).unwrap();
+    assert_eq!(from_json, record);
+    assert_eq!(from_bytes, record);
+
+    // The reloaded surface prices identically
+    let model = from_bytes.model().unwrap();
+    for t in [0.03, 0.04] {
+        assert_eq!(
+            model.total_variance(0.05, t).unwrap(),
+            surface.model.total_variance(0.05, t).unwrap()
+        );
+    }
+}
+
+#[test]
+fn test_discrete_dividends() {
+    use surface_lib::models::svi::svi_model::SVISlice;
+    use surface_lib::{
+        evaluate_svi, price_with_svi, Dividend, DividendSchedule, FixedParameters, MarketDataRow,
+        SVIParams,
+    };
+
+    let schedule = DividendSchedule::new(vec![
+        Dividend::proportional(0.75, 0.01),
+        Dividend::cash(0.25, 2.0),
+    ])
+    .unwrap();
+    let fixed = FixedParameters::flat(0.03, 0.0).with_dividends(schedule);
+    let carry = |t: f64| (0.03 * t).exp();
+
+    // Escrowed forward: carry to each ex-date, drop the dividend, carry on
+    assert!((fixed.forward(100.0, 0.1) - 100.0 * carry(0.1)).abs() < 1e-12);
+    let after_cash = 100.0 * carry(0.25) - 2.0;
+    assert!((fixed.forward(100.0, 0.5) - after_cash * carry(0.25)).abs() < 1e-12);
+    let after_both = after_cash * carry(0.5) * 0.99;
+    assert!((fixed.forward(100.0, 1.0) - after_both * carry(0.25)).abs() < 1e-12);
+    assert_eq!(
+        FixedParameters::flat(0.03, 0.0).dividend_adjusted_spot(100.0, 1.0),
+        100.0
+    );
+
+    // Quotes generated in dividend-adjusted moneyness for a slice straddling the cash dividend
+    let t = 0.5;
+    let truth = SVIParams::new(t, 0.01, 0.05, -0.3, 0.02, 0.15).unwrap();
+    let smile = SVISlice::new(truth.clone());
+    let data: Vec<MarketDataRow> = (0..41)
+        .map(|i| {
+            let strike = 70.0 + 1.5 * i as f64;
+            let k = fixed.log_moneyness(strike, 100.0, t);
+            MarketDataRow {
+                option_type: if strike < 100.0 { "put" } else { "call" }.to_string(),
+                strike_price: strike,
+                underlying_price: 100.0,
+                years_to_exp: t,
+                market_iv: smile.implied_vol(k),
+                vega: 1.0,
+                expiration: 1_750_000_000,
+            }
+        })
+        .collect();
+
+    let mut config = create_test_config();
+    config.cmaes.verbosity = 0;
+    config.fixed_params = fixed.clone();
+    let (obj, p, _) = calibrate_svi(data.clone(), config, CalibrationParams::default(), None)
+        .expect("calibration with dividends failed");
+    let fitted = SVIParams::new(t, p[0], p[1], p[2], p[3], p[4]).unwrap();
+    // Evaluation scores the quotes at the same moneyness calibration fits them at
+    let check = evaluate_svi(
+        data.clone(),
+        fitted.clone(),
+        CalibrationParams::default(),
+        &fixed,
+    )
+    .unwrap();
+    assert!((check - obj).abs() < 1e-9 * obj.max(1.0));
+    for result in price_with_svi(fitted.clone(), data.clone(), fixed.clone()) {
+        // The default ATM boost leaves the far wings loosely fit
+        if (result.strike_price / 100.0).ln().abs() > 0.1 {
+            continue;
+        }
+        let row = data
+            .iter()
+            .find(|r| r.strike_price == result.strike_price)
+            .unwrap();
+        assert!(
+            (result.model_iv - row.market_iv).abs() < 2e-3,
+            "strike {}: model {} vs market {}",
+            row.strike_price,
+            result.model_iv,
+            row.market_iv
+        );
+    }
+
+    // Dividends beyond the carried spot leave no moneyness to fit at
+    let oversized = FixedParameters::flat(0.03, 0.0)
+        .with_dividends(DividendSchedule::new(vec![Dividend::cash(0.25, 150.0)]).unwrap());
+    let err = oversized.adjust_for_dividends(data.clone()).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Non-positive dividend-adjusted spot"),
+        "{}",
+        err
+    );
+    let mut config = create_test_config();
+    config.fixed_params = oversized.clone();
+    assert!(calibrate_svi(data.clone(), config, CalibrationParams::default(), None).is_err());
+    assert!(evaluate_svi(
+        data.clone(),
+        fitted.clone(),
+        CalibrationParams::default(),
+        &oversized
+    )
+    .is_err());
+
+    // Put-call parity holds against the escrowed forward
+    let call = MarketDataRow {
+        option_type: "call".to_string(),
+        ..data[10].clone()
+    };
+    let put = MarketDataRow {
+        option_type: "put".to_string(),
+        ..data[10].clone()
+    };
+    let prices = price_with_svi(truth, vec![call, put], fixed.clone());
+    let strike = data[10].strike_price;
+    let parity = fixed.r.discount_factor(t) * (fixed.forward(100.0, t) - strike);
+    let (c, p) = if prices[0].option_type == "call" {
+        (prices[0].model_price, prices[1].model_price)
+    } else {
+        (prices[1].model_price, prices[0].model_price)
+    };
+    assert!(
+        (c - p - parity).abs() < 1e-6,
+        "C - P = {} vs {}",
+        c - p,
+        parity
+    );
+}
@@ -1,81 +1,20 @@
-use serde::Deserialize;
 use std::collections::HashMap;
 use std::sync::{Mutex, OnceLock};
+use surface_lib::io::SnapshotReader;
 use surface_lib::{MarketDataRow, OptimizationConfig};
 
-/// CSV row structure matching the actual data format
-#[derive(Debug, Deserialize)]
-#[allow(dead_code)] // Some fields are only used via serde deserialization
-struct CsvRow {
-    #[serde(rename = "symbol")]
-    symbol: String,
-    #[serde(rename = "snapshot_ts")]
-    snapshot_ts: String,
-    #[serde(rename = "option_type")]
-    option_type: String,
-    #[serde(rename = "strike_price")]
-    strike_price: f64,
-    #[serde(rename = "underlying_price")]
-    underlying_price: f64,
-    #[serde(rename = "years_to_exp")]
-    years_to_exp: f64,
-    #[serde(rename = "mark_iv")]
-    mark_iv: f64,
-    #[serde(rename = "open_interest", default)]
-    open_interest: f64,
-    #[serde(rename = "vega", default)]
-    vega: f64,
-    #[serde(rename = "expiration_ts", default)]
-    expiration_ts: Option<i64>,
-}
-
-/// Extract expiration string from symbol (e.g., "BTC-10JAN25-100000-C" -> "10JAN25")
-fn extract_expiration_from_symbol(symbol: &str) -> Option<String> {
-    let parts: Vec<&str> = symbol.split('-').collect();
-    if parts.len() >= 3 {
-        Some(parts[1].to_string())
-    } else {
-        None
-    }
-}
-
 /// Global mapping to store timestamp -> expiration string mappings learned from CSV data
 static EXPIRATION_MAPPING: OnceLock<Mutex<HashMap<i64, String>>> = OnceLock::new();
 
 /// Load market data from CSV file and convert to surface-lib format
 pub fn load_test_data(file_path: &str) -> Result<Vec<MarketDataRow>, Box<dyn std::error::Error>> {
-    let mut reader = csv::Reader::from_path(file_path)?;
-    let mut data = Vec::new();
-    let mut timestamp_to_expiration: HashMap<i64, String> = HashMap::new();
+    let quotes = SnapshotReader::new().read_path(file_path)?;
 
-    for result in reader.deserialize() {
-        let row: CsvRow = result?;
-
-        // Extract expiration string from symbol and map it to timestamp
-        if let Some(expiration_str) = extract_expiration_from_symbol(&row.symbol) {
-            if let Some(timestamp) = row.expiration_ts {
-                timestamp_to_expiration.insert(timestamp, expiration_str);
-            }
-        }
-
-        // Convert CSV row to surface-lib MarketDataRow format
-        let market_data = MarketDataRow {
-            option_type: row.option_type,
-            strike_price: row.strike_price,
-            underlying_price: row.underlying_price,
-            years_to_exp: row.years_to_exp,
-            market_iv: row.mark_iv / 100.0, // Convert from percentage to decimal
-            vega: if row.vega > 0.0 { row.vega } else { 1.0 }, // Default vega if missing
-            expiration: row.expiration_ts.unwrap_or_else(|| {
-                // Fallback: derive from years_to_exp
-                let seconds_per_year = 365.25 * 24.0 * 3600.0;
-                let base_timestamp = 1735689600; // 2025-01-01 00:00:00 UTC
-                base_timestamp + (row.years_to_exp * seconds_per_year) as i64
-            }),
-        };
-
-        data.push(market_data);
-    }
+    // Map expiration timestamps to the expiry codes of their symbols
+    let timestamp_to_expiration: HashMap<i64, String> = quotes
+        .iter()
+        .filter_map(|q| Some((q.row.expiration, q.parsed_symbol()?.expiry.code())))
+        .collect();
 
     // Store the mapping globally for use by timestamp_to_expiration_string
     let mapping = EXPIRATION_MAPPING.get_or_init(|| Mutex::new(HashMap::new()));
@@ -84,7 +23,7 @@ pub fn load_test_data(file_path: &str) -> Result<Vec<MarketDataRow>, Box<dyn std
         *guard = timestamp_to_expiration;
     }
 
-    Ok(data)
+    Ok(quotes.into_iter().map(|q| q.row).collect())
 }
 
 /// Filter data by expiration timestamp (approximate matching)